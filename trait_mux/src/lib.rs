@@ -1 +1,39 @@
-pub use trait_mux_macros::trait_mux;
+//! Proc macro library for generating enums that can multiplex different trait objects.
+//!
+//! The code generated by [`trait_mux!`] is free of warnings wherever it's invoked, which the
+//! examples below check by denying them, like a doctest of a crate using the macro can.
+//!
+//! ```
+//! use std::fmt::{Debug, Display};
+//!
+//! use trait_mux::trait_mux;
+//!
+//! trait_mux!(MyMux { Debug, Display });
+//!
+//! let value = 42;
+//! match into_my_mux!(value) {
+//!     MyMux::MyMuxDebugDisplay(v) => assert_eq!(v.to_string(), "42"),
+//!     _ => unreachable!(),
+//! }
+//! ```
+//!
+//! The invocation can also be placed in a module, with the macros scoped to it:
+//!
+//! ```
+//! mod mux {
+//!     use std::fmt::{Debug, Display};
+//!
+//!     use trait_mux::trait_mux;
+//!
+//!     trait_mux!(#[trait_mux(scoped_macros)] MyMux { Debug, Display });
+//!
+//!     pub fn describe(value: &i32) -> &'static str {
+//!         into_my_mux!(value).variant_name()
+//!     }
+//! }
+//!
+//! assert_eq!(mux::describe(&1), "MyMuxDebugDisplay");
+//! ```
+#![doc(test(attr(deny(warnings))))]
+
+pub use trait_mux_macros::{trait_mux, trait_mux_traits};
@@ -0,0 +1,21 @@
+use std::fmt::Debug;
+
+mod mux {
+    use std::fmt::Debug;
+    use trait_mux::trait_mux;
+
+    trait_mux!(#[trait_mux(access_trait)] Dispatcher{Debug});
+}
+
+struct Other;
+
+// The module defining the supertrait that seals the accessor trait is private to `mux`.
+impl mux::__dispatcher_sealed::Sealed for Other {}
+
+impl mux::DispatcherAccess for Other {
+    fn try_as_debug(&self) -> Option<&dyn Debug> {
+        None
+    }
+}
+
+fn main() {}
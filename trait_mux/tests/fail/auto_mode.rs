@@ -0,0 +1,9 @@
+use trait_mux::trait_mux;
+
+trait_mux!(#[trait_mux(mode = auto, bitset)] Detector{std::fmt::Debug, std::fmt::Display});
+
+trait_mux!(#[trait_mux(mode = auto, owned)] Single{std::fmt::Debug});
+
+trait_mux!(#[trait_mux(mode = auto)] Writer{mut std::io::Write});
+
+fn main() {}
@@ -0,0 +1,7 @@
+use trait_mux::trait_mux;
+
+trait_mux!(#[trait_mux(bitset)] Bitset{std::fmt::Debug + Send});
+
+trait_mux!(Duplicate{std::fmt::Debug + Send, std::fmt::Debug + Sync});
+
+fn main() {}
@@ -0,0 +1,5 @@
+use trait_mux::trait_mux;
+
+trait_mux!(#[trait_mux(bitset)] Dispatcher{mut std::io::Write, std::fmt::Debug});
+
+fn main() {}
@@ -0,0 +1,9 @@
+use trait_mux::trait_mux;
+
+trait_mux!(
+    #[trait_mux(bitset)]
+    #[cfg_attr(all(), derive(Clone, Copy))]
+    Detector{std::fmt::Debug, std::fmt::Display}
+);
+
+fn main() {}
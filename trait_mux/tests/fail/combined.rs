@@ -0,0 +1,11 @@
+use trait_mux::trait_mux;
+
+pub trait Named<'a> {
+    fn name(&self) -> &'a str;
+}
+
+trait_mux!(Single{(std::fmt::Debug), std::fmt::Display});
+trait_mux!(Bound{(std::fmt::Debug + Named<'_>), std::fmt::Display});
+trait_mux!(Held types{i32} traits{(std::fmt::Debug + std::fmt::Display)});
+
+fn main() {}
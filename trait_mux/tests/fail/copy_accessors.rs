@@ -0,0 +1,7 @@
+use trait_mux::trait_mux;
+
+trait_mux!(#[trait_mux(copy_accessors)] Dispatcher{std::fmt::Debug});
+trait_mux!(#[trait_mux(copy_accessors)] Values types{u8, i32} traits{std::fmt::Debug});
+trait_mux!(#[trait_mux(copy_accessors)] Text types{String} traits{std::fmt::Debug});
+
+fn main() {}
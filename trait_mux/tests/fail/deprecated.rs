@@ -0,0 +1,17 @@
+#![deny(deprecated)]
+
+use std::fmt::{Debug, Display};
+use trait_mux::trait_mux;
+
+trait_mux!(Dispatcher {
+    Debug,
+    #[deprecated(note = "use `Debug` instead")]
+    Display
+});
+
+fn main() {
+    let number = 5;
+    let dispatcher = into_dispatcher!(number);
+    dispatcher.try_as_debug();
+    dispatcher.try_as_display();
+}
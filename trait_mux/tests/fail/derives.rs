@@ -0,0 +1,13 @@
+use trait_mux::trait_mux;
+
+trait_mux!(#[derive(Clone, PartialEq, Hash)] Compared{std::fmt::Debug, std::fmt::Display});
+
+trait_mux!(#[derive(PartialOrd)] Ordered{std::fmt::Debug, std::fmt::Display});
+
+trait_mux!(#[derive(Clone, Copy)] Mutable{mut std::io::Write, std::fmt::Debug});
+
+trait_mux!(#[derive(Clone)] #[trait_mux(bitset)] Flags{std::fmt::Debug, std::fmt::Display});
+
+trait_mux!(#[derive(Clone, Debug)] #[trait_mux(error_impl)] Failure{std::error::Error, std::fmt::Display});
+
+fn main() {}
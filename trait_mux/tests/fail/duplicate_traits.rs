@@ -0,0 +1,7 @@
+use trait_mux::trait_mux;
+
+trait_mux!(#[trait_mux(no_duplicates)] Strict{Debug, std::fmt::Display, Debug, std::fmt::Display});
+
+trait_mux!(Conflicting{std::fmt::Write, std::io::Write});
+
+fn main() {}
@@ -0,0 +1,9 @@
+use trait_mux::trait_mux;
+
+pub trait Named<'a> {
+    fn name(&self) -> &'a str;
+}
+
+trait_mux!(#[trait_mux(owned, static)] Dispatcher{Named<'_>});
+
+fn main() {}
@@ -0,0 +1,6 @@
+use trait_mux::trait_mux;
+
+// The variant implementing `elf` would be named `Self`, a keyword.
+trait_mux!(S{elf});
+
+fn main() {}
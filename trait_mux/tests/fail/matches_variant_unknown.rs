@@ -0,0 +1,14 @@
+use std::fmt::{Debug, Display};
+use trait_mux::trait_mux;
+
+trait_mux!(#[trait_mux(implies(Display => Debug))] Dispatcher{Debug, Display});
+
+fn main() {
+    let number = 5;
+    let dispatcher = into_dispatcher!(number);
+
+    // Every value implementing `Display` implements `Debug`, so there's no `Display` variant.
+    matches_variant_dispatcher!(dispatcher, [Display]);
+    matches_variant_dispatcher!(dispatcher, [Debug, Debug]);
+    matches_variant_dispatcher!(dispatcher, [Debug, Binary]);
+}
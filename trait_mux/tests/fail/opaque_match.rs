@@ -0,0 +1,26 @@
+use std::fmt::{Debug, Display};
+use trait_mux::trait_mux;
+
+mod scoped {
+    use super::*;
+
+    trait_mux!(#[trait_mux(opaque, scoped_macros)] Dispatcher{Debug, Display});
+
+    pub fn none() -> Dispatcher<'static> {
+        Dispatcher::none()
+    }
+}
+
+fn main() {
+    let dispatcher = scoped::none();
+
+    match dispatcher {
+        scoped::Dispatcher::DispatcherNone => {}
+        _ => {}
+    }
+
+    match dispatcher.0 {
+        scoped::DispatcherInner::DispatcherNone => {}
+        _ => {}
+    }
+}
@@ -0,0 +1,6 @@
+use trait_mux::trait_mux;
+
+// The owned enum could be matched on, defeating the private enum.
+trait_mux!(#[trait_mux(opaque, owned)] Dispatcher{std::fmt::Debug, std::fmt::Display});
+
+fn main() {}
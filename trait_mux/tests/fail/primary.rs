@@ -0,0 +1,9 @@
+use trait_mux::trait_mux;
+
+trait_mux!(#[trait_mux(primary = std::fmt::Display)] Dispatcher{std::fmt::Debug});
+
+trait_mux!(#[trait_mux(primary = Clone)] Renamed{std::fmt::Debug, std::io::Write as IoWrite});
+
+trait_mux!(#[trait_mux(implies(std::fmt::LowerHex => Debug))] Implied{std::fmt::Debug});
+
+fn main() {}
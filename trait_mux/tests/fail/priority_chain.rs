@@ -0,0 +1,11 @@
+use trait_mux::trait_mux;
+
+trait_mux!(#[trait_mux(mode = priority_chain, bitset)] Detector{std::fmt::Debug, std::fmt::Display});
+
+trait_mux!(#[trait_mux(mode = priority_chain, universal = Debug)] Dispatcher{std::fmt::Display});
+
+trait_mux!(#[trait_mux(mode = priority_chain, implies(Display => Debug))] Chain{std::fmt::Display, std::fmt::Debug});
+
+trait_mux!(#[trait_mux(mode = first)] Unknown{std::fmt::Debug});
+
+fn main() {}
@@ -0,0 +1,13 @@
+use std::fmt::{Binary, Debug, Display, LowerExp, LowerHex, Octal, Pointer, UpperHex};
+use trait_mux::trait_mux;
+
+// 8 traits make 256 variants, more than the 128 non-negative `i8` discriminants.
+trait_mux!(#[trait_mux(repr = i8)] Formatter{Debug, Display, Binary, Octal, LowerHex, UpperHex, LowerExp, Pointer});
+
+pub trait All: Debug + Display + Binary + Octal + LowerHex + UpperHex + LowerExp + Pointer {}
+
+// The 256 combinations without `All` and the one with every trait make 257 variants, one more
+// than the `u8` discriminants.
+trait_mux!(#[trait_mux(repr = u8, aggregate(All => Debug, Display, Binary, Octal, LowerHex, UpperHex, LowerExp, Pointer))] Everything{All, Debug, Display, Binary, Octal, LowerHex, UpperHex, LowerExp, Pointer});
+
+fn main() {}
@@ -0,0 +1,18 @@
+use std::fmt::Debug;
+use std::io::Write;
+use trait_mux::trait_mux;
+
+trait_mux!(#[trait_mux(shared_view)] Shared{Debug, std::fmt::Display});
+
+trait_mux!(#[trait_mux(bitset, shared_view)] Bitset{Debug, std::fmt::Display});
+
+trait_mux!(#[trait_mux(shared_view)] Writer{mut Write, Debug});
+
+fn main() {
+    // The enum can't be mutated while the view borrows it.
+    let mut buffer = Vec::new();
+    let mut writer = into_writer!(buffer);
+    let shared = writer.as_shared();
+    writer.as_write_mut().write_all(b"data").unwrap();
+    assert!(shared.try_as_debug().is_some());
+}
@@ -0,0 +1,11 @@
+use std::fmt::{Debug, Display};
+use trait_mux::trait_mux;
+
+trait_mux!(#[trait_mux(strict)] Dispatcher{Debug, Display});
+
+struct Nothing;
+
+fn main() {
+    let nothing = Nothing;
+    let _ = into_dispatcher_strict!(nothing);
+}
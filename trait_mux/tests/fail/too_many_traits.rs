@@ -0,0 +1,17 @@
+use trait_mux::trait_mux;
+
+macro_rules! traits {
+    ($($name:ident),*) => {
+        $(trait $name {})*
+    };
+}
+
+traits!(T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12, T13, T14, T15, T16, T17, T18, T19, T20, T21, T22, T23, T24, T25, T26, T27, T28, T29, T30, T31, T32);
+
+// A priority chain has a variant per trait, but the traits of a variant are a `u32` mask.
+trait_mux!(#[trait_mux(mode = priority_chain)] Chain{T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12, T13, T14, T15, T16, T17, T18, T19, T20, T21, T22, T23, T24, T25, T26, T27, T28, T29, T30, T31, T32});
+
+// The traits of the bitset struct are a `u32` mask as well.
+trait_mux!(#[trait_mux(bitset)] Bits{T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12, T13, T14, T15, T16, T17, T18, T19, T20, T21, T22, T23, T24, T25, T26, T27, T28, T29, T30, T31, T32});
+
+fn main() {}
@@ -0,0 +1,5 @@
+use trait_mux::trait_mux;
+
+trait_mux!(#[trait_mux(trait_ref = refs::MyTraitRef)] Dispatcher{std::fmt::Debug});
+
+fn main() {}
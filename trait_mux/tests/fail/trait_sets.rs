@@ -0,0 +1,5 @@
+use trait_mux::trait_mux_traits;
+
+trait_mux_traits!(fmt_traits = {std::fmt::Debug, 5});
+
+fn main() {}
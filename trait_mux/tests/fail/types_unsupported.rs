@@ -0,0 +1,5 @@
+use trait_mux::trait_mux;
+
+trait_mux!(#[trait_mux(owned)] Dispatcher types{Vec<u8>, [u8; 4]} traits{mut std::io::Write});
+
+fn main() {}
@@ -0,0 +1,5 @@
+use trait_mux::trait_mux;
+
+trait_mux!(#[trait_mux(unknown)] Dispatcher{Clone});
+
+fn main() {}
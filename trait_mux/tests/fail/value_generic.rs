@@ -0,0 +1,5 @@
+use trait_mux::trait_mux;
+
+trait_mux!(#[trait_mux(value_generic = S)] Dispatcher{std::fmt::Debug});
+
+fn main() {}
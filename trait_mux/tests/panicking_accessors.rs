@@ -0,0 +1,42 @@
+use std::cell::Cell;
+use std::fmt::{Debug, Display};
+use std::panic::{self, AssertUnwindSafe, Location};
+use trait_mux::trait_mux;
+
+trait_mux!(Dispatcher { Debug, Display });
+
+thread_local! {
+    static PANIC_LINE: Cell<Option<u32>> = const { Cell::new(None) };
+}
+
+#[test]
+fn panicking_accessor() {
+    let number = 5;
+    let dispatcher = into_dispatcher!(number);
+    assert_eq!(dispatcher.as_display().to_string(), "5");
+}
+
+#[test]
+#[should_panic(expected = "`DispatcherDebug` does not implement `Display`")]
+fn panicking_accessor_missing_trait() {
+    let unit = ();
+    into_dispatcher!(unit).as_display();
+}
+
+#[test]
+fn panicking_accessor_location() {
+    let previous_hook = panic::take_hook();
+    panic::set_hook(Box::new(|info| {
+        PANIC_LINE.with(|line| line.set(info.location().map(Location::line)));
+    }));
+
+    let unit = ();
+    let dispatcher = into_dispatcher!(unit);
+    let expected_line = line!() + 1;
+    let result = panic::catch_unwind(AssertUnwindSafe(|| dispatcher.as_display().to_string()));
+
+    panic::set_hook(previous_hook);
+
+    assert!(result.is_err());
+    assert_eq!(PANIC_LINE.with(Cell::get), Some(expected_line));
+}
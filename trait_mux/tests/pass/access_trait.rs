@@ -0,0 +1,39 @@
+use std::fmt::{Debug, Display};
+use std::io::Write;
+use trait_mux::trait_mux;
+
+trait_mux!(#[trait_mux(access_trait)] Dispatcher{Debug, Display, mut Write});
+trait_mux!(#[trait_mux(access_trait, opaque)] Hidden{Debug, Display});
+trait_mux!(#[trait_mux(access_trait, bitset)] Detector{Debug, Display});
+trait_mux!(#[trait_mux(access_trait)] Values types{String, i32} traits{Debug});
+
+// Generic code is bounded on the accessor trait, without naming the enum.
+fn debug_string(access: &impl DispatcherAccess) -> Option<String> {
+    access.try_as_debug().map(|v| format!("{:?}", v))
+}
+
+fn write_hello(access: &mut impl DispatcherAccess) -> bool {
+    access
+        .try_as_write_mut()
+        .map(|w| w.write_all(b"hello").unwrap())
+        .is_some()
+}
+
+fn display_string<A: HiddenAccess>(access: A) -> Option<String> {
+    access.try_as_display().map(ToString::to_string)
+}
+
+fn main() {
+    let mut number = 5;
+    assert_eq!(debug_string(&into_dispatcher!(number)).unwrap(), "5");
+    assert!(!write_hello(&mut into_dispatcher!(number)));
+
+    let mut buffer = Vec::new();
+    assert!(write_hello(&mut into_dispatcher!(buffer)));
+    assert_eq!(buffer, b"hello");
+
+    let text = "text";
+    assert_eq!(display_string(into_hidden!(text)).unwrap(), "text");
+    assert!(DetectorAccess::try_as_display(&into_detector!(text)).is_some());
+    assert!(ValuesAccess::try_as_debug(&Values::from(5)).is_some());
+}
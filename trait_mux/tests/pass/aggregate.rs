@@ -0,0 +1,25 @@
+use std::fmt::{Debug, Display};
+use trait_mux::trait_mux;
+
+pub trait DebugDisplay: Debug + Display {}
+
+impl<T: Debug + Display + ?Sized> DebugDisplay for T {}
+
+trait_mux!(#[trait_mux(aggregate(DebugDisplay => Debug, Display))] Dispatcher{Debug, Display, DebugDisplay});
+
+fn main() {
+    // The combinations with `DebugDisplay` but without `Debug` or `Display` aren't generated.
+    assert_eq!(Dispatcher::VARIANT_COUNT, 5);
+
+    let number = 5;
+    let dispatcher = into_dispatcher!(number);
+    assert!(matches!(dispatcher, Dispatcher::DispatcherDebugDebugDisplayDisplay(_)));
+    assert_eq!(format!("{:?}", dispatcher.try_as_debug().unwrap()), "5");
+    assert_eq!(dispatcher.try_as_display().unwrap().to_string(), "5");
+    assert_eq!(dispatcher.try_as_debug_display().unwrap().to_string(), "5");
+
+    let unit = ();
+    let dispatcher = into_dispatcher!(unit);
+    assert!(dispatcher.try_as_debug().is_some());
+    assert!(dispatcher.try_as_debug_display().is_none());
+}
@@ -0,0 +1,37 @@
+use trait_mux::trait_mux;
+
+mod very {
+    pub mod long {
+        pub mod path {
+            pub trait Trait {
+                fn value(&self) -> u32;
+            }
+
+            impl Trait for u32 {
+                fn value(&self) -> u32 {
+                    *self
+                }
+            }
+        }
+    }
+}
+
+mod other {
+    pub trait Trait {}
+
+    impl Trait for &str {}
+}
+
+trait_mux!(Dispatcher{very::long::path::Trait as Valued, other::Trait as Other});
+
+fn main() {
+    let number = 5u32;
+    let dispatcher = into_dispatcher!(number);
+    assert_eq!(dispatcher.variant_name(), "DispatcherValued");
+    assert_eq!(dispatcher.try_as_valued().unwrap().value(), 5);
+    assert!(dispatcher.try_as_other().is_none());
+
+    let text = "text";
+    let dispatcher = into_dispatcher!(text);
+    assert!(matches!(dispatcher, Dispatcher::DispatcherOther(_)));
+}
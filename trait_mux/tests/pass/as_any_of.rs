@@ -0,0 +1,29 @@
+use std::fmt::{Debug, Display};
+use trait_mux::trait_mux;
+
+trait_mux!(Dispatcher{Debug, Display, mut std::fmt::Write});
+
+fn main() {
+    let mut number = 5;
+    let dispatcher = into_dispatcher!(number);
+
+    // The first implemented trait in the given order is returned, not the alphabetical first.
+    match dispatcher.as_any_of(&["Display", "Debug"]) {
+        Some(DispatcherTraitRef::Display(v)) => assert_eq!(v.to_string(), "5"),
+        _ => panic!("expected the Display view"),
+    }
+    match dispatcher.as_any_of(&["Debug", "Display"]) {
+        Some(DispatcherTraitRef::Debug(v)) => assert_eq!(format!("{:?}", v), "5"),
+        _ => panic!("expected the Debug view"),
+    }
+
+    // Unknown and unimplemented traits are skipped, as are mutable ones.
+    assert!(matches!(
+        dispatcher.as_any_of(&["Unknown", "Write", "Debug"]),
+        Some(DispatcherTraitRef::Debug(_))
+    ));
+    assert!(dispatcher.as_any_of(&[]).is_none());
+
+    let mut unit = ();
+    assert!(into_dispatcher!(unit).as_any_of(&["Display"]).is_none());
+}
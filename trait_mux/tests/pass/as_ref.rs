@@ -0,0 +1,25 @@
+use std::borrow::Borrow;
+use std::fmt::{Debug, Display};
+use trait_mux::trait_mux;
+
+// Every variant implements `Debug`, so `AsRef<dyn Debug>` can't fail. Values not implementing
+// `Debug` don't convert into the enum.
+trait_mux!(#[trait_mux(universal = Debug)] Dispatcher{Debug, Display});
+
+fn show<'t>(value: &impl AsRef<dyn Debug + 't>) -> String {
+    format!("{:?}", value.as_ref())
+}
+
+fn main() {
+    let number = 5;
+    let bytes = vec![1u8, 2];
+
+    let dispatcher = into_dispatcher!(number);
+    assert_eq!(show(&dispatcher), "5");
+
+    let dispatcher = into_dispatcher!(bytes);
+    assert_eq!(show(&dispatcher), "[1, 2]");
+
+    let debug: &dyn Debug = dispatcher.borrow();
+    assert_eq!(format!("{:?}", debug), "[1, 2]");
+}
@@ -0,0 +1,19 @@
+use std::fmt::Debug;
+use trait_mux::trait_mux;
+
+trait_mux!(#[trait_mux(owned)] Dispatcher{mut Iterator<Item = u8>, Debug, AsRef<str>});
+
+fn main() {
+    let mut bytes = vec![1u8, 2].into_iter();
+    let mut dispatcher = into_dispatcher!(bytes);
+    assert_eq!(dispatcher.variant_name(), "DispatcherDebugIterator");
+    assert_eq!(dispatcher.try_as_iterator_mut().unwrap().next(), Some(1));
+
+    let mut text = String::from("text");
+    let dispatcher = into_dispatcher!(text);
+    assert_eq!(dispatcher.try_as_as_ref().unwrap().as_ref(), "text");
+    assert!(dispatcher.try_as_iterator().is_none());
+
+    let mut owned = into_dispatcher_owned!(vec![3u8].into_iter());
+    assert_eq!(owned.as_iterator_mut().next(), Some(3));
+}
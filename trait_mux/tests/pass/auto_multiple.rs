@@ -0,0 +1,22 @@
+use std::fmt::{Debug, Display};
+use trait_mux::trait_mux;
+
+trait_mux!(#[trait_mux(mode = auto)] Multiple{Debug, Display});
+
+struct Nothing;
+
+fn main() {
+    // Several traits get the enum, with the same accessors as the single trait struct.
+    let number = 5;
+    let multiple = into_multiple!(number);
+    assert!(matches!(multiple, Multiple::MultipleDebugDisplay(_)));
+    assert_eq!(multiple.try_as_display().unwrap().to_string(), "5");
+    assert_eq!(format!("{:?}", multiple.try_as_debug().unwrap()), "5");
+
+    let vec = vec![1];
+    let multiple = into_multiple!(vec);
+    assert!(multiple.try_as_debug().is_some());
+    assert!(multiple.try_as_display().is_none());
+
+    assert!(matches!(into_multiple!(Nothing), Multiple::MultipleNone));
+}
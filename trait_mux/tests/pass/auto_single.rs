@@ -0,0 +1,24 @@
+use std::fmt::Display;
+use trait_mux::trait_mux;
+
+trait_mux!(#[trait_mux(mode = auto, dispatch_eq)] Single{Display});
+
+struct Nothing;
+
+fn main() {
+    // A single trait is detected at compile time, so only the reference is stored.
+    let number = 5;
+    let single = into_single!(number);
+    assert_eq!(std::mem::size_of_val(&single), std::mem::size_of::<&i32>());
+    assert_eq!(single.try_as_display().unwrap().to_string(), "5");
+    assert_eq!(single.traits_mask(), 1);
+    assert!(!single.is_none());
+
+    let nothing = into_single!(Nothing);
+    assert!(nothing.try_as_display().is_none());
+    assert_eq!(nothing.traits_mask(), 0);
+    assert!(nothing.is_none());
+
+    assert!(dispatch_eq_single!(number, 7));
+    assert!(!dispatch_eq_single!(number, Nothing));
+}
@@ -0,0 +1,43 @@
+use std::fmt::{Debug, Display};
+use std::rc::Rc;
+use trait_mux::trait_mux;
+
+// Only the `Debug` and `Write` trait objects can be shared and sent to other threads.
+trait_mux!(#[trait_mux(with_accessors)] Dispatcher{Debug + Sync, Display, mut std::fmt::Write + Send});
+
+fn assert_sync<T: ?Sized + Sync>(_: &T) {}
+
+fn main() {
+    // A `Sync` value gets both traits, and the `Debug` trait object can be shared.
+    let mut number = 5;
+    let dispatcher = into_dispatcher!(number);
+    assert_eq!(dispatcher.variant_name(), "DispatcherDebugDisplay");
+    let debug = dispatcher.try_as_debug().unwrap();
+    std::thread::scope(|s| {
+        s.spawn(move || assert_eq!(format!("{:?}", debug), "5"));
+    });
+    assert_eq!(dispatcher.try_as_display().unwrap().to_string(), "5");
+    assert_eq!(dispatcher.with_debug(|v| format!("{:?}", v)).unwrap(), "5");
+    match dispatcher.as_any_of(&["Debug"]) {
+        Some(DispatcherTraitRef::Debug(v)) => assert_sync(v),
+        _ => panic!("expected the Debug view"),
+    }
+
+    // A value that isn't `Sync` doesn't satisfy the bounded trait, but still gets the others.
+    let mut shared = Rc::new(7);
+    let dispatcher = into_dispatcher!(shared);
+    assert_eq!(dispatcher.variant_name(), "DispatcherDisplay");
+    assert!(dispatcher.try_as_debug().is_none());
+    assert_eq!(dispatcher.try_as_display().unwrap().to_string(), "7");
+
+    // The bounds apply to mutable traits as well.
+    let mut text = String::new();
+    {
+        let mut dispatcher = into_dispatcher!(text);
+        let write = dispatcher.try_as_write_mut().unwrap();
+        std::thread::scope(|s| {
+            s.spawn(move || write.write_str("hello").unwrap());
+        });
+    }
+    assert_eq!(text, "hello");
+}
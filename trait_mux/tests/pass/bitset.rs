@@ -0,0 +1,127 @@
+use trait_mux::trait_mux;
+
+trait TraitA {}
+trait TraitB {}
+trait TraitC {}
+trait TraitD {}
+trait TraitE {}
+trait TraitF {}
+trait TraitG {}
+trait TraitH {}
+trait TraitI {}
+trait TraitJ {}
+trait TraitK {}
+trait TraitL {}
+trait TraitM {}
+trait TraitN {}
+trait TraitO {}
+trait TraitP {}
+trait TraitQ {}
+trait TraitR {}
+trait TraitS {}
+trait TraitT {}
+
+struct Evens;
+impl TraitA for Evens {}
+impl TraitC for Evens {}
+impl TraitE for Evens {}
+impl TraitG for Evens {}
+impl TraitI for Evens {}
+impl TraitK for Evens {}
+impl TraitM for Evens {}
+impl TraitO for Evens {}
+impl TraitQ for Evens {}
+impl TraitS for Evens {}
+
+struct All;
+impl TraitA for All {}
+impl TraitB for All {}
+impl TraitC for All {}
+impl TraitD for All {}
+impl TraitE for All {}
+impl TraitF for All {}
+impl TraitG for All {}
+impl TraitH for All {}
+impl TraitI for All {}
+impl TraitJ for All {}
+impl TraitK for All {}
+impl TraitL for All {}
+impl TraitM for All {}
+impl TraitN for All {}
+impl TraitO for All {}
+impl TraitP for All {}
+impl TraitQ for All {}
+impl TraitR for All {}
+impl TraitS for All {}
+impl TraitT for All {}
+
+struct Nothing;
+
+trait_mux!(
+    #[trait_mux(bitset)]
+    Dispatcher {
+        TraitA,
+        TraitB,
+        TraitC,
+        TraitD,
+        TraitE,
+        TraitF,
+        TraitG,
+        TraitH,
+        TraitI,
+        TraitJ,
+        TraitK,
+        TraitL,
+        TraitM,
+        TraitN,
+        TraitO,
+        TraitP,
+        TraitQ,
+        TraitR,
+        TraitS,
+        TraitT
+    }
+);
+
+trait_mux!(#[trait_mux(bitset)] Formatter{std::fmt::Debug, std::fmt::Display});
+
+struct Borrowed<'a>(&'a str);
+
+impl std::fmt::Display for Borrowed<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "borrowed {}", self.0)
+    }
+}
+
+fn main() {
+    let evens = Evens;
+    let dispatcher = into_dispatcher!(evens);
+    assert!(dispatcher.try_as_trait_a().is_some());
+    assert!(dispatcher.try_as_trait_b().is_none());
+    assert!(dispatcher.try_as_trait_s().is_some());
+    assert!(dispatcher.try_as_trait_t().is_none());
+    assert_eq!(dispatcher.traits_mask(), 0b0101_0101_0101_0101_0101);
+
+    let all = All;
+    let dispatcher = into_dispatcher!(all);
+    assert!(dispatcher.try_as_trait_h().is_some());
+    assert_eq!(dispatcher.traits_mask(), (1 << 20) - 1);
+
+    let nothing = Nothing;
+    let dispatcher = into_dispatcher!(nothing);
+    assert!(dispatcher.try_as_trait_a().is_none());
+    assert_eq!(dispatcher.traits_mask(), 0);
+
+    // The trait objects are cast back out of the erased pointer to the value.
+    let text = String::from("text");
+    let formatter = into_formatter!(text);
+    assert_eq!(format!("{:?}", formatter.try_as_debug().unwrap()), "\"text\"");
+    assert_eq!(formatter.try_as_display().unwrap().to_string(), "text");
+    assert_eq!(formatter.traits_mask(), 0b11);
+
+    let owner = String::from("value");
+    let borrowed = Borrowed(&owner);
+    let formatter = into_formatter!(borrowed);
+    assert!(formatter.try_as_debug().is_none());
+    assert_eq!(formatter.try_as_display().unwrap().to_string(), "borrowed value");
+}
@@ -0,0 +1,35 @@
+use std::fmt::{Debug, Display};
+use trait_mux::trait_mux;
+
+trait_mux!(
+    #[cfg_attr(all(), derive(Clone, Copy))]
+    #[cfg_attr(any(), derive(NotADerive))]
+    Dispatcher{Debug, Display}
+);
+
+trait_mux!(
+    #[trait_mux(repr = u8)]
+    #[cfg_attr(not(any()), derive(Clone, Copy))]
+    Counter{Debug}
+);
+
+trait_mux!(
+    #[cfg_attr(all(), derive(Clone, Copy))]
+    Values types{u8, i32} traits{Debug}
+);
+
+fn assert_copy<T: Copy>(_: T) {}
+
+fn main() {
+    let number = 5;
+
+    // The enabled `cfg_attr` derives `Copy`, the disabled one is dropped along with its
+    // unknown derive.
+    let dispatcher = into_dispatcher!(number);
+    let copy = dispatcher;
+    assert_copy(dispatcher);
+    assert_eq!(copy.as_display().to_string(), "5");
+
+    assert_copy(into_counter!(number));
+    assert_copy(Values::from(5u8));
+}
@@ -0,0 +1,41 @@
+use std::fmt::{Debug, Display};
+use trait_mux::trait_mux;
+
+trait_mux!(#[trait_mux(collection)] Dispatcher{Debug, Display});
+
+struct Opaque;
+
+fn main() {
+    let number = 5;
+    let bytes = vec![1u8, 2];
+    let opaque = Opaque;
+    let text = "text";
+
+    let mut dispatchers = DispatcherVec::new();
+    push_dispatcher!(dispatchers, number, bytes);
+    push_dispatcher!(dispatchers, opaque);
+
+    // The collection can be given through a mutable reference as well.
+    let collection = &mut dispatchers;
+    push_dispatcher!(collection, text,);
+
+    let names: Vec<_> = dispatchers.iter().map(|d| d.variant_name()).collect();
+    assert_eq!(
+        names,
+        [
+            "DispatcherDebugDisplay",
+            "DispatcherDebug",
+            "DispatcherNone",
+            "DispatcherDebugDisplay",
+        ]
+    );
+
+    let mut displayed = String::new();
+    for dispatcher in &dispatchers {
+        if let Some(display) = dispatcher.try_as_display() {
+            displayed += &display.to_string();
+        }
+    }
+    assert_eq!(displayed, "5text");
+    assert_eq!(dispatchers.into_iter().count(), 4);
+}
@@ -0,0 +1,50 @@
+use std::fmt::{Debug, Display};
+use std::io::{Read, Write};
+use trait_mux::trait_mux;
+
+pub trait Named {
+    fn name(&self) -> &'static str;
+}
+
+trait_mux!(Dispatcher{(Debug + Display), Named});
+trait_mux!(#[trait_mux(bitset)] Flags{(Debug + Display), Named});
+trait_mux!(Stream{mut (Read + Write) as Io, Debug});
+
+#[derive(Debug)]
+struct DebugNamed;
+
+impl Named for DebugNamed {
+    fn name(&self) -> &'static str {
+        "debug"
+    }
+}
+
+fn main() {
+    // The combination is a single trait, so there is no variant per combined trait.
+    assert_eq!(Dispatcher::VARIANT_COUNT, 4);
+
+    let number = 5;
+    let dispatcher = into_dispatcher!(number);
+    assert!(matches!(dispatcher, Dispatcher::DispatcherDebugDisplay(_)));
+    let both = dispatcher.try_as_debug_display().unwrap();
+    assert_eq!(format!("{both:?} {both}"), "5 5");
+    assert!(dispatcher.try_as_named().is_none());
+
+    // Implementing only some of the combined traits isn't enough.
+    let vec = vec![1];
+    assert!(matches!(into_dispatcher!(vec), Dispatcher::DispatcherNone));
+    assert!(matches!(
+        into_dispatcher!(DebugNamed),
+        Dispatcher::DispatcherNamed(_)
+    ));
+
+    let flags = into_flags!(number);
+    assert_eq!(flags.try_as_debug_display().unwrap().to_string(), "5");
+    assert!(flags.try_as_named().is_none());
+
+    let mut cursor = std::io::Cursor::new(Vec::new());
+    let mut stream = into_stream!(cursor);
+    let io = stream.try_as_io_mut().unwrap();
+    io.write_all(b"data").unwrap();
+    assert_eq!(cursor.into_inner(), b"data");
+}
@@ -0,0 +1,64 @@
+use trait_mux::trait_mux;
+
+trait CommonBase {
+    fn name(&self) -> String;
+}
+
+trait Small: CommonBase {}
+trait Signed: CommonBase {}
+
+impl CommonBase for u8 {
+    fn name(&self) -> String {
+        format!("u8 {}", self)
+    }
+}
+impl Small for u8 {}
+
+impl CommonBase for i64 {
+    fn name(&self) -> String {
+        format!("i64 {}", self)
+    }
+}
+impl Signed for i64 {}
+
+impl CommonBase for i8 {
+    fn name(&self) -> String {
+        format!("i8 {}", self)
+    }
+}
+impl Small for i8 {}
+impl Signed for i8 {}
+
+trait_mux!(#[trait_mux(common = CommonBase)] Dispatcher{Small, Signed});
+
+fn describe(dispatcher: Dispatcher) -> String {
+    match Option::<&dyn CommonBase>::from(dispatcher) {
+        Some(base) => base.name(),
+        None => "nothing".to_string(),
+    }
+}
+
+fn main() {
+    let (a, b, c, d) = (1u8, -2i64, -3i8, "text");
+
+    assert_eq!(describe(into_dispatcher!(a)), "u8 1");
+    assert_eq!(describe(into_dispatcher!(b)), "i64 -2");
+    assert_eq!(describe(into_dispatcher!(c)), "i8 -3");
+    assert_eq!(describe(into_dispatcher!(d)), "nothing");
+
+    assert_eq!(into_dispatcher!(c).as_common_base().name(), "i8 -3");
+    assert!(into_dispatcher!(d).try_as_common_base().is_none());
+
+    let base: Option<&dyn CommonBase> = into_dispatcher!(a).into();
+    assert!(base.is_some());
+
+    let items = [
+        into_dispatcher!(a),
+        into_dispatcher!(d),
+        into_dispatcher!(b),
+        into_dispatcher!(c),
+    ];
+    let bases: Vec<&dyn CommonBase> = Dispatcher::as_common_vec(&items);
+    let names: Vec<String> = bases.iter().map(|base| base.name()).collect();
+    assert_eq!(names, ["u8 1", "i64 -2", "i8 -3"]);
+}
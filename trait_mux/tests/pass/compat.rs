@@ -0,0 +1,22 @@
+use std::error::Error;
+use std::fmt::{Debug, Display};
+use trait_mux::trait_mux;
+
+trait_mux!(#[trait_mux(compat(Other))] Dispatcher{Debug, Display});
+trait_mux!(Other{Debug, Error});
+
+fn main() {
+    let unit = ();
+    let dispatcher = into_dispatcher!(unit);
+    let other = into_other!(unit);
+    assert_eq!(dispatcher.trait_names(), ["Debug"]);
+    assert_eq!(other.trait_names(), ["Debug"]);
+    assert!(dispatcher.same_capabilities(&other));
+
+    // Both variants hold `Debug`, but only the first one holds `Display` as well.
+    let number = 5;
+    let dispatcher = into_dispatcher!(number);
+    let other = into_other!(number);
+    assert_eq!(dispatcher.trait_names(), ["Debug", "Display"]);
+    assert!(!dispatcher.same_capabilities(&other));
+}
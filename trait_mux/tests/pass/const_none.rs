@@ -0,0 +1,20 @@
+use std::fmt::{Debug, Display};
+use trait_mux::trait_mux;
+
+trait_mux!(Dispatcher{Debug, Display});
+
+// The enum holds `&dyn` references, which aren't `Sync`, so it can't be stored in a `static`.
+const DISPATCHER: Dispatcher = Dispatcher::none();
+const DISPATCHERS: [Dispatcher; 2] = [Dispatcher::none(), Dispatcher::none()];
+
+fn main() {
+    assert!(DISPATCHER.is_none());
+    assert_eq!(DISPATCHER.variant_name(), "DispatcherNone");
+    assert!(DISPATCHERS.iter().all(Dispatcher::is_none));
+
+    let number = 5;
+    let mut dispatcher = Dispatcher::none();
+    assert!(dispatcher.try_as_debug().is_none());
+    dispatcher = into_dispatcher!(number);
+    assert!(dispatcher.try_as_debug().is_some());
+}
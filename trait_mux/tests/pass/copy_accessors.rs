@@ -0,0 +1,16 @@
+use std::fmt::{Debug, Display};
+use trait_mux::trait_mux;
+
+trait_mux!(#[trait_mux(copy_accessors)] Small types{u8} traits{Debug, Display, std::error::Error});
+
+fn main() {
+    let small = Small::from(7);
+
+    let value: Option<u8> = small.try_as_debug_copy();
+    assert_eq!(value, Some(7));
+    assert_eq!(small.try_as_display_copy(), Some(7));
+    assert_eq!(small.try_as_error_copy(), None);
+
+    // The reference accessors are still generated.
+    assert_eq!(small.try_as_display().unwrap().to_string(), "7");
+}
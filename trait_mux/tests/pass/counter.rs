@@ -0,0 +1,36 @@
+use std::fmt::{Debug, Display};
+use trait_mux::trait_mux;
+
+trait_mux!(#[trait_mux(counter)] Dispatcher{Debug, Display});
+trait_mux!(#[trait_mux(counter, opaque)] Hidden{Debug});
+
+struct Nothing;
+
+fn main() {
+    let number = 5;
+    let text = String::from("text");
+    let vec = vec![1];
+    let nothing = Nothing;
+
+    let mut counter = DispatcherCounter::new();
+    counter.record(&into_dispatcher!(number));
+    counter.record(&into_dispatcher!(text));
+    counter.record(&into_dispatcher!(vec));
+    counter.record(&into_dispatcher!(nothing));
+    counter.record(&into_dispatcher!(number));
+
+    assert_eq!(counter.counts.len(), Dispatcher::VARIANT_COUNT);
+    assert_eq!(
+        counter.report(),
+        [
+            ("DispatcherDebugDisplay", 3),
+            ("DispatcherDebug", 1),
+            ("DispatcherDisplay", 0),
+            ("DispatcherNone", 1),
+        ]
+    );
+
+    let mut counter = HiddenCounter::default();
+    counter.record(&into_hidden!(number));
+    assert_eq!(counter.report(), [("HiddenDebug", 1), ("HiddenNone", 0)]);
+}
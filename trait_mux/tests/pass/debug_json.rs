@@ -0,0 +1,45 @@
+use std::fmt::{Debug, Display};
+use trait_mux::trait_mux;
+
+trait_mux!(#[trait_mux(debug_json)] Dispatcher{Debug, Display});
+trait_mux!(#[trait_mux(debug_json)] Cloner{std::fmt::Display, ToString});
+
+struct DisplayOnly;
+
+impl Display for DisplayOnly {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line\none")
+    }
+}
+
+struct Nothing;
+
+fn main() {
+    let vec = vec![1, 2];
+    let text = "say \"hi\"";
+    let display_only = DisplayOnly;
+    let nothing = Nothing;
+
+    assert_eq!(
+        into_dispatcher!(vec).to_debug_json(),
+        r#"{"variant":"DispatcherDebug","value":"[1, 2]"}"#
+    );
+    // `Debug` is preferred over `Display`, and the rendering is escaped.
+    assert_eq!(
+        into_dispatcher!(text).to_debug_json(),
+        r#"{"variant":"DispatcherDebugDisplay","value":"\"say \\\"hi\\\"\""}"#
+    );
+    assert_eq!(
+        into_dispatcher!(display_only).to_debug_json(),
+        r#"{"variant":"DispatcherDisplay","value":"line\u000aone"}"#
+    );
+    assert_eq!(
+        into_dispatcher!(nothing).to_debug_json(),
+        r#"{"variant":"DispatcherNone"}"#
+    );
+
+    assert_eq!(
+        into_cloner!(display_only).to_debug_json(),
+        r#"{"variant":"ClonerDisplayToString","value":"line\u000aone"}"#
+    );
+}
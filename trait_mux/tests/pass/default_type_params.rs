@@ -0,0 +1,46 @@
+use trait_mux::trait_mux;
+
+/// A trait with a defaulted type parameter, like `Add<Rhs = Self>` but with a concrete default.
+trait Scale<T = u8> {
+    fn scale(&self, by: T) -> u32;
+}
+
+struct Narrow(u32);
+
+impl Scale for Narrow {
+    fn scale(&self, by: u8) -> u32 {
+        self.0 * u32::from(by)
+    }
+}
+
+struct Both(u32);
+
+impl Scale for Both {
+    fn scale(&self, by: u8) -> u32 {
+        self.0 * u32::from(by)
+    }
+}
+
+impl Scale<u16> for Both {
+    fn scale(&self, by: u16) -> u32 {
+        self.0 * u32::from(by) * 10
+    }
+}
+
+// `Scale` means `Scale<u8>`, both in the trait objects and in the bounds.
+trait_mux!(Dispatcher{Scale, Scale<u16> as ScaleWide, Scale<u8> as ScaleExplicit});
+
+fn main() {
+    let narrow = Narrow(2);
+    let dispatcher = into_dispatcher!(narrow);
+    assert_eq!(dispatcher.variant_name(), "DispatcherScaleScaleExplicit");
+    assert_eq!(dispatcher.as_scale().scale(3), 6);
+    assert_eq!(dispatcher.as_scale_explicit().scale(3), 6);
+    assert!(dispatcher.try_as_scale_wide().is_none());
+
+    let both = Both(2);
+    let dispatcher = into_dispatcher!(both);
+    assert_eq!(dispatcher.variant_name(), "DispatcherScaleScaleExplicitScaleWide");
+    assert_eq!(dispatcher.as_scale().scale(3), 6);
+    assert_eq!(dispatcher.as_scale_wide().scale(3), 60);
+}
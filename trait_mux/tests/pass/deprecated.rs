@@ -0,0 +1,45 @@
+#![deny(deprecated)]
+
+use std::fmt::{Debug, Display};
+use trait_mux::trait_mux;
+
+pub trait Legacy {
+    fn legacy(&self) -> u32;
+}
+
+impl Legacy for u32 {
+    fn legacy(&self) -> u32 {
+        *self
+    }
+}
+
+// The generated code calls the deprecated accessors without warnings, only the callers are warned.
+trait_mux!(#[trait_mux(owned, named_accessors, lifetime_accessors, debug_json)] Dispatcher {
+    Debug,
+    #[deprecated(note = "use `Display` instead")]
+    Display,
+    /// The legacy trait.
+    #[deprecated]
+    mut Legacy
+});
+
+trait_mux!(#[trait_mux(bitset)] Detector {
+    Debug,
+    #[deprecated]
+    Display
+});
+
+fn main() {
+    let mut number = 5u32;
+    let mut dispatcher = into_dispatcher!(number);
+    assert!(dispatcher.try_as_debug().is_some());
+
+    #[allow(deprecated)]
+    {
+        assert_eq!(dispatcher.try_as_display().unwrap().to_string(), "5");
+        assert_eq!(dispatcher.as_legacy_mut().legacy(), 5);
+    }
+
+    let detector = into_detector!(number);
+    assert!(detector.try_as_debug().is_some());
+}
@@ -0,0 +1,40 @@
+use std::collections::HashSet;
+use std::error::Error;
+use std::fmt::{Debug, Display};
+use trait_mux::trait_mux;
+
+// The variants hold shared references, which are `Copy`, so the copies refer to the same values.
+trait_mux!(#[derive(Clone, Copy)] Dispatcher{Debug, Display});
+
+// Every listed trait has `Debug` as a supertrait, so the trait objects can be formatted.
+trait_mux!(#[derive(Debug)] Debuggable{Debug, Error});
+
+trait_mux!(#[derive(Clone, Copy)] #[trait_mux(eq_by_variant)] Compared{Debug, Display});
+
+// In types mode the enum holds the values themselves, so any derive they support is fine.
+trait_mux!(#[derive(Clone, PartialEq, Debug)] Held types{String, i32} traits{Display});
+
+fn main() {
+    let number = 5;
+    let dispatcher = into_dispatcher!(number);
+    let copy = dispatcher;
+    let clone = dispatcher.clone();
+    assert_eq!(copy.try_as_display().unwrap().to_string(), "5");
+    assert_eq!(clone.variant_name(), dispatcher.variant_name());
+
+    let text = "text";
+    assert_eq!(format!("{:?}", into_debuggable!(text)), "DebuggableDebug(\"text\")");
+
+    // Values implementing the same traits are equal, whatever the values.
+    let other = 6;
+    let vec = vec![1];
+    assert!(into_compared!(number) == into_compared!(other));
+    assert!(into_compared!(number) != into_compared!(vec));
+    let set: HashSet<_> = [into_compared!(number), into_compared!(other), into_compared!(vec)]
+        .into_iter()
+        .collect();
+    assert_eq!(set.len(), 2);
+
+    let held = Held::from(String::from("held"));
+    assert_eq!(held.clone(), held);
+}
@@ -0,0 +1,56 @@
+use std::fmt::{self, Debug, Display};
+use trait_mux::trait_mux;
+
+trait_mux!(Dispatcher{Debug, Display});
+
+struct DisplayOnly;
+
+impl Display for DisplayOnly {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "display only")
+    }
+}
+
+struct Nothing;
+
+fn main() {
+    let number = 5;
+    let unit = ();
+    let display_only = DisplayOnly;
+    let nothing = Nothing;
+
+    let dispatchers = [
+        into_dispatcher!(number),
+        into_dispatcher!(unit),
+        into_dispatcher!(display_only),
+        into_dispatcher!(nothing),
+    ];
+
+    let mut discriminants = vec![];
+    for dispatcher in &dispatchers {
+        let discriminant = dispatcher.discriminant();
+        assert_eq!(
+            Dispatcher::variant_name_of(discriminant),
+            Some(dispatcher.variant_name())
+        );
+        // The traits of a stored discriminant are recovered without an instance.
+        assert_eq!(
+            Dispatcher::variant_traits(discriminant),
+            dispatcher.trait_names()
+        );
+        discriminants.push(discriminant);
+    }
+
+    // Every variant has its own discriminant, numbered in declaration order.
+    discriminants.sort();
+    assert_eq!(discriminants, [0, 1, 2, 3]);
+    assert_eq!(Dispatcher::variant_name_of(0), Some("DispatcherDebugDisplay"));
+    assert_eq!(Dispatcher::variant_name_of(4), None);
+
+    const DEBUG_DISPLAY: &[&str] = Dispatcher::variant_traits(0);
+    assert_eq!(DEBUG_DISPLAY, ["Debug", "Display"]);
+    assert_eq!(Dispatcher::variant_traits(1), ["Debug"]);
+    assert_eq!(Dispatcher::variant_traits(2), ["Display"]);
+    assert!(Dispatcher::variant_traits(3).is_empty());
+    assert!(Dispatcher::variant_traits(4).is_empty());
+}
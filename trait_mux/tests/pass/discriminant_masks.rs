@@ -0,0 +1,58 @@
+use std::fmt::{self, Debug, Display};
+use trait_mux::trait_mux;
+
+trait_mux!(Dispatcher{Debug, Display, std::error::Error});
+
+#[derive(Debug)]
+struct Failure;
+
+impl Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "failure")
+    }
+}
+
+impl std::error::Error for Failure {}
+
+struct Nothing;
+
+/// Computes the mask of a value by matching its variant, with the traits in sorted order.
+fn matched_mask(dispatcher: &Dispatcher) -> u32 {
+    let mut mask = 0;
+    if dispatcher.try_as_debug().is_some() {
+        mask |= 1 << 0;
+    }
+    if dispatcher.try_as_display().is_some() {
+        mask |= 1 << 1;
+    }
+    if dispatcher.try_as_error().is_some() {
+        mask |= 1 << 2;
+    }
+    mask
+}
+
+fn main() {
+    let failure = Failure;
+    let number = 5;
+    let unit = ();
+    let nothing = Nothing;
+
+    let dispatchers = [
+        into_dispatcher!(failure),
+        into_dispatcher!(number),
+        into_dispatcher!(unit),
+        into_dispatcher!(nothing),
+    ];
+
+    for dispatcher in &dispatchers {
+        let discriminant = dispatcher.discriminant();
+        assert_eq!(
+            Dispatcher::DISCRIMINANT_MASKS[discriminant as usize],
+            matched_mask(dispatcher)
+        );
+        assert_eq!(dispatcher.traits_mask(), matched_mask(dispatcher));
+    }
+
+    assert_eq!(dispatchers[0].traits_mask(), 0b111);
+    assert_eq!(Dispatcher::DISCRIMINANT_MASKS.len(), Dispatcher::VARIANT_COUNT);
+}
@@ -0,0 +1,24 @@
+use std::fmt::{Debug, Display};
+use trait_mux::trait_mux;
+
+trait_mux!(#[trait_mux(dispatch_array)] Dispatcher{Debug, Display});
+trait_mux!(#[trait_mux(dispatch_array)] Writer{mut std::io::Write, Debug});
+
+struct Nothing;
+
+fn main() {
+    let numbers = [1i32, 2, 3];
+    let dispatchers: [Dispatcher; 3] = dispatch_array_dispatcher!(numbers);
+    for (dispatcher, number) in dispatchers.iter().zip(numbers) {
+        assert_eq!(format!("{:?}", dispatcher.try_as_debug().unwrap()), number.to_string());
+    }
+
+    let nothings = [Nothing, Nothing];
+    assert!(dispatch_array_dispatcher!(nothings).iter().all(Dispatcher::is_none));
+
+    let mut buffers = [Vec::new(), Vec::new()];
+    for (i, mut writer) in dispatch_array_writer!(buffers).into_iter().enumerate() {
+        write!(writer.as_write_mut(), "{}", i).unwrap();
+    }
+    assert_eq!(buffers, [b"0", b"1"]);
+}
@@ -0,0 +1,32 @@
+use std::fmt::{Debug, Display};
+use trait_mux::trait_mux;
+
+trait Numeric {}
+impl Numeric for i32 {}
+
+trait_mux!(#[trait_mux(dispatch_eq)] Dispatcher{Debug, Numeric});
+trait_mux!(#[trait_mux(dispatch_eq)] Writer{mut std::io::Write, Debug});
+trait_mux!(#[trait_mux(bitset, dispatch_eq)] Detector{Debug, Display});
+
+struct Nothing;
+
+fn main() {
+    let (a, b) = (1i32, 2i32);
+    let text = String::from("text");
+    let nothing = Nothing;
+
+    assert!(dispatch_eq_dispatcher!(a, b));
+    assert!(!dispatch_eq_dispatcher!(a, text));
+    assert!(!dispatch_eq_dispatcher!(text, nothing));
+    assert!(dispatch_eq_dispatcher!(nothing, nothing));
+
+    let mut buffer = Vec::new();
+    let mut other = Vec::new();
+    assert!(dispatch_eq_writer!(buffer, buffer));
+    assert!(dispatch_eq_writer!(buffer, other));
+    other.push(0u8);
+
+    let unit = ();
+    assert!(dispatch_eq_detector!(a, text));
+    assert!(!dispatch_eq_detector!(a, unit));
+}
@@ -0,0 +1,28 @@
+use std::fmt::{Debug, Display};
+use trait_mux::trait_mux;
+
+trait_mux!(#[trait_mux(dispatch_tuple)] Dispatcher{Debug, Display});
+trait_mux!(#[trait_mux(bitset, dispatch_tuple)] Detector{Debug, Display});
+
+struct Nothing;
+
+fn main() {
+    let (first, second) = dispatch_tuple_dispatcher!((1i32, "s"));
+    assert_eq!(first.try_as_display().unwrap().to_string(), "1");
+    assert_eq!(format!("{:?}", second.try_as_debug().unwrap()), "\"s\"");
+
+    // The elements keep their own types, so each selects its own variant.
+    let (number, nothing, values) = (2u8, Nothing, vec![1, 2]);
+    let (a, b, c) = dispatch_tuple_dispatcher!((number, nothing, values,));
+    assert!(matches!(a, Dispatcher::DispatcherDebugDisplay(_)));
+    assert!(b.is_none());
+    assert!(matches!(c, Dispatcher::DispatcherDebug(_)));
+
+    let tuple = dispatch_tuple_dispatcher!((1, 2, 3, 4, 5, 6, 7, Nothing));
+    assert!(tuple.0.try_as_debug().is_some());
+    assert!(tuple.7.is_none());
+
+    let (a, b) = dispatch_tuple_detector!((number, nothing));
+    assert!(a.try_as_display().is_some());
+    assert!(b.is_none());
+}
@@ -0,0 +1,31 @@
+#![deny(unused_doc_comments)]
+
+use std::fmt::{Debug, Display};
+use trait_mux::trait_mux;
+
+trait_mux!(Dispatcher {
+    /// Accesses the value as `Debug`.
+    Debug,
+    /// Accesses the value as `Display`.
+    ///
+    /// Spanning multiple lines.
+    Display,
+    /// Accesses the value as `io::Write`.
+    mut std::io::Write as IoWrite
+});
+
+trait_mux!(#[trait_mux(bitset)] Detector {
+    /// Accesses the value as `Debug`.
+    Debug,
+    Display
+});
+
+fn main() {
+    let mut number = 5;
+    let mut dispatcher = into_dispatcher!(number);
+    assert_eq!(dispatcher.as_display().to_string(), "5");
+    assert!(dispatcher.try_as_io_write_mut().is_none());
+
+    let detector = into_detector!(number);
+    assert_eq!(format!("{:?}", detector.try_as_debug().unwrap()), "5");
+}
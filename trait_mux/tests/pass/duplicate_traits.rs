@@ -0,0 +1,14 @@
+use std::fmt::{Debug, Display};
+use trait_mux::trait_mux;
+
+// Traits listed twice are merged, e.g. after copy-pasting parts of a long list.
+trait_mux!(Dispatcher{Debug, Display, Debug, ::std::fmt::Write, mut std::fmt::Write});
+
+fn main() {
+    let mut string = String::new();
+
+    assert_eq!(Dispatcher::VARIANT_COUNT, 8);
+    let mut dispatcher = into_dispatcher!(string);
+    dispatcher.as_write_mut().write_str("hello").unwrap();
+    assert!(dispatcher.try_as_debug().is_some());
+}
@@ -0,0 +1,49 @@
+#![deny(warnings)]
+
+use std::fmt::Debug;
+use trait_mux::trait_mux;
+
+/// A trait borrowing from the value it's implemented for.
+pub trait Named<'a> {
+    fn name(&self) -> &'a str;
+}
+
+impl<'a> Named<'a> for &'a str {
+    fn name(&self) -> &'a str {
+        self
+    }
+}
+
+pub trait Rename<'a> {
+    fn rename(&mut self, name: &'a str);
+}
+
+impl<'a> Rename<'a> for &'a str {
+    fn rename(&mut self, name: &'a str) {
+        *self = name;
+    }
+}
+
+// The elided lifetime is bound to the enum lifetime, so the names outlive the borrow of the enum.
+trait_mux!(Dispatcher{Named<'_>, Debug});
+
+trait_mux!(#[trait_mux(opaque, strict)] Renamer{mut Rename<'_>, Named<'_>});
+
+fn name<'t>(dispatcher: &Dispatcher<'t>) -> Option<&'t str> {
+    Some(dispatcher.try_as_named()?.name())
+}
+
+fn main() {
+    let text = "text";
+    let dispatcher = into_dispatcher!(text);
+    assert_eq!(dispatcher.variant_name(), "DispatcherDebugNamed");
+    assert_eq!(name(&dispatcher), Some("text"));
+
+    let number = 5;
+    assert_eq!(name(&into_dispatcher!(number)), None);
+
+    let mut value = "old";
+    let mut renamer = into_renamer_strict!(value);
+    renamer.as_rename_mut().rename("new");
+    assert_eq!(renamer.as_named().name(), "new");
+}
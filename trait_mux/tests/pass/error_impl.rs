@@ -0,0 +1,49 @@
+use std::error::Error;
+use std::fmt::{self, Debug, Display};
+use trait_mux::trait_mux;
+
+trait_mux!(#[trait_mux(error_impl)] Dispatcher{Error, Display});
+trait_mux!(#[trait_mux(error_impl, opaque)] Hidden{std::error::Error + Send as StdError});
+
+#[derive(Debug)]
+struct Wrapped(std::num::ParseIntError);
+
+impl Display for Wrapped {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid count")
+    }
+}
+
+impl Error for Wrapped {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.0)
+    }
+}
+
+fn check<'a>(value: &'a Wrapped) -> Result<(), Box<dyn Error + 'a>> {
+    Err(into_dispatcher!((*value)))?
+}
+
+fn main() {
+    let wrapped = Wrapped("x".parse::<u32>().unwrap_err());
+
+    // The error variants delegate to the held error.
+    let boxed = check(&wrapped).unwrap_err();
+    assert_eq!(boxed.to_string(), "invalid count");
+    assert_eq!(format!("{boxed:?}"), format!("{wrapped:?}"));
+    assert_eq!(
+        boxed.source().unwrap().to_string(),
+        "invalid digit found in string"
+    );
+
+    // The other variants are formatted as their name, without a source.
+    let dispatcher = into_dispatcher!(5);
+    assert_eq!(dispatcher.to_string(), "DispatcherDisplay");
+    assert_eq!(format!("{dispatcher:?}"), "DispatcherDisplay");
+    assert!(dispatcher.source().is_none());
+
+    let hidden = into_hidden!(wrapped);
+    assert_eq!(hidden.to_string(), "invalid count");
+    assert!(hidden.source().is_some());
+    assert_eq!(into_hidden!(5).to_string(), "HiddenNone");
+}
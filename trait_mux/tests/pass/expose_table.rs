@@ -0,0 +1,21 @@
+use std::fmt::{Debug, Display};
+use trait_mux::trait_mux;
+
+trait_mux!(#[trait_mux(expose_table)] Dispatcher{Debug, Display});
+
+fn main() {
+    let table = Dispatcher::VARIANTS_TABLE;
+    assert_eq!(table.len(), 4);
+
+    for (variant, traits) in table {
+        if traits.is_empty() {
+            assert_eq!(*variant, "DispatcherNone");
+        } else {
+            // Every other variant is named after its traits.
+            assert_eq!(*variant, format!("Dispatcher{}", traits.concat()));
+        }
+    }
+
+    assert_eq!(table[0], ("DispatcherDebugDisplay", &["Debug", "Display"][..]));
+    assert_eq!(table.last(), Some(&("DispatcherNone", &[][..])));
+}
@@ -0,0 +1,39 @@
+use std::fmt::{self, Debug, Display};
+use trait_mux::trait_mux;
+
+trait_mux!(#[trait_mux(extension(i32, DisplayOnly, Nothing))] Dispatcher{Debug, Display});
+
+// Mutable traits make the method take `&mut self`.
+trait_mux!(#[trait_mux(extension(Vec<u8>))] Writer{mut std::io::Write, Debug});
+
+struct DisplayOnly;
+
+impl Display for DisplayOnly {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "display only")
+    }
+}
+
+struct Nothing;
+
+fn main() {
+    let number = 5;
+    assert_eq!(format!("{:?}", number.as_dispatcher().as_debug()), "5");
+    assert_eq!(
+        number.as_dispatcher().variant_name(),
+        "DispatcherDebugDisplay"
+    );
+
+    let display_only = DisplayOnly;
+    assert_eq!(
+        display_only.as_dispatcher().as_display().to_string(),
+        "display only"
+    );
+    assert!(display_only.as_dispatcher().try_as_debug().is_none());
+
+    assert_eq!(Nothing.as_dispatcher().variant_name(), "DispatcherNone");
+
+    let mut buffer = Vec::new();
+    buffer.as_writer().as_write_mut().write_all(b"chained").unwrap();
+    assert_eq!(buffer, b"chained");
+}
@@ -0,0 +1,30 @@
+use std::fmt::{Debug, Display};
+use trait_mux::trait_mux;
+
+trait_mux!(#[trait_mux(extension(i32, String, Nothing))] Dispatcher{Debug, Display});
+
+// Mutable traits make the conversion take `&mut T`.
+trait_mux!(#[trait_mux(extension(Vec<u8>))] Writer{mut std::io::Write, Debug});
+
+struct Nothing;
+
+fn main() {
+    // Each type converts into its maximal variant, as with the into macro.
+    let number = 5;
+    let dispatcher = Dispatcher::from(&number);
+    assert_eq!(dispatcher.variant_name(), into_dispatcher!(number).variant_name());
+    assert_eq!(format!("{:?}", dispatcher.as_debug()), "5");
+
+    let text = String::from("text");
+    let dispatcher: Dispatcher = (&text).into();
+    assert_eq!(dispatcher.variant_name(), into_dispatcher!(text).variant_name());
+    assert_eq!(dispatcher.as_display().to_string(), "text");
+
+    let nothing = Nothing;
+    let dispatcher = Dispatcher::from(&nothing);
+    assert_eq!(dispatcher.variant_name(), "DispatcherNone");
+
+    let mut buffer = Vec::new();
+    Writer::from(&mut buffer).as_write_mut().write_all(b"from").unwrap();
+    assert_eq!(buffer, b"from");
+}
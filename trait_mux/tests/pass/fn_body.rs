@@ -0,0 +1,29 @@
+use trait_mux::trait_mux;
+
+fn main() {
+    // The traits and the generated items are local to the function body.
+    trait Named {
+        fn name(&self) -> &'static str;
+    }
+
+    impl Named for u32 {
+        fn name(&self) -> &'static str {
+            "u32"
+        }
+    }
+
+    trait_mux!(Dispatcher{std::fmt::Debug, std::fmt::Display, Named});
+    trait_mux!(#[trait_mux(bitset)] Detector{std::fmt::Debug, Named});
+
+    let number = 5u32;
+    let dispatcher = into_dispatcher!(number);
+    assert_eq!(dispatcher.variant_name(), "DispatcherDebugDisplayNamed");
+    assert_eq!(dispatcher.as_named().name(), "u32");
+
+    let text = "text";
+    assert_eq!(into_dispatcher!(text).variant_name(), "DispatcherDebugDisplay");
+
+    let detector = into_detector!(text);
+    assert!(detector.try_as_debug().is_some());
+    assert!(detector.try_as_named().is_none());
+}
@@ -0,0 +1,34 @@
+use std::fmt::Debug;
+use trait_mux::trait_mux;
+
+trait_mux!(Dispatcher{Fn(u32) -> u32, Debug});
+trait_mux!(Callbacks{mut FnMut(&str) as Callback, Fn() -> String as Producer});
+
+fn double(x: u32) -> u32 {
+    x * 2
+}
+
+fn main() {
+    let increment = |x: u32| x + 1;
+    let dispatcher = into_dispatcher!(increment);
+    assert_eq!(dispatcher.try_as_fn().unwrap()(1), 2);
+    assert!(dispatcher.try_as_debug().is_none());
+
+    let double = double;
+    assert_eq!(into_dispatcher!(double).as_fn()(4), 8);
+
+    let number = 5u32;
+    let dispatcher = into_dispatcher!(number);
+    assert!(dispatcher.try_as_fn().is_none());
+    assert!(dispatcher.try_as_debug().is_some());
+
+    let mut seen = Vec::new();
+    let mut record = |s: &str| seen.push(s.to_owned());
+    let mut callbacks = into_callbacks!(record);
+    callbacks.as_callback_mut()("called");
+    assert!(callbacks.try_as_producer().is_none());
+    assert_eq!(seen, ["called"]);
+
+    let mut produce = || String::from("produced");
+    assert_eq!(into_callbacks!(produce).as_producer()(), "produced");
+}
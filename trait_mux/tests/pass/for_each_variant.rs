@@ -0,0 +1,21 @@
+use std::fmt::{Debug, Display};
+use trait_mux::trait_mux;
+
+trait_mux!(Dispatcher{Debug, Display});
+
+fn main() {
+    let mut variants = vec![];
+    Dispatcher::for_each_variant(|name, trait_count| variants.push((name, trait_count)));
+
+    // The variants are visited in declaration order, the most specific first.
+    assert_eq!(
+        variants,
+        [
+            ("DispatcherDebugDisplay", 2),
+            ("DispatcherDebug", 1),
+            ("DispatcherDisplay", 1),
+            ("DispatcherNone", 0),
+        ]
+    );
+    assert_eq!(variants.len(), Dispatcher::VARIANT_COUNT);
+}
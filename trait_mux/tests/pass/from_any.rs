@@ -0,0 +1,16 @@
+use std::any::Any;
+use std::fmt::{Debug, Display};
+use trait_mux::trait_mux;
+
+trait_mux!(Dispatcher { Debug, Display });
+
+fn main() {
+    let value = String::from("hello");
+    let any: &dyn Any = &value;
+
+    let dispatcher = from_any_as_dispatcher!(any, String).unwrap();
+    assert!(matches!(dispatcher, Dispatcher::DispatcherDebugDisplay(_)));
+    assert_eq!(dispatcher.try_as_display().unwrap().to_string(), "hello");
+
+    assert!(from_any_as_dispatcher!(any, i32).is_none());
+}
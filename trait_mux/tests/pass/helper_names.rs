@@ -0,0 +1,43 @@
+use std::fmt::{Debug, Display};
+use trait_mux::trait_mux;
+
+// The autoref specialization helpers are named after the enum, so the user's items may have the
+// names of the traits' tags, steps, match traits and casts, or of the wrap struct.
+#[derive(Debug)]
+struct DebugTag;
+#[derive(Debug)]
+struct DisplayTag;
+#[allow(dead_code)]
+struct NoneTag;
+#[allow(dead_code)]
+struct DebugStep<S>(S);
+#[allow(dead_code)]
+struct DebugNoneStep<S>(S);
+#[allow(dead_code)]
+trait DebugMatch {}
+#[allow(dead_code)]
+trait DebugNoneMatch {}
+#[allow(dead_code)]
+trait DebugErase {}
+#[allow(dead_code)]
+trait DebugCast {}
+#[allow(dead_code)]
+struct Wrap<T>(T);
+
+trait_mux!(Dispatcher{Debug, Display});
+trait_mux!(#[trait_mux(bitset)] Detector{Debug, Display});
+trait_mux!(#[trait_mux(mode = auto)] Single{Debug});
+
+fn main() {
+    let (tag, other) = (DebugTag, DisplayTag);
+
+    assert_eq!(into_dispatcher!(tag).variant_name(), "DispatcherDebug");
+    assert_eq!(into_dispatcher!(other).variant_name(), "DispatcherDebug");
+    assert_eq!(format!("{:?}", into_dispatcher!(tag).as_debug()), "DebugTag");
+
+    let detector = into_detector!(other);
+    assert!(detector.try_as_debug().is_some());
+    assert!(detector.try_as_display().is_none());
+
+    assert!(into_single!(tag).try_as_debug().is_some());
+}
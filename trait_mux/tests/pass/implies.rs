@@ -0,0 +1,35 @@
+use std::fmt::{Debug, Display};
+use trait_mux::trait_mux;
+
+trait DisplayExt: Display {
+    fn shout(&self) -> String {
+        self.to_string().to_uppercase()
+    }
+}
+
+impl DisplayExt for String {}
+
+trait_mux!(#[trait_mux(implies(DisplayExt => Display))] Dispatcher{Debug, Display, DisplayExt});
+
+/// Only compiles if every variant is listed, the variants with `DisplayExt` but without `Display`
+/// must not exist.
+fn variant_name(dispatcher: &Dispatcher) -> &'static str {
+    match dispatcher {
+        Dispatcher::DispatcherDebugDisplayDisplayExt(_) => "DebugDisplayDisplayExt",
+        Dispatcher::DispatcherDebugDisplay(_) => "DebugDisplay",
+        Dispatcher::DispatcherDisplayDisplayExt(_) => "DisplayDisplayExt",
+        Dispatcher::DispatcherDebug(_) => "Debug",
+        Dispatcher::DispatcherDisplay(_) => "Display",
+        Dispatcher::DispatcherNone => "None",
+    }
+}
+
+fn main() {
+    let string = String::from("hi");
+    let dispatcher = into_dispatcher!(string);
+    assert_eq!(variant_name(&dispatcher), "DebugDisplayDisplayExt");
+    assert_eq!(dispatcher.try_as_display_ext().unwrap().shout(), "HI");
+
+    let number = 5;
+    assert_eq!(variant_name(&into_dispatcher!(number)), "DebugDisplay");
+}
@@ -0,0 +1,34 @@
+use std::fmt::{Debug, Display};
+use trait_mux::trait_mux;
+
+pub trait Named {
+    fn name(&self) -> &str;
+}
+
+pub trait Loud {}
+
+trait_mux!(#[trait_mux(implies(Named => Display))] Dispatcher{Named, Display});
+trait_mux!(#[trait_mux(implies(Named => Display))] Tagged{Debug, Loud, Named, Display});
+
+#[derive(Debug)]
+pub struct Unnamed;
+
+impl Named for Unnamed {
+    fn name(&self) -> &str {
+        "unnamed"
+    }
+}
+
+impl Loud for Unnamed {}
+
+fn main() {
+    // `Unnamed` implements `Named` without `Display`, so it has no variant of its own, and falls
+    // back to the variant with the most traits among those it implements.
+    let value = Unnamed;
+    assert_eq!(into_dispatcher!(value).variant_name(), "DispatcherNone");
+
+    let tagged = into_tagged!(value);
+    assert_eq!(tagged.variant_name(), "TaggedDebugLoud");
+    assert!(tagged.try_as_named().is_none());
+    assert_eq!(format!("{:?}", tagged.try_as_debug().unwrap()), "Unnamed");
+}
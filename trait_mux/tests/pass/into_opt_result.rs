@@ -0,0 +1,34 @@
+use std::fmt::{Debug, Display};
+use trait_mux::trait_mux;
+
+trait_mux!(#[trait_mux(into_opt_result)] Dispatcher{Debug, Display});
+trait_mux!(#[trait_mux(into_opt_result)] Writer{mut std::io::Write, Debug});
+trait_mux!(#[trait_mux(bitset, into_opt_result)] Detector{Debug, Display});
+
+struct Nothing;
+
+fn main() {
+    let some = Some(1i32);
+    let none: Option<i32> = None;
+    let nothing = Some(Nothing);
+
+    let dispatcher = into_dispatcher_opt!(&some).unwrap();
+    assert_eq!(dispatcher.try_as_display().unwrap().to_string(), "1");
+    assert!(into_dispatcher_opt!(&none).is_none());
+    assert!(into_dispatcher_opt!(&nothing).unwrap().is_none());
+
+    // The error is borrowed along, without being converted.
+    let ok: Result<String, Nothing> = Ok(String::from("text"));
+    let err: Result<String, Nothing> = Err(Nothing);
+    let dispatcher = into_dispatcher_result!(&ok).ok().unwrap();
+    assert_eq!(dispatcher.try_as_display().unwrap().to_string(), "text");
+    assert!(matches!(into_dispatcher_result!(&err), Err(&Nothing)));
+
+    let mut buffer = Some(Vec::new());
+    let mut writer = into_writer_opt!(&mut buffer).unwrap();
+    writer.try_as_write_mut().unwrap().write_all(b"data").unwrap();
+    assert_eq!(buffer.unwrap(), b"data");
+
+    let detector = into_detector_opt!(&some).unwrap();
+    assert!(detector.try_as_debug().is_some());
+}
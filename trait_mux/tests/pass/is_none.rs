@@ -0,0 +1,21 @@
+use std::fmt::{Debug, Display};
+use trait_mux::trait_mux;
+
+trait_mux!(#[trait_mux(owned)] Dispatcher{Debug, Display});
+trait_mux!(#[trait_mux(bitset)] Detector{Debug, Display});
+
+struct Nothing;
+
+fn main() {
+    let number = 5;
+    let nothing = Nothing;
+
+    assert!(!into_dispatcher!(number).is_none());
+    assert!(into_dispatcher!(nothing).is_none());
+
+    assert!(!into_dispatcher_owned!(String::from("owned")).is_none());
+    assert!(into_dispatcher_owned!(Nothing).is_none());
+
+    assert!(!into_detector!(number).is_none());
+    assert!(into_detector!(nothing).is_none());
+}
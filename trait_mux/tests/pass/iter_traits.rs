@@ -0,0 +1,54 @@
+use std::fmt::{Debug, Display};
+use trait_mux::trait_mux;
+
+trait Writer {
+    fn write(&mut self, s: &str);
+}
+
+impl Writer for String {
+    fn write(&mut self, s: &str) {
+        self.push_str(s);
+    }
+}
+
+trait_mux!(Dispatcher{Debug, Display});
+trait_mux!(MutDispatcher{mut Writer, Display});
+
+fn describe(r: DispatcherTraitRef) -> String {
+    match r {
+        DispatcherTraitRef::Debug(v) => format!("debug {:?}", v),
+        DispatcherTraitRef::Display(v) => format!("display {}", v),
+    }
+}
+
+fn main() {
+    let text = "text";
+    let dispatcher = into_dispatcher!(text);
+
+    let mut views = vec![];
+    for view in &dispatcher {
+        views.push(describe(view));
+    }
+    assert_eq!(views, ["debug \"text\"", "display text"]);
+
+    let unit = ();
+    let dispatcher = into_dispatcher!(unit);
+    let views: Vec<_> = dispatcher.iter_traits().map(describe).collect();
+    assert_eq!(views, ["debug ()"]);
+
+    struct Nothing;
+    let nothing = Nothing;
+    assert_eq!(into_dispatcher!(nothing).iter_traits().count(), 0);
+
+    // Mutable traits are skipped, as the iterator only borrows the dispatcher.
+    let mut string = String::from("hello");
+    let dispatcher = into_mut_dispatcher!(string);
+    let views: Vec<_> = dispatcher
+        .iter_traits()
+        .map(|r| match r {
+            MutDispatcherTraitRef::Display(v) => v.to_string(),
+            MutDispatcherTraitRef::Writer(_) => unreachable!(),
+        })
+        .collect();
+    assert_eq!(views, ["hello"]);
+}
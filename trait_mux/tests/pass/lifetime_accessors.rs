@@ -0,0 +1,37 @@
+use std::fmt::{Debug, Display, Write};
+use trait_mux::trait_mux;
+
+trait_mux!(#[trait_mux(lifetime_accessors)] Dispatcher{Debug, Display, mut Write});
+
+// The default `try_as_debug` returns `&'a (dyn Debug + 'a)`, which can't be pushed into a vector of
+// `&'a (dyn Debug + 't)`, as `&mut Vec<_>` is invariant over its items.
+fn collect_debug<'a, 't>(dispatcher: &'a Dispatcher<'t>, out: &mut Vec<&'a (dyn Debug + 't)>) {
+    if let Some(debug) = dispatcher.try_as_debug_for() {
+        out.push(debug);
+    }
+}
+
+fn write_hello<'a, 't>(dispatcher: &'a mut Dispatcher<'t>) -> Option<&'a mut (dyn Write + 't)> {
+    let writer = dispatcher.try_as_write_mut_for()?;
+    writer.write_str("hello").unwrap();
+    Some(writer)
+}
+
+fn main() {
+    let mut number = 5;
+    let mut text = "text";
+    let dispatchers = [into_dispatcher!(number), into_dispatcher!(text)];
+
+    let mut debugs = vec![];
+    for dispatcher in &dispatchers {
+        collect_debug(dispatcher, &mut debugs);
+    }
+    assert_eq!(format!("{:?}", debugs), r#"[5, "text"]"#);
+    assert_eq!(dispatchers[1].try_as_display_for().unwrap().to_string(), "text");
+
+    let mut string = String::new();
+    let mut dispatcher = into_dispatcher!(string);
+    assert!(write_hello(&mut dispatcher).is_some());
+    assert!(dispatcher.try_as_debug_for().is_some());
+    assert_eq!(string, "hello");
+}
@@ -0,0 +1,52 @@
+use std::fmt::{Debug, Display};
+use trait_mux::trait_mux;
+
+trait Writer {
+    fn write(&mut self, s: &str);
+}
+
+#[derive(Debug, Default)]
+struct Buffer(String);
+
+impl Writer for Buffer {
+    fn write(&mut self, s: &str) {
+        self.0.push_str(s);
+    }
+}
+
+trait_mux!(Dispatcher{Debug, Display});
+trait_mux!(MutDispatcher{mut Writer, Display});
+
+fn describe(r: DispatcherTraitRef) -> String {
+    match r {
+        DispatcherTraitRef::Debug(v) => format!("{:?}", v),
+        DispatcherTraitRef::Display(v) => v.to_string(),
+    }
+}
+
+fn main() {
+    // `Debug` comes first in the sorted trait order, so it's passed for `Debug + Display`.
+    let text = "text";
+    assert_eq!(
+        into_dispatcher!(text).map(describe).as_deref(),
+        Some("\"text\"")
+    );
+
+    struct Nothing;
+    let nothing = Nothing;
+    assert_eq!(into_dispatcher!(nothing).map(describe), None);
+
+    let values = [1, 2, 3];
+    let strings: Vec<String> = values
+        .iter()
+        .filter_map(|v| into_dispatcher!(v).map(describe))
+        .collect();
+    assert_eq!(strings, ["1", "2", "3"]);
+
+    let mut buffer = Buffer::default();
+    into_mut_dispatcher!(buffer).map(|r| match r {
+        MutDispatcherTraitRef::Writer(w) => w.write("hello"),
+        MutDispatcherTraitRef::Display(_) => unreachable!(),
+    });
+    assert_eq!(buffer.0, "hello");
+}
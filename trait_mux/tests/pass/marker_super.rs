@@ -0,0 +1,95 @@
+use trait_mux::trait_mux;
+
+pub trait Marker {
+    fn id(&self) -> u32;
+}
+
+pub trait Speak: Marker {
+    fn speak(&self) -> String;
+}
+
+pub trait Count: Marker {
+    fn count(&self) -> usize;
+}
+
+pub trait Reset: Marker {
+    fn reset(&mut self);
+}
+
+trait_mux!(#[trait_mux(marker_super = Marker, owned)] Dispatcher{Count, Speak});
+trait_mux!(#[trait_mux(marker_super = Marker, opaque)] Hidden{Count, Speak});
+trait_mux!(#[trait_mux(marker_super = Marker, shared_view)] Mutable{mut Reset, Speak});
+
+struct Dog;
+
+impl Marker for Dog {
+    fn id(&self) -> u32 {
+        1
+    }
+}
+
+impl Speak for Dog {
+    fn speak(&self) -> String {
+        "woof".to_string()
+    }
+}
+
+struct Crowd(usize);
+
+impl Marker for Crowd {
+    fn id(&self) -> u32 {
+        2
+    }
+}
+
+impl Count for Crowd {
+    fn count(&self) -> usize {
+        self.0
+    }
+}
+
+struct Choir;
+
+impl Marker for Choir {
+    fn id(&self) -> u32 {
+        3
+    }
+}
+
+impl Speak for Choir {
+    fn speak(&self) -> String {
+        "la".to_string()
+    }
+}
+
+impl Count for Choir {
+    fn count(&self) -> usize {
+        12
+    }
+}
+
+impl Reset for Choir {
+    fn reset(&mut self) {}
+}
+
+struct Nothing;
+
+fn main() {
+    // Every variant with traits is viewed as the marker.
+    assert_eq!(into_dispatcher!(Dog).as_marker().unwrap().id(), 1);
+    let crowd = Crowd(3);
+    assert_eq!(into_dispatcher!(crowd).as_marker().unwrap().id(), 2);
+    assert_eq!(into_dispatcher!(Choir).as_marker().unwrap().id(), 3);
+    assert!(into_dispatcher!(Nothing).as_marker().is_none());
+
+    let owned = into_dispatcher_owned!(Choir);
+    assert_eq!(owned.as_marker().unwrap().id(), 3);
+
+    assert_eq!(into_hidden!(crowd).as_marker().unwrap().id(), 2);
+    assert!(into_hidden!(Nothing).as_marker().is_none());
+
+    let mut choir = Choir;
+    let mutable = into_mutable!(choir);
+    assert_eq!(mutable.as_marker().unwrap().id(), 3);
+    assert_eq!(mutable.as_shared().as_marker().unwrap().id(), 3);
+}
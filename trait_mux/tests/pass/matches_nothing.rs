@@ -0,0 +1,22 @@
+// The tag of the variant without traits discards the value, without any warning.
+#![deny(warnings)]
+
+use std::fmt::{Debug, Display};
+use trait_mux::trait_mux;
+
+trait_mux!(#[trait_mux(owned, strict)] Dispatcher{Debug, Display});
+trait_mux!(Writer{mut std::io::Write, Debug});
+trait_mux!(#[trait_mux(mode = priority_chain)] Chain{Display, Debug});
+
+struct Nothing;
+
+fn main() {
+    let nothing = Nothing;
+    assert!(matches!(into_dispatcher!(nothing), Dispatcher::DispatcherNone));
+    assert!(matches!(into_dispatcher_owned!(Nothing), DispatcherOwned::DispatcherNone));
+
+    let mut nothing = Nothing;
+    assert!(into_writer!(nothing).is_none());
+
+    assert!(into_chain!(Nothing).is_none());
+}
@@ -0,0 +1,35 @@
+use std::fmt::{Debug, Display};
+use trait_mux::trait_mux;
+
+trait_mux!(Dispatcher{Debug, Display});
+trait_mux!(Reversed{Display, Debug});
+
+struct Nothing;
+
+fn main() {
+    let number = 5;
+    let vec = vec![1];
+    let nothing = Nothing;
+
+    let both = into_dispatcher!(number);
+    assert!(matches_variant_dispatcher!(both, [Debug, Display]));
+    assert!(!matches_variant_dispatcher!(both, [Debug]));
+    assert!(!matches_variant_dispatcher!(both, [Display]));
+    assert!(!matches_variant_dispatcher!(both, []));
+
+    // Only the exact combination matches, not its subsets.
+    let debug = into_dispatcher!(vec);
+    assert!(matches_variant_dispatcher!(debug, [Debug]));
+    assert!(!matches_variant_dispatcher!(&debug, [Debug, Display]));
+
+    let none = into_dispatcher!(nothing);
+    assert!(matches_variant_dispatcher!(none, []));
+    assert!(!matches_variant_dispatcher!(none, [Debug]));
+
+    // The traits may be listed in any order, whatever their order in the declaration.
+    assert!(matches_variant_dispatcher!(both, [Display, Debug]));
+    let reversed = into_reversed!(number);
+    assert!(matches_variant_reversed!(reversed, [Display, Debug]));
+    assert!(matches_variant_reversed!(reversed, [Debug, Display]));
+    assert!(!matches_variant_reversed!(reversed, [Display]));
+}
@@ -0,0 +1,30 @@
+use std::fmt::Debug;
+use trait_mux::trait_mux;
+
+pub trait Counter {
+    fn increment(&mut self);
+}
+
+impl Counter for u32 {
+    fn increment(&mut self) {
+        *self += 1;
+    }
+}
+
+trait_mux!(Dispatcher{Debug, mut Counter, mut std::io::Write});
+
+fn main() {
+    let mut count = 1u32;
+    let mut dispatcher = into_dispatcher!(count);
+    assert_eq!(dispatcher.modify_counter(|c| c.increment()), Some(()));
+    assert_eq!(dispatcher.modify_counter(|c| c.increment()), Some(()));
+    assert_eq!(dispatcher.modify_write(|_| unreachable!()), None::<()>);
+    assert_eq!(count, 3);
+
+    let mut buffer = Vec::<u8>::new();
+    let mut dispatcher = into_dispatcher!(buffer);
+    let written = dispatcher.modify_write(|w| w.write(b"hello").unwrap());
+    assert_eq!(written, Some(5));
+    assert!(dispatcher.modify_counter(|c| c.increment()).is_none());
+    assert_eq!(buffer, b"hello");
+}
@@ -0,0 +1,46 @@
+use std::any::Any;
+use std::fmt::Debug;
+use trait_mux::trait_mux;
+
+trait Writer {
+    fn write(&mut self, s: &str);
+}
+
+#[derive(Debug, Default)]
+struct Buffer(String);
+
+impl Writer for Buffer {
+    fn write(&mut self, s: &str) {
+        self.0.push_str(s);
+    }
+}
+
+trait_mux!(Dispatcher{mut Writer, Debug});
+
+fn main() {
+    let mut buffer = Buffer::default();
+
+    {
+        let mut dispatcher = into_dispatcher!(buffer);
+        dispatcher.try_as_writer_mut().unwrap().write("hello");
+        assert_eq!(
+            format!("{:?}", dispatcher.try_as_debug().unwrap()),
+            "Buffer(\"hello\")"
+        );
+        assert!(dispatcher.try_as_writer().is_some());
+    }
+
+    assert_eq!(buffer.0, "hello");
+
+    // Recovering from `Any` requires a mutable reference as well.
+    let any: &mut dyn Any = &mut buffer;
+    let mut dispatcher = from_any_as_dispatcher!(any, Buffer).unwrap();
+    dispatcher.try_as_writer_mut().unwrap().write(" world");
+    assert_eq!(buffer.0, "hello world");
+
+    // Shared-only values are borrowed mutably as well, but only expose shared access.
+    let mut number = 5;
+    let mut dispatcher = into_dispatcher!(number);
+    assert!(dispatcher.try_as_writer_mut().is_none());
+    assert_eq!(format!("{:?}", dispatcher.try_as_debug().unwrap()), "5");
+}
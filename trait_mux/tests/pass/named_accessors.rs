@@ -0,0 +1,26 @@
+use std::fmt::{Debug, Display, Write};
+use trait_mux::trait_mux;
+
+trait_mux!(#[trait_mux(named_accessors)] Dispatcher{Debug, Display, mut Write});
+
+fn main() {
+    let mut number = 5;
+    let mut dispatcher = into_dispatcher!(number);
+
+    let (name, debug) = dispatcher.try_as_debug_named().unwrap();
+    assert_eq!(name, dispatcher.variant_name());
+    assert_eq!(name, "DispatcherDebugDisplay");
+    assert_eq!(format!("{:?}", debug), "5");
+
+    let (name, display) = dispatcher.try_as_display_named().unwrap();
+    assert_eq!(name, "DispatcherDebugDisplay");
+    assert_eq!(display.to_string(), "5");
+    assert!(dispatcher.try_as_write_mut_named().is_none());
+
+    let mut string = String::new();
+    let mut dispatcher = into_dispatcher!(string);
+    let (name, writer) = dispatcher.try_as_write_mut_named().unwrap();
+    assert_eq!(name, "DispatcherDebugDisplayWrite");
+    writer.write_str("hello").unwrap();
+    assert_eq!(string, "hello");
+}
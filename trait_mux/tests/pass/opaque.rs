@@ -0,0 +1,68 @@
+#![deny(warnings)]
+
+use std::fmt::{Debug, Display};
+use trait_mux::trait_mux;
+
+pub trait Counter {
+    fn increment(&mut self);
+}
+
+impl Counter for u32 {
+    fn increment(&mut self) {
+        *self += 1;
+    }
+}
+
+trait_mux!(#[trait_mux(opaque)] Dispatcher{Debug, Display, mut Counter});
+
+mod scoped {
+    use super::*;
+
+    trait_mux!(#[trait_mux(opaque, scoped_macros, strict)] Dispatcher{Debug, Display});
+
+    pub fn into(value: &i32) -> Dispatcher<'_> {
+        into_dispatcher_strict!((*value))
+    }
+}
+
+fn main() {
+    let mut count = 1u32;
+    let mut dispatcher = into_dispatcher!(count);
+    assert_eq!(dispatcher.variant_name(), "DispatcherCounterDebugDisplay");
+    assert_eq!(dispatcher.trait_names(), ["Counter", "Debug", "Display"]);
+    assert_eq!(format!("{:?}", dispatcher.as_debug()), "1");
+    assert_eq!(dispatcher.as_display().to_string(), "1");
+    assert!(!dispatcher.is_none());
+    dispatcher.as_counter_mut().increment();
+    assert_eq!(dispatcher.modify_counter(|c| c.increment()), Some(()));
+    assert_eq!(count, 3);
+
+    let mut text = "text";
+    let dispatcher = into_dispatcher!(text);
+    assert!(dispatcher.try_as_counter().is_none());
+    assert_eq!(
+        dispatcher.traits_mask(),
+        Dispatcher::DISCRIMINANT_MASKS[dispatcher.discriminant() as usize]
+    );
+    assert_eq!(
+        Dispatcher::variant_name_of(dispatcher.discriminant()),
+        Some(dispatcher.variant_name())
+    );
+    assert_eq!(
+        Dispatcher::variant_traits(dispatcher.discriminant()),
+        dispatcher.trait_names()
+    );
+
+    assert!(Dispatcher::none().is_none());
+    assert_eq!(Dispatcher::VARIANT_COUNT, 8);
+    assert_ne!(dispatcher.traits_bitmask() & Dispatcher::DISPLAY_BIT, 0);
+
+    let mut variants = 0;
+    Dispatcher::for_each_variant(|_, _| variants += 1);
+    assert_eq!(variants, Dispatcher::VARIANT_COUNT);
+
+    let value = 5;
+    let scoped = scoped::into(&value);
+    assert_eq!(scoped.as_display().to_string(), "5");
+}
+
@@ -0,0 +1,52 @@
+use std::fmt::{Debug, Display};
+use trait_mux::trait_mux;
+
+trait Writer {
+    fn write(&mut self, s: &str);
+}
+
+#[derive(Debug, Default)]
+struct Buffer(String);
+
+impl Writer for Buffer {
+    fn write(&mut self, s: &str) {
+        self.0.push_str(s);
+    }
+}
+
+trait_mux!(#[trait_mux(owned)] Dispatcher{mut Writer, Debug, Display});
+
+fn make() -> Vec<DispatcherOwned> {
+    let number = 5;
+    let text = String::from("text");
+
+    // The values are moved in, so the dispatchers outlive this scope.
+    vec![
+        into_dispatcher_owned!(number),
+        into_dispatcher_owned!(text.clone()),
+        into_dispatcher_owned!(Buffer::default()),
+        into_dispatcher_owned!(()),
+    ]
+}
+
+fn main() {
+    let mut dispatchers = make();
+
+    assert_eq!(dispatchers[0].variant_name(), "DispatcherDebugDisplay");
+    assert_eq!(dispatchers[0].try_as_display().unwrap().to_string(), "5");
+    assert_eq!(dispatchers[1].try_as_display().unwrap().to_string(), "text");
+
+    assert_eq!(dispatchers[2].variant_name(), "DispatcherDebugWriter");
+    dispatchers[2].try_as_writer_mut().unwrap().write("hello");
+    assert_eq!(
+        format!("{:?}", dispatchers[2].try_as_debug().unwrap()),
+        "Buffer(\"hello\")"
+    );
+
+    assert_eq!(dispatchers[3].variant_name(), "DispatcherDebug");
+    assert!(dispatchers[3].try_as_display().is_none());
+
+    // The borrowed dispatcher is still available alongside the owned one.
+    let mut number = 7;
+    assert!(into_dispatcher!(number).try_as_display().is_some());
+}
@@ -0,0 +1,29 @@
+use std::fmt::{Debug, Display};
+use trait_mux::trait_mux;
+
+trait_mux!(#[trait_mux(owned)] Dispatcher{Debug, Display});
+
+struct DisplayOnly;
+
+impl Display for DisplayOnly {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "display only")
+    }
+}
+
+fn replace(slot: &mut DispatcherOwned) {
+    set_dispatcher_owned!(slot, DisplayOnly);
+}
+
+fn main() {
+    let mut dispatcher = into_dispatcher_owned!(vec![1]);
+    assert_eq!(dispatcher.variant_name(), "DispatcherDebug");
+
+    replace(&mut dispatcher);
+    assert_eq!(dispatcher.variant_name(), "DispatcherDisplay");
+    assert_eq!(dispatcher.as_display().to_string(), "display only");
+
+    let mut slots = vec![into_dispatcher_owned!(())];
+    set_dispatcher_owned!(&mut slots[0], 5);
+    assert_eq!(slots[0].variant_name(), "DispatcherDebugDisplay");
+}
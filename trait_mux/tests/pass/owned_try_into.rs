@@ -0,0 +1,26 @@
+use std::fmt::{Debug, Display};
+use trait_mux::trait_mux;
+
+trait_mux!(#[trait_mux(owned)] Dispatcher{Debug, Display + Send});
+
+struct Nothing;
+
+fn main() {
+    // The box of a variant implementing several traits is upcast to the requested one.
+    let boxed: Box<dyn Debug> = into_dispatcher_owned!(5).try_into().ok().unwrap();
+    assert_eq!(format!("{boxed:?}"), "5");
+
+    let boxed: Result<Box<dyn Display + Send>, _> = into_dispatcher_owned!(String::from("text")).try_into();
+    assert_eq!(boxed.ok().unwrap().to_string(), "text");
+
+    // The dispatcher is returned back if its variant doesn't implement the trait.
+    let unit = into_dispatcher_owned!(());
+    let dispatcher = match Box::<dyn Display + Send>::try_from(unit) {
+        Ok(_) => unreachable!(),
+        Err(dispatcher) => dispatcher,
+    };
+    assert_eq!(dispatcher.variant_name(), "DispatcherDebug");
+
+    let result: Result<Box<dyn Debug>, DispatcherOwned> = into_dispatcher_owned!(Nothing).try_into();
+    assert!(result.err().unwrap().is_none());
+}
@@ -0,0 +1,21 @@
+use std::fmt::{Debug, Display};
+use trait_mux::trait_mux;
+
+trait_mux!(Dispatcher(Debug, Display));
+
+mod types {
+    use super::*;
+
+    trait_mux!(Known types(String, i32) traits(Debug, Display));
+}
+
+fn main() {
+    let value = 5;
+    let dispatcher = into_dispatcher!(value);
+    assert_eq!(dispatcher.variant_name(), "DispatcherDebugDisplay");
+    assert_eq!(dispatcher.as_display().to_string(), "5");
+
+    let known = types::Known::from(5);
+    assert_eq!(known.variant_name(), "I32");
+    assert!(known.try_as_debug().is_some());
+}
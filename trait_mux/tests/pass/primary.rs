@@ -0,0 +1,27 @@
+use std::fmt::{Debug, Display};
+use trait_mux::trait_mux;
+
+trait_mux!(#[trait_mux(primary = Debug)] Dispatcher{Display, Debug});
+trait_mux!(#[trait_mux(primary = std::fmt::Display, bitset)] Detector{Debug, Display});
+trait_mux!(#[trait_mux(primary = Display, opaque)] Hidden{Debug, Display});
+
+fn main() {
+    let (number, unit) = (5, ());
+
+    let dispatcher = into_dispatcher!(number);
+    assert_eq!(
+        format!("{:?}", dispatcher.as_primary()),
+        format!("{:?}", dispatcher.try_as_debug())
+    );
+    assert!(into_dispatcher!(unit).as_primary().is_some());
+
+    struct Nothing;
+    let nothing = Nothing;
+    assert!(into_dispatcher!(nothing).as_primary().is_none());
+    assert!(into_dispatcher!(nothing).try_as_debug().is_none());
+
+    assert_eq!(into_detector!(number).as_primary().unwrap().to_string(), "5");
+    assert!(into_detector!(unit).as_primary().is_none());
+
+    assert_eq!(into_hidden!(number).as_primary().unwrap().to_string(), "5");
+}
@@ -0,0 +1,54 @@
+use std::fmt::{Debug, Display};
+use trait_mux::trait_mux;
+
+pub trait Named {
+    fn name(&self) -> &'static str;
+}
+
+trait_mux!(#[trait_mux(mode = priority_chain)] Dispatcher{Display, Debug, Named});
+trait_mux!(#[trait_mux(mode = priority_chain, self_test, strict)] Writer{mut std::io::Write, Debug});
+
+#[derive(Debug)]
+struct DebugNamed;
+
+impl Named for DebugNamed {
+    fn name(&self) -> &'static str {
+        "debug"
+    }
+}
+
+struct OnlyNamed;
+
+impl Named for OnlyNamed {
+    fn name(&self) -> &'static str {
+        "only"
+    }
+}
+
+struct Nothing;
+
+fn main() {
+    // Only the first listed trait is kept, even if the value implements the others.
+    let number = 5;
+    let dispatcher = into_dispatcher!(number);
+    assert!(matches!(dispatcher, Dispatcher::DispatcherDisplay(_)));
+    assert_eq!(dispatcher.try_as_display().unwrap().to_string(), "5");
+    assert!(dispatcher.try_as_debug().is_none());
+
+    let vec = vec![1];
+    assert!(matches!(into_dispatcher!(vec), Dispatcher::DispatcherDebug(_)));
+    assert!(matches!(into_dispatcher!(DebugNamed), Dispatcher::DispatcherDebug(_)));
+    let only = into_dispatcher!(OnlyNamed);
+    assert_eq!(only.try_as_named().unwrap().name(), "only");
+    assert!(matches!(into_dispatcher!(Nothing), Dispatcher::DispatcherNone));
+
+    // The variants follow the declaration order, with the variant without traits last.
+    assert_eq!(Dispatcher::VARIANT_COUNT, 4);
+    assert_eq!(into_dispatcher!(number).discriminant(), 0);
+    assert_eq!(into_dispatcher!(Nothing).discriminant(), 3);
+
+    let mut buffer = Vec::new();
+    let mut writer = into_writer!(buffer);
+    writer.try_as_write_mut().unwrap().write_all(b"data").unwrap();
+    assert_eq!(buffer, b"data");
+}
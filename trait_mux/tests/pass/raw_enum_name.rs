@@ -0,0 +1,28 @@
+use std::fmt::{Debug, Display};
+use trait_mux::trait_mux;
+
+trait_mux!(
+    #[trait_mux(owned, strict, expose_table, collection)]
+    r#match { Debug, Display }
+);
+
+fn main() {
+    let number = 5;
+    let dispatcher: r#match = into_match!(number);
+    assert_eq!(dispatcher.variant_name(), "matchDebugDisplay");
+    assert!(matches!(dispatcher, r#match::matchDebugDisplay(_)));
+    assert!(matches_variant_match!(dispatcher, [Debug, Display]));
+    assert_eq!(
+        into_match_strict!(number)
+            .try_as_debug()
+            .map(|d| format!("{:?}", d)),
+        Some("5".into())
+    );
+
+    let owned: matchOwned = into_match_owned!(String::from("text"));
+    assert_eq!(owned.try_as_display().unwrap().to_string(), "text");
+
+    let mut collection = matchVec::new();
+    push_match!(collection, number);
+    assert_eq!(collection.len(), 1);
+}
@@ -0,0 +1,30 @@
+use trait_mux::trait_mux;
+
+#[allow(non_camel_case_types)]
+trait r#match {
+    fn matched(&self) -> bool;
+}
+
+impl r#match for u8 {
+    fn matched(&self) -> bool {
+        true
+    }
+}
+
+trait Ärger {}
+
+impl Ärger for u8 {}
+impl Ärger for &str {}
+
+trait_mux!(Dispatcher{r#match, Ärger, std::fmt::Debug});
+
+fn main() {
+    let number = 1u8;
+    let dispatcher = into_dispatcher!(number);
+    assert_eq!(dispatcher.variant_name(), "DispatcherDebugmatchÄrger");
+    assert!(dispatcher.try_as_match().unwrap().matched());
+    assert!(dispatcher.try_as_ärger().is_some());
+
+    let text = "text";
+    assert_eq!(into_dispatcher!(text).variant_name(), "DispatcherDebugÄrger");
+}
@@ -0,0 +1,41 @@
+use std::fmt::{Debug, Display};
+use trait_mux::trait_mux;
+
+mod detail {
+    use super::*;
+
+    pub trait Named {
+        fn name(&self) -> &'static str;
+    }
+
+    impl Named for i32 {
+        fn name(&self) -> &'static str {
+            "i32"
+        }
+    }
+
+    trait_mux!(#[trait_mux(reexport_at = crate)] Dispatcher{Debug, Display, Named});
+    trait_mux!(#[trait_mux(reexport_at = pub, scoped_macros, owned)] Printer{Display});
+
+    pub fn dispatch(value: &i32) -> Dispatcher<'_> {
+        into_dispatcher!((*value))
+    }
+
+    pub fn printer(value: String) -> PrinterOwned {
+        into_printer_owned!(value)
+    }
+}
+
+// The items are re-exported into the module the macro is invoked in.
+use detail::{Dispatcher, PrinterOwned};
+
+fn main() {
+    let number = 5;
+    let dispatcher: Dispatcher = detail::dispatch(&number);
+    assert_eq!(dispatcher.try_as_named().unwrap().name(), "i32");
+    assert_eq!(Dispatcher::traits(), ["Debug", "Display", "Named"]);
+
+    let printer: PrinterOwned = detail::printer("text".to_string());
+    assert_eq!(printer.try_as_display().unwrap().to_string(), "text");
+    assert_eq!(detail::Printer::traits(), ["Display"]);
+}
@@ -0,0 +1,42 @@
+use std::fmt::{self, Binary, Debug, Display, LowerExp, LowerHex, Octal, Pointer, UpperHex};
+use trait_mux::trait_mux;
+
+trait_mux!(#[trait_mux(repr = u8)] Dispatcher{Debug, Display});
+
+// 7 traits make 128 variants, exactly the number of non-negative `i8` discriminants.
+trait_mux!(#[trait_mux(repr = i8)] Formatter{Debug, Display, Binary, Octal, LowerHex, UpperHex, Pointer});
+
+// 8 traits make 256 variants, exactly the number of `u8` discriminants.
+trait_mux!(#[trait_mux(repr = u8)] Numeric{Debug, Display, Binary, Octal, LowerHex, UpperHex, LowerExp, Pointer});
+
+struct DisplayOnly;
+
+impl Display for DisplayOnly {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "display only")
+    }
+}
+
+struct Nothing;
+
+fn main() {
+    let number = 5;
+    let display_only = DisplayOnly;
+    let nothing = Nothing;
+
+    assert_eq!(Dispatcher::VARIANT_COUNT, 4);
+    assert_eq!(Formatter::VARIANT_COUNT, 128);
+    assert_eq!(Numeric::VARIANT_COUNT, 256);
+
+    let dispatchers = [
+        into_dispatcher!(number),
+        into_dispatcher!(display_only),
+        into_dispatcher!(nothing),
+    ];
+
+    for dispatcher in &dispatchers {
+        // With a primitive representation, the enum starts with its discriminant.
+        let tag = unsafe { *(dispatcher as *const Dispatcher as *const u8) };
+        assert_eq!(u16::from(tag), dispatcher.discriminant());
+    }
+}
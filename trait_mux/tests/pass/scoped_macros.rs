@@ -0,0 +1,26 @@
+mod numbers {
+    use std::fmt::{Debug, Display};
+    use trait_mux::trait_mux;
+
+    trait_mux!(#[trait_mux(scoped_macros)] Dispatcher{Debug, Display});
+
+    pub fn variant(number: u32) -> &'static str {
+        into_dispatcher!(number).variant_name()
+    }
+}
+
+mod binaries {
+    use trait_mux::trait_mux;
+
+    // Same enum and macro names as in `numbers`, without clashing at the crate root.
+    trait_mux!(#[trait_mux(scoped_macros)] Dispatcher{std::fmt::Binary});
+
+    pub fn variant(number: u32) -> &'static str {
+        crate::binaries::into_dispatcher!(number).variant_name()
+    }
+}
+
+fn main() {
+    assert_eq!(numbers::variant(5), "DispatcherDebugDisplay");
+    assert_eq!(binaries::variant(5), "DispatcherBinary");
+}
@@ -0,0 +1,20 @@
+use std::fmt::{Debug, Display};
+use trait_mux::trait_mux;
+
+// Two enums in the same module listing the same traits, whose generated items must not collide.
+trait_mux!(Dispatcher{Debug, Display});
+trait_mux!(Router{Debug, Display, ToString});
+trait_mux!(#[trait_mux(owned)] Handler{Debug});
+
+fn main() {
+    let number = 5;
+
+    let dispatcher = into_dispatcher!(number);
+    let router = into_router!(number);
+    let handler = into_handler!(number);
+
+    assert_eq!(dispatcher.variant_name(), "DispatcherDebugDisplay");
+    assert!(router.try_as_to_string().is_some());
+    assert_eq!(handler.variant_name(), "HandlerDebug");
+    assert_eq!(format!("{:?}", into_handler_owned!(number).as_debug()), "5");
+}
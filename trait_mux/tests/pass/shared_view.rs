@@ -0,0 +1,28 @@
+use std::fmt::Debug;
+use std::io::Write;
+use trait_mux::trait_mux;
+
+trait_mux!(#[trait_mux(shared_view)] Writer{mut Write, Debug});
+
+struct Nothing;
+
+fn main() {
+    let mut buffer = Vec::new();
+    let mut writer = into_writer!(buffer);
+
+    writer.as_write_mut().write_all(b"data").unwrap();
+
+    // The view reborrows the enum, which can be written to again once it's dropped.
+    let shared = writer.as_shared();
+    let copy = shared;
+    assert_eq!(format!("{:?}", shared.try_as_debug().unwrap()), "[100, 97, 116, 97]");
+    assert!(copy.try_as_write().is_some());
+    assert_eq!(shared.variant_name(), "WriterDebugWrite");
+
+    writer.as_write_mut().write_all(b"!").unwrap();
+    assert_eq!(format!("{:?}", writer.as_shared().as_debug()), "[100, 97, 116, 97, 33]");
+
+    let mut nothing = Nothing;
+    let writer = into_writer!(nothing);
+    assert!(writer.as_shared().is_none());
+}
@@ -0,0 +1,34 @@
+use std::fmt::{Debug, Display};
+use trait_mux::trait_mux;
+
+trait_mux!(#[trait_mux(show_internals)] Dispatcher{Debug, Display});
+trait_mux!(#[trait_mux(show_internals, bitset)] Detector{Debug, Display});
+
+fn main() {
+    // The state left by the selection steps and the tag it selects are the intermediate steps of
+    // the into macro.
+    let value = 5;
+    let state = (&&WrapDispatcher(&value))
+        .into_dispatcher_display_step((&&WrapDispatcher(&value)).into_dispatcher_debug_step(()));
+    assert_eq!(
+        format!("{:?}", state),
+        "DispatcherDisplayStep(DispatcherDebugStep(()))"
+    );
+    let tag = state.into_dispatcher_tag();
+    assert_eq!(format!("{:?}", tag), "DispatcherDebugDisplayTag");
+
+    struct Nothing;
+    let nothing = Nothing;
+    let state = (&&WrapDispatcher(&nothing)).into_dispatcher_display_step(
+        (&&WrapDispatcher(&nothing)).into_dispatcher_debug_step(()),
+    );
+    assert_eq!(
+        format!("{:?}", state),
+        "DispatcherDisplayNoneStep(DispatcherDebugNoneStep(()))"
+    );
+    let tag = state.into_dispatcher_tag();
+    assert_eq!(format!("{:?}", tag), "DispatcherNoneTag");
+
+    let tag = (&&WrapDetector(&nothing)).into_detector_debug_tag();
+    assert_eq!(format!("{:?}", tag), "DetectorDebugNoneTag");
+}
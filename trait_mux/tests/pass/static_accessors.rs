@@ -0,0 +1,25 @@
+use std::any::Any;
+use std::fmt::{Debug, Display};
+use trait_mux::trait_mux;
+
+trait_mux!(#[trait_mux(static)] Dispatcher{Debug, Display, Any});
+
+static TEXT: &str = "text";
+static NUMBER: i32 = 5;
+
+// Requires a `'static` bound trait object, which `try_as_debug` can't provide, as its trait object is
+// only bound by the borrow of the enum.
+fn store_debug(debug: &(dyn Debug + 'static)) -> String {
+    format!("{:?}", debug)
+}
+
+fn main() {
+    let text: Dispatcher<'static> = into_dispatcher!(TEXT);
+    let debug: &(dyn Debug + 'static) = text.try_as_debug_static().unwrap();
+    assert_eq!(store_debug(debug), r#""text""#);
+    assert_eq!(text.try_as_display_static().unwrap().to_string(), "text");
+
+    let number: Dispatcher<'static> = into_dispatcher!(NUMBER);
+    let any = number.try_as_any_static().unwrap();
+    assert_eq!(any.downcast_ref::<i32>(), Some(&5));
+}
@@ -0,0 +1,20 @@
+use std::fmt::{Debug, Display};
+use trait_mux::trait_mux;
+
+trait_mux!(#[trait_mux(strict)] Dispatcher{Debug, Display});
+
+fn main() {
+    let number = 5;
+    let unit = ();
+
+    assert_eq!(
+        into_dispatcher_strict!(number).variant_name(),
+        "DispatcherDebugDisplay"
+    );
+    assert_eq!(into_dispatcher_strict!(unit).variant_name(), "DispatcherDebug");
+
+    // The regular macro still accepts values implementing none of the traits.
+    struct Nothing;
+    let nothing = Nothing;
+    assert_eq!(into_dispatcher!(nothing).variant_name(), "DispatcherNone");
+}
@@ -0,0 +1,38 @@
+use std::fmt::{Debug, Display};
+use trait_mux::trait_mux;
+
+trait_mux!(Dispatcher{Debug, Display});
+
+struct DisplayOnly;
+
+impl Display for DisplayOnly {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "display only")
+    }
+}
+
+struct Nothing;
+
+fn main() {
+    let number = 5;
+    let vec = vec![1, 2];
+    let display_only = DisplayOnly;
+    let nothing = Nothing;
+
+    let values = vec![
+        into_dispatcher!(number),
+        into_dispatcher!(display_only),
+        into_dispatcher!(vec),
+        into_dispatcher!(nothing),
+    ];
+
+    let debugs: Vec<_> = Dispatcher::debug_objects(&values)
+        .map(|v| format!("{:?}", v))
+        .collect();
+    assert_eq!(debugs, ["5", "[1, 2]"]);
+
+    let displays: Vec<_> = Dispatcher::display_objects(values.iter())
+        .map(|v| v.to_string())
+        .collect();
+    assert_eq!(displays, ["5", "display only"]);
+}
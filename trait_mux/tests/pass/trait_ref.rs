@@ -0,0 +1,33 @@
+use std::fmt::{Debug, Display};
+use trait_mux::trait_mux;
+
+trait_mux!(#[trait_mux(trait_ref = MyTraitRef)] Dispatcher{Debug, Display});
+
+// Both enums list the same traits, so the second one reuses the enum defined by the first.
+trait_mux!(#[trait_mux(trait_ref = use MyTraitRef)] OtherDispatcher{Display, Debug});
+
+fn describe(r: MyTraitRef) -> String {
+    match r {
+        MyTraitRef::Debug(v) => format!("{:?}", v),
+        MyTraitRef::Display(v) => v.to_string(),
+    }
+}
+
+fn main() {
+    let (text, number) = ("text", 5);
+
+    assert_eq!(
+        into_dispatcher!(text).map(describe).as_deref(),
+        Some("\"text\"")
+    );
+    assert_eq!(
+        into_other_dispatcher!(number).map(describe).as_deref(),
+        Some("5")
+    );
+
+    let views: Vec<String> = into_other_dispatcher!(text)
+        .iter_traits()
+        .map(describe)
+        .collect();
+    assert_eq!(views, ["\"text\"", "text"]);
+}
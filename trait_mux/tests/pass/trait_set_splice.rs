@@ -0,0 +1,39 @@
+use std::fmt::{Debug, Display};
+use trait_mux::{trait_mux, trait_mux_traits};
+
+trait_mux_traits!(fmt_traits = {Debug, Display});
+trait_mux_traits!(error_traits = (std::error::Error,));
+
+trait Numeric {}
+impl Numeric for i32 {}
+
+// A set is spliced into the list along with literal traits, wherever it's placed.
+trait_mux!(Dispatcher{@fmt_traits, Numeric});
+trait_mux!(#[trait_mux(bitset)] Detector(Numeric, @fmt_traits));
+trait_mux!(Checker{@error_traits, Numeric, @fmt_traits});
+trait_mux!(Values types{i32, String} traits{@fmt_traits, Numeric});
+
+fn main() {
+    let (number, text) = (5i32, String::from("text"));
+
+    let dispatcher = into_dispatcher!(number);
+    assert_eq!(dispatcher.variant_name(), "DispatcherDebugDisplayNumeric");
+    assert_eq!(
+        into_dispatcher!(text).variant_name(),
+        "DispatcherDebugDisplay"
+    );
+    assert_eq!(Dispatcher::traits(), ["Debug", "Display", "Numeric"]);
+
+    assert!(into_detector!(number).try_as_numeric().is_some());
+    assert!(into_detector!(text).try_as_numeric().is_none());
+
+    let error = std::fmt::Error;
+    assert_eq!(
+        into_checker!(error).variant_name(),
+        "CheckerDebugDisplayError"
+    );
+    assert_eq!(Checker::traits(), ["Debug", "Display", "Error", "Numeric"]);
+
+    assert!(Values::from(5).try_as_numeric().is_some());
+    assert!(Values::from(text).try_as_numeric().is_none());
+}
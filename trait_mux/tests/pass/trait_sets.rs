@@ -0,0 +1,24 @@
+use std::fmt::{Debug, Display};
+use trait_mux::{trait_mux, trait_mux_traits};
+
+trait_mux_traits!(fmt_traits = {Debug, Display});
+
+// Both enums list the traits of the set, along with their own options.
+trait_mux!(Dispatcher = @fmt_traits);
+trait_mux!(#[trait_mux(owned)] OwnedDispatcher = @fmt_traits);
+
+fn main() {
+    let (number, unit) = (5, ());
+
+    let dispatcher = into_dispatcher!(number);
+    assert_eq!(dispatcher.variant_name(), "DispatcherDebugDisplay");
+    assert_eq!(dispatcher.as_display().to_string(), "5");
+    assert_eq!(into_dispatcher!(unit).variant_name(), "DispatcherDebug");
+
+    let owned = into_owned_dispatcher_owned!(String::from("text"));
+    assert_eq!(owned.as_display().to_string(), "text");
+    assert_eq!(
+        into_owned_dispatcher!(unit).variant_name(),
+        "OwnedDispatcherDebug"
+    );
+}
@@ -0,0 +1,54 @@
+use std::fmt::{self, Debug, Display};
+use trait_mux::trait_mux;
+
+trait_mux!(Dispatcher{Debug, Display, std::error::Error});
+
+#[derive(Debug)]
+struct Failure;
+
+impl Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "failure")
+    }
+}
+
+impl std::error::Error for Failure {}
+
+struct Nothing;
+
+fn main() {
+    let failure = Failure;
+    let number = 5;
+    let unit = ();
+    let nothing = Nothing;
+
+    let dispatchers = [
+        into_dispatcher!(failure),
+        into_dispatcher!(number),
+        into_dispatcher!(unit),
+        into_dispatcher!(nothing),
+    ];
+
+    for dispatcher in &dispatchers {
+        let bitmask = dispatcher.traits_bitmask();
+        assert_eq!(
+            bitmask & Dispatcher::DEBUG_BIT != 0,
+            dispatcher.try_as_debug().is_some()
+        );
+        assert_eq!(
+            bitmask & Dispatcher::DISPLAY_BIT != 0,
+            dispatcher.try_as_display().is_some()
+        );
+        assert_eq!(
+            bitmask & Dispatcher::ERROR_BIT != 0,
+            dispatcher.try_as_error().is_some()
+        );
+        assert_eq!(bitmask, dispatcher.traits_mask());
+    }
+
+    // The bits follow the sorted trait order.
+    assert_eq!(Dispatcher::DEBUG_BIT, 1 << 0);
+    assert_eq!(Dispatcher::DISPLAY_BIT, 1 << 1);
+    assert_eq!(Dispatcher::ERROR_BIT, 1 << 2);
+    assert_eq!(dispatchers[3].traits_bitmask(), 0);
+}
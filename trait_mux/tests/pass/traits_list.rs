@@ -0,0 +1,20 @@
+use std::fmt::{Debug, Display};
+use trait_mux::trait_mux;
+
+trait_mux!(Dispatcher{Display, std::error::Error, Debug});
+trait_mux!(#[trait_mux(bitset)] Detector{Display, Debug});
+trait_mux!(#[trait_mux(opaque)] Hidden{mut std::io::Write as IoWrite, Debug});
+trait_mux!(Values types{String, i32} traits{Display, Debug});
+
+fn main() {
+    // The traits are listed in sorted order, regardless of the declaration order.
+    assert_eq!(Dispatcher::traits(), ["Debug", "Display", "Error"]);
+    assert_eq!(Detector::traits(), ["Debug", "Display"]);
+    assert_eq!(Hidden::traits(), ["Debug", "IoWrite"]);
+    assert_eq!(Values::traits(), ["Debug", "Display"]);
+
+    // Unlike `trait_names()`, the list doesn't depend on the variant.
+    struct Nothing;
+    let nothing = Nothing;
+    assert!(into_dispatcher!(nothing).trait_names().is_empty());
+}
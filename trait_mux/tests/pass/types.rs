@@ -0,0 +1,36 @@
+use std::fmt::{self, Debug, Display};
+use trait_mux::trait_mux;
+
+pub struct DisplayOnly;
+
+impl Display for DisplayOnly {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "display only")
+    }
+}
+
+trait_mux!(Dispatcher types{String, i32, DisplayOnly} traits{Debug, Display, std::error::Error});
+
+fn main() {
+    let dispatchers = [
+        Dispatcher::from(String::from("text")),
+        Dispatcher::from(5),
+        Dispatcher::DisplayOnly(DisplayOnly),
+    ];
+
+    assert_eq!(Dispatcher::VARIANT_COUNT, 3);
+    assert_eq!(dispatchers[0].variant_name(), "String");
+    assert_eq!(dispatchers[1].variant_name(), "I32");
+
+    assert_eq!(format!("{:?}", dispatchers[0].try_as_debug().unwrap()), "\"text\"");
+    assert_eq!(format!("{:?}", dispatchers[1].try_as_debug().unwrap()), "5");
+    assert!(dispatchers[2].try_as_debug().is_none());
+
+    let displayed: Vec<_> = dispatchers
+        .iter()
+        .map(|d| d.try_as_display().unwrap().to_string())
+        .collect();
+    assert_eq!(displayed, ["text", "5", "display only"]);
+
+    assert!(dispatchers.iter().all(|d| d.try_as_error().is_none()));
+}
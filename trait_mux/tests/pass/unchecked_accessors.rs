@@ -0,0 +1,41 @@
+use std::fmt::{Debug, Display, Write};
+use trait_mux::trait_mux;
+
+trait_mux!(#[trait_mux(unchecked_accessors)] Dispatcher{Debug, Display, mut Write});
+
+fn main() {
+    let mut number = 5;
+    let mut string = String::new();
+
+    {
+        let mut values = [into_dispatcher!(number), into_dispatcher!(string)];
+
+        let mut debugs = vec![];
+        for value in &values {
+            if value.try_as_debug().is_some() {
+                // SAFETY: the value was just checked to implement `Debug`.
+                debugs.push(format!("{:?}", unsafe { value.as_debug_unchecked() }));
+            }
+        }
+        assert_eq!(debugs, ["5", r#""""#]);
+
+        for value in &mut values {
+            if value.try_as_write_mut().is_some() {
+                // SAFETY: the value was just checked to implement `Write`.
+                let writer = unsafe { value.as_write_mut_unchecked() };
+                writer.write_str("hello").unwrap();
+            }
+        }
+    }
+    assert_eq!(string, "hello");
+
+    // Debug builds still check the variant, so misuse panics instead of being undefined behavior.
+    if cfg!(debug_assertions) {
+        let result = std::panic::catch_unwind(move || {
+            let dispatcher = into_dispatcher!(number);
+            // SAFETY: not upheld on purpose, caught by the debug assertion.
+            let _ = unsafe { dispatcher.as_write_unchecked() };
+        });
+        assert!(result.is_err());
+    }
+}
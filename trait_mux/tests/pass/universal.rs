@@ -0,0 +1,44 @@
+use std::fmt::{Debug, Display};
+use trait_mux::trait_mux;
+
+pub trait Universal {
+    fn describe(&self) -> &'static str {
+        "anything"
+    }
+}
+
+impl<T: ?Sized> Universal for T {}
+
+trait_mux!(#[trait_mux(universal = Universal)] Dispatcher{Debug, Display});
+
+struct Nothing;
+
+fn main() {
+    let number = 5;
+    let nothing = Nothing;
+
+    let dispatcher = into_dispatcher!(number);
+    assert_eq!(dispatcher.variant_name(), "DispatcherDebugDisplayUniversal");
+    assert_eq!(dispatcher.as_universal().describe(), "anything");
+
+    // Values implementing none of the listed traits still implement the universal one.
+    let dispatcher = into_dispatcher!(nothing);
+    assert_eq!(dispatcher.variant_name(), "DispatcherUniversal");
+    assert!(dispatcher.try_as_universal().is_some());
+    assert!(dispatcher.try_as_debug().is_none());
+
+    // There is no variant without traits, so matching the universal variants is exhaustive.
+    match dispatcher {
+        Dispatcher::DispatcherDebugDisplayUniversal(_)
+        | Dispatcher::DispatcherDebugUniversal(_)
+        | Dispatcher::DispatcherDisplayUniversal(_)
+        | Dispatcher::DispatcherUniversal(_) => {}
+    }
+    assert_eq!(Dispatcher::VARIANT_COUNT, 4);
+    for discriminant in 0..Dispatcher::VARIANT_COUNT as u16 {
+        assert_ne!(
+            Dispatcher::variant_name_of(discriminant),
+            Some("DispatcherNone")
+        );
+    }
+}
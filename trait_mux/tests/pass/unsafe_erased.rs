@@ -0,0 +1,35 @@
+use std::fmt::{Debug, Display};
+use trait_mux::trait_mux;
+
+trait_mux!(#[trait_mux(unsafe_erased)] Dispatcher{Debug, Display});
+
+struct Nothing;
+
+// The erased references don't borrow the values, so they can be stored in a cache without a
+// lifetime.
+#[derive(Default)]
+struct Cache {
+    entries: Vec<DispatcherDynRef>,
+}
+
+fn main() {
+    let number = 5;
+    let vec = vec![1, 2];
+    let nothing = Nothing;
+
+    let mut cache = Cache::default();
+    cache.entries.push(into_dispatcher!(number).as_dyn_ref());
+    cache.entries.push(into_dispatcher!(vec).as_dyn_ref());
+    cache.entries.push(into_dispatcher!(nothing).as_dyn_ref());
+
+    assert_eq!(cache.entries[0].data(), (&number as *const i32).cast());
+    assert!(cache.entries[2].data().is_null());
+
+    // SAFETY: the values are still alive and not mutably borrowed.
+    unsafe {
+        assert_eq!(cache.entries[0].try_as_display().unwrap().to_string(), "5");
+        assert_eq!(format!("{:?}", cache.entries[1].try_as_debug().unwrap()), "[1, 2]");
+        assert!(cache.entries[1].try_as_display().is_none());
+        assert!(cache.entries[2].try_as_debug().is_none());
+    }
+}
@@ -0,0 +1,28 @@
+use std::fmt::{Debug, Display};
+use trait_mux::trait_mux;
+
+trait_mux!(Dispatcher{Debug, Display});
+
+fn main() {
+    // Unsized values can't be coerced into trait objects, so they're dispatched through their
+    // references, which implement the traits by forwarding them.
+    let text: &str = "text";
+    let bytes: &[u8] = &[1, 2];
+    let boxed: Box<str> = "boxed".into();
+    let debug: &dyn Debug = &5;
+
+    let dispatcher = into_dispatcher!(text);
+    assert_eq!(dispatcher.variant_name(), "DispatcherDebugDisplay");
+    assert_eq!(dispatcher.as_display().to_string(), "text");
+
+    let dispatcher = into_dispatcher!(bytes);
+    assert_eq!(dispatcher.variant_name(), "DispatcherDebug");
+    assert_eq!(format!("{:?}", dispatcher.as_debug()), "[1, 2]");
+
+    let dispatcher = into_dispatcher!(boxed);
+    assert_eq!(dispatcher.as_display().to_string(), "boxed");
+
+    let dispatcher = into_dispatcher!(debug);
+    assert_eq!(dispatcher.variant_name(), "DispatcherDebug");
+    assert_eq!(format!("{:?}", dispatcher.as_debug()), "5");
+}
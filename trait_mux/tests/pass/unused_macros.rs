@@ -0,0 +1,26 @@
+#![deny(warnings)]
+
+use std::fmt::{Debug, Display};
+use trait_mux::trait_mux;
+
+// None of the generated macros are used, only the enums.
+trait_mux!(#[trait_mux(strict, owned)] Dispatcher{Debug, Display});
+
+mod scoped {
+    use super::*;
+
+    trait_mux!(#[trait_mux(scoped_macros, strict, owned)] Dispatcher{Debug, Display});
+
+    pub fn variant_name(dispatcher: &Dispatcher) -> &'static str {
+        dispatcher.variant_name()
+    }
+}
+
+fn main() {
+    assert!(Dispatcher::none().is_none());
+    assert_eq!(
+        scoped::variant_name(&scoped::Dispatcher::none()),
+        "DispatcherNone"
+    );
+    assert!(DispatcherOwned::DispatcherNone.try_as_debug().is_none());
+}
@@ -0,0 +1,47 @@
+use std::fmt::Debug as Dbg;
+use std::fmt::{self, Display as Disp};
+use trait_mux::trait_mux;
+
+// The traits are named by the `use` aliases, in the generated names as well as the trait objects.
+trait_mux!(#[trait_mux(owned)] Dispatcher{Dbg, Disp});
+trait_mux!(#[trait_mux(bitset)] Detector{Dbg, Disp});
+
+struct DisplayOnly;
+
+impl Disp for DisplayOnly {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "display only")
+    }
+}
+
+mod elsewhere {
+    // The into macro expands here, where the aliases aren't in scope, only the generated items.
+    use super::{DispatcherDbgMatch, DispatcherDispMatch, WrapDispatcher, into_dispatcher};
+
+    pub fn is_debug(value: &str) -> bool {
+        into_dispatcher!(value).try_as_dbg().is_some()
+    }
+}
+
+fn main() {
+    let number = 5;
+    let display_only = DisplayOnly;
+
+    let dispatcher = into_dispatcher!(number);
+    assert_eq!(dispatcher.variant_name(), "DispatcherDbgDisp");
+    assert_eq!(format!("{:?}", dispatcher.as_dbg()), "5");
+    assert_eq!(dispatcher.as_disp().to_string(), "5");
+
+    let dispatcher = into_dispatcher!(display_only);
+    assert_eq!(dispatcher.variant_name(), "DispatcherDisp");
+    assert!(dispatcher.try_as_dbg().is_none());
+
+    let owned = into_dispatcher_owned!(DisplayOnly);
+    assert_eq!(owned.as_disp().to_string(), "display only");
+
+    let detector = into_detector!(display_only);
+    assert!(detector.try_as_dbg().is_none());
+    assert!(detector.try_as_disp().is_some());
+
+    assert!(elsewhere::is_debug("text"));
+}
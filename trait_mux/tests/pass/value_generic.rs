@@ -0,0 +1,43 @@
+use std::fmt::Debug;
+use trait_mux::trait_mux;
+
+// A type named like the generic parameters usually are.
+#[derive(Debug)]
+struct T;
+
+#[allow(non_camel_case_types)]
+#[derive(Debug)]
+struct __T;
+
+struct Matcher;
+
+impl PartialEq<T> for Matcher {
+    fn eq(&self, _: &T) -> bool {
+        true
+    }
+}
+
+trait Accepts<X> {}
+impl Accepts<__T> for Matcher {}
+
+// The trait paths name the user's `T`, not the generic parameter of the implementations, so
+// `i32: PartialEq<i32>` isn't mistaken for `i32: PartialEq<T>`.
+trait_mux!(Dispatcher{PartialEq<T>, Debug});
+trait_mux!(#[trait_mux(bitset)] Detector{PartialEq<T>, Debug});
+trait_mux!(#[trait_mux(value_generic = Value, owned)] Renamed{Accepts<__T>, Debug});
+
+fn main() {
+    let matcher = Matcher;
+    let number = 1i32;
+
+    let dispatcher = into_dispatcher!(matcher);
+    assert!(dispatcher.as_partial_eq() == &T);
+    assert!(into_dispatcher!(number).try_as_partial_eq().is_none());
+
+    assert!(into_detector!(matcher).try_as_partial_eq().is_some());
+    assert!(into_detector!(number).try_as_partial_eq().is_none());
+
+    assert!(into_renamed!(matcher).try_as_accepts().is_some());
+    assert!(into_renamed!(number).try_as_accepts().is_none());
+    assert!(into_renamed_owned!(Matcher).try_as_accepts().is_some());
+}
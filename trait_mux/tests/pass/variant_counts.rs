@@ -0,0 +1,19 @@
+use std::fmt::{Debug, Display};
+use trait_mux::trait_mux;
+
+trait_mux!(Dispatcher{Debug, Display, std::error::Error});
+trait_mux!(#[trait_mux(opaque)] Hidden{Debug, Display, std::error::Error});
+
+// The counts are constants, usable in const contexts.
+const _: () = assert!(Dispatcher::DEBUG_VARIANT_COUNT == 4);
+
+fn main() {
+    // Each trait is implemented by half of the 8 trait combinations.
+    assert_eq!(Dispatcher::VARIANT_COUNT, 8);
+    assert_eq!(Dispatcher::DEBUG_VARIANT_COUNT, 4);
+    assert_eq!(Dispatcher::DISPLAY_VARIANT_COUNT, 4);
+    assert_eq!(Dispatcher::ERROR_VARIANT_COUNT, 4);
+
+    assert_eq!(Hidden::DEBUG_VARIANT_COUNT, 4);
+    assert_eq!(Hidden::ERROR_VARIANT_COUNT, 4);
+}
@@ -0,0 +1,43 @@
+use std::fmt::{Debug, Display};
+use trait_mux::trait_mux;
+
+trait_mux!(#[trait_mux(variant_macro)] Dispatcher{Debug, Display});
+trait_mux!(#[trait_mux(variant_macro)] Writer{Display, std::fmt::Write as FmtWrite, Debug});
+
+macro_rules! names {
+    ($($name:ident),*) => {
+        [$(stringify!($name)),*]
+    };
+}
+
+macro_rules! writer {
+    ($($path:path),*) => {
+        fn write_debug<T: $($path +)* ?Sized>(target: &mut T) -> String {
+            target.write_str("text").unwrap();
+            format!("{target:?}")
+        }
+    };
+}
+
+macro_rules! bounded {
+    ($($name:ident),*) => {
+        fn describe<T: $($name +)* ?Sized>(value: &T) -> String {
+            format!("{value:?} {value}")
+        }
+    };
+}
+
+// The traits of a variant can be used as the bounds of a generic function.
+dispatcher_variant_traits!(DispatcherDebugDisplay => bounded);
+// The traits are the listed paths rather than their aliases, so they can be used as bounds too.
+writer_variant_traits!(WriterDebugFmtWrite => writer);
+
+fn main() {
+    assert_eq!(dispatcher_variant_traits!(DispatcherDebugDisplay => names), ["Debug", "Display"]);
+    assert_eq!(dispatcher_variant_traits!(DispatcherDisplay => names), ["Display"]);
+    let none: [&str; 0] = dispatcher_variant_traits!(DispatcherNone => names);
+    assert_eq!(none, [""; 0]);
+
+    assert_eq!(describe("text"), "\"text\" text");
+    assert_eq!(write_debug(&mut String::new()), "\"text\"");
+}
@@ -0,0 +1,28 @@
+use std::fmt::{Debug, Display};
+use trait_mux::trait_mux;
+
+trait_mux!(#[trait_mux(with_accessors)] Dispatcher{Debug, Display});
+trait_mux!(#[trait_mux(with_accessors, bitset)] Detector{Debug, std::error::Error});
+trait_mux!(#[trait_mux(with_accessors, opaque)] Hidden{Display});
+
+struct Nothing;
+
+fn main() {
+    let number = 5;
+    let dispatcher = into_dispatcher!(number);
+    assert_eq!(
+        dispatcher.with_debug(|d| format!("{:?}", d)),
+        Some("5".to_string())
+    );
+    assert_eq!(dispatcher.with_display(|d| d.to_string().len()), Some(1));
+
+    let unit = ();
+    assert_eq!(into_dispatcher!(unit).with_display(|d| d.to_string()), None);
+
+    let nothing = Nothing;
+    assert!(into_detector!(nothing).with_debug(|_| ()).is_none());
+    assert_eq!(into_detector!(unit).with_debug(|d| format!("{:?}", d)), Some("()".to_string()));
+    assert_eq!(into_detector!(unit).with_error(|e| e.to_string()), None);
+
+    assert_eq!(into_hidden!(number).with_display(|d| d.to_string()), Some("5".to_string()));
+}
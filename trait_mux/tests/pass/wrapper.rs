@@ -0,0 +1,92 @@
+use std::cell::Cell;
+use std::fmt::{Debug, Display};
+use std::ops::{Deref, DerefMut};
+use trait_mux::trait_mux;
+
+thread_local! {
+    static ACTIVE_GUARDS: Cell<usize> = const { Cell::new(0) };
+}
+
+/// A guard counting how many values are currently dispatched.
+pub struct Guard<T>(T);
+
+impl<T> From<T> for Guard<T> {
+    fn from(value: T) -> Self {
+        ACTIVE_GUARDS.with(|c| c.set(c.get() + 1));
+        Guard(value)
+    }
+}
+
+impl<T> Drop for Guard<T> {
+    fn drop(&mut self) {
+        ACTIVE_GUARDS.with(|c| c.set(c.get() - 1));
+    }
+}
+
+impl<T> Deref for Guard<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for Guard<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+trait Writer {
+    fn write(&mut self, s: &str);
+}
+
+impl Writer for String {
+    fn write(&mut self, s: &str) {
+        self.push_str(s);
+    }
+}
+
+trait_mux!(#[trait_mux(wrapper = Guard)] Dispatcher{Debug, Display});
+trait_mux!(#[trait_mux(wrapper = Guard)] MutDispatcher{mut Writer, Debug});
+
+fn active_guards() -> usize {
+    ACTIVE_GUARDS.with(|c| c.get())
+}
+
+fn main() {
+    let number = 5;
+    {
+        let dispatcher = into_dispatcher!(number);
+        assert_eq!(active_guards(), 1);
+        assert_eq!(dispatcher.try_as_display().unwrap().to_string(), "5");
+        assert_eq!(format!("{:?}", dispatcher.try_as_debug().unwrap()), "5");
+
+        let display = dispatcher.map(|r| match r {
+            DispatcherTraitRef::Debug(v) => format!("{:?}", v),
+            DispatcherTraitRef::Display(v) => v.to_string(),
+        });
+        assert_eq!(display.as_deref(), Some("5"));
+    }
+    assert_eq!(active_guards(), 0);
+
+    let mut text = String::new();
+    {
+        let mut dispatcher = into_mut_dispatcher!(text);
+        dispatcher.try_as_writer_mut().unwrap().write("hello");
+        assert_eq!(
+            format!("{:?}", dispatcher.try_as_debug().unwrap()),
+            "\"hello\""
+        );
+        dispatcher.try_as_writer_mut().unwrap().write(" world");
+
+        // `Debug` comes first in the sorted trait order.
+        let debug = dispatcher.map(|r| match r {
+            MutDispatcherTraitRef::Debug(v) => format!("{:?}", v),
+            MutDispatcherTraitRef::Writer(_) => unreachable!(),
+        });
+        assert_eq!(debug.as_deref(), Some("\"hello world\""));
+    }
+    assert_eq!(text, "hello world");
+    assert_eq!(active_guards(), 0);
+}
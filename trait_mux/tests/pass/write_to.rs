@@ -0,0 +1,70 @@
+use std::fmt::{self, Debug, Display, Write as _};
+use std::io::Write;
+use trait_mux::trait_mux;
+
+trait_mux!(Dispatcher{Debug, Display, mut Write});
+trait_mux!(#[trait_mux(bitset)] Detector{std::fmt::Debug});
+trait_mux!(#[trait_mux(opaque)] Hidden{Display});
+
+/// A fixed-size buffer, writing without allocating.
+struct Buffer {
+    bytes: [u8; 16],
+    len: usize,
+}
+
+impl Buffer {
+    fn new() -> Self {
+        Self {
+            bytes: [0; 16],
+            len: 0,
+        }
+    }
+
+    fn as_str(&self) -> &str {
+        std::str::from_utf8(&self.bytes[..self.len]).unwrap()
+    }
+}
+
+impl fmt::Write for Buffer {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let end = self.len + s.len();
+        self.bytes.get_mut(self.len..end).ok_or(fmt::Error)?.copy_from_slice(s.as_bytes());
+        self.len = end;
+        Ok(())
+    }
+}
+
+struct Nothing;
+
+fn main() {
+    // `Display` is preferred over `Debug`.
+    let mut text = String::from("text");
+    let mut buffer = Buffer::new();
+    into_dispatcher!(text).write_to(&mut buffer).unwrap();
+    assert_eq!(buffer.as_str(), "text");
+
+    let mut unit = ();
+    let mut buffer = Buffer::new();
+    into_dispatcher!(unit).write_to(&mut buffer).unwrap();
+    assert_eq!(buffer.as_str(), "()");
+
+    // Nothing is written for a value implementing neither.
+    let mut nothing = Nothing;
+    let mut buffer = Buffer::new();
+    into_dispatcher!(nothing).write_to(&mut buffer).unwrap();
+    assert_eq!(buffer.as_str(), "");
+
+    // The writer's errors are returned.
+    let mut long = String::from("longer than sixteen bytes");
+    assert!(into_dispatcher!(long).write_to(&mut Buffer::new()).is_err());
+
+    let mut output = String::new();
+    into_detector!(text).write_to(&mut output).unwrap();
+    write!(output, " ").unwrap();
+    into_hidden!(text).write_to(&mut output).unwrap();
+    assert_eq!(output, "\"text\" text");
+
+    let mut sink = Vec::new();
+    into_dispatcher!(sink).as_write_mut().write_all(b"io").unwrap();
+    assert_eq!(sink, b"io");
+}
@@ -0,0 +1,36 @@
+//! The `self_test` option generates a `variant_selection` test in a `__*_self_test` module, run
+//! along with the tests of this file.
+
+use std::fmt::{Debug, Display};
+use trait_mux::trait_mux;
+
+trait_mux!(
+    #[trait_mux(self_test)]
+    Dispatcher { Debug, Display, std::error::Error }
+);
+
+trait_mux!(
+    #[trait_mux(self_test, mode = priority_chain)]
+    Chain { Display, std::error::Error, mut std::io::Write, Debug }
+);
+
+mod mutable {
+    use super::*;
+
+    trait_mux!(
+        #[trait_mux(self_test, scoped_macros, opaque)]
+        Writer { mut std::io::Write as IoWrite, Debug, r#Display }
+    );
+}
+
+#[test]
+fn real_values() {
+    let number = 5;
+    let unit = ();
+
+    assert_eq!(
+        into_dispatcher!(number).variant_name(),
+        "DispatcherDebugDisplay"
+    );
+    assert_eq!(into_dispatcher!(unit).variant_name(), "DispatcherDebug");
+}
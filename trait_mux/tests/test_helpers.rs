@@ -0,0 +1,29 @@
+use std::fmt::{Debug, Display};
+use trait_mux::trait_mux;
+
+trait_mux!(
+    #[trait_mux(test_helpers)]
+    Dispatcher { Debug, Display }
+);
+
+struct Nothing;
+
+#[test]
+fn assert_dispatch() {
+    let number = 5;
+    let string = String::from("hello");
+    let unit = ();
+    let nothing = Nothing;
+
+    assert_dispatch_dispatcher!(number => DispatcherDebugDisplay);
+    assert_dispatch_dispatcher!(string => DispatcherDebugDisplay);
+    assert_dispatch_dispatcher!(unit => DispatcherDebug);
+    assert_dispatch_dispatcher!(nothing => DispatcherNone);
+}
+
+#[test]
+#[should_panic]
+fn assert_dispatch_mismatch() {
+    let unit = ();
+    assert_dispatch_dispatcher!(unit => DispatcherDisplay);
+}
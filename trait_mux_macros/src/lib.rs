@@ -5,14 +5,32 @@ mod trait_mux;
 use crate::trait_mux::analyze;
 use crate::trait_mux::codegen;
 use crate::trait_mux::lower;
+use crate::trait_mux::options;
 use crate::trait_mux::parse;
 
 #[proc_macro]
 #[proc_macro_error]
 pub fn trait_mux(ts: TokenStream) -> TokenStream {
+    if let Some(set_use) = parse::parse_trait_set_use(ts.clone().into()) {
+        return codegen::generate_trait_set_use(&set_use).into();
+    }
+    if let Some(splice) = parse::parse_trait_set_splice(ts.clone().into()) {
+        return codegen::generate_trait_set_splice(&splice).into();
+    }
+
     let ast = parse::parse(ts.clone().into());
     let model = analyze::analyze(&ast);
     let ir = lower::lower(&model);
     let ts = codegen::codegen(ir);
+    if let Some(path) = &ast.options.dump_to {
+        codegen::dump(&ts, path);
+    }
     ts.into()
 }
+
+#[proc_macro]
+#[proc_macro_error]
+pub fn trait_mux_traits(ts: TokenStream) -> TokenStream {
+    let set = parse::parse_trait_set(ts.into());
+    codegen::generate_trait_set(&set).into()
+}
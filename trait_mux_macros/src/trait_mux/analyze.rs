@@ -3,33 +3,95 @@
 //! The main responsibility is to extract traits and generate all possible enum variants
 //! that will be used in the final generated code.
 
+use convert_case::{Case, Casing};
 use proc_macro2::{Ident, Span};
-use syn::{Path, spanned::Spanned};
+use quote::ToTokens;
+use syn::token::Comma;
+use syn::{
+    Attribute, GenericArgument, Path, PathArguments, Type, ext::IdentExt, punctuated::Punctuated,
+    spanned::Spanned,
+};
 
+use crate::options::{Mode, Options, TraitRefName};
 use crate::parse::Ast;
 
 /// The core model structure that contains all processed information from the AST.
 /// This model is used as input for code generation, representing enum variants and traits
 /// in a format that's easy to work with.
 pub struct Model<'t> {
+    /// The options set through `#[trait_mux(...)]` attributes, taken from the AST.
+    pub options: &'t Options,
     /// The identifier of the main enum, taken from the AST.
     pub enum_ident: &'t Ident,
     /// All possible variants of the enum based on trait combinations.
     pub enum_variants: Vec<EnumVariant<'t>>,
     /// The identifier for the wrapper structure that will encapsulate the enum.
     pub wrap_ident: Ident,
+    /// The identifier of the private enum wrapped by the opaque struct, e.g. `TypeInner`, if
+    /// `opaque` is enabled.
+    pub inner_ident: Option<Ident>,
     /// All traits extracted from the AST.
     pub traits: Vec<Trait<'t>>,
+    /// The concrete types held by the enum in types mode, or None otherwise.
+    pub types: Option<Vec<ConcreteType<'t>>>,
+    /// The listed trait declared with the `primary` option, if any.
+    pub primary: Option<Trait<'t>>,
+    /// The listed `std::error::Error` trait the enum delegates to, if `error_impl` is enabled.
+    pub error: Option<Trait<'t>>,
+    /// The traits generated for the parenthesized combinations of traits, e.g. `(Debug + Display)`.
+    pub combined: Vec<CombinedTrait<'t>>,
+    /// Whether the single listed trait is detected by a generic struct instead of the enum, in
+    /// auto mode.
+    pub monomorphic: bool,
+}
+
+/// A trait generated for a parenthesized combination of listed traits, e.g. `TypeDebugDisplay` for
+/// `(Debug + Display)`, which is listed as a single trait.
+pub struct CombinedTrait<'t> {
+    /// The name of the generated trait, e.g. `TypeDebugDisplay`.
+    pub name: &'t Ident,
+    /// The combined traits, the supertraits of the generated trait.
+    pub traits: Vec<Trait<'t>>,
+}
+
+/// A concrete type listed in types mode, held by its own enum variant.
+pub struct ConcreteType<'t> {
+    /// The variant name, the last segment of the type path in upper camel case, e.g. `I32` for
+    /// `i32`.
+    pub ident: Ident,
+    /// The listed type.
+    pub ty: &'t Type,
 }
 
 /// Represents a trait with its identifier and path.
 /// Used to track traits throughout the code generation process.
 #[derive(Copy, Clone)]
 pub struct Trait<'t> {
-    /// The identifier of the trait (the name).
+    /// The identifier of the trait (the name), used for the variant and accessor names.
+    /// This is the alias set with `as` if present, or the last segment of the path otherwise.
     pub ident: &'t Ident,
-    /// The full path to the trait, including any module qualifiers.
+    /// The full path to the trait, including any module qualifiers, used wherever the trait itself
+    /// is referred to (e.g. in the `dyn` position).
     pub path: &'t Path,
+    /// The auto-trait bounds of the entry, added to the trait objects of this trait only, e.g.
+    /// `Send` in `Debug + Send`.
+    /// The variants implementing several traits hold an aggregate bound by the union of their
+    /// bounds, so the trait object of each trait can be taken with its own.
+    pub bounds: &'t [Path],
+    /// Whether the trait was requested with `mut`, in which case it's accessed mutably.
+    pub mutable: bool,
+    /// The doc comments of the entry, attached to the generated accessors.
+    pub docs: &'t [Attribute],
+}
+
+/// A declared implication between two listed traits, resolved from the `implies(...)` option.
+/// Any type implementing `from` is known to also implement `to`.
+#[derive(Copy, Clone)]
+struct Implication<'t> {
+    /// The implying trait.
+    from: Trait<'t>,
+    /// The implied trait.
+    to: Trait<'t>,
 }
 
 /// Represents an enum variant, including its identifier, and the traits it implements.
@@ -44,6 +106,21 @@ pub struct EnumVariant<'t> {
     pub implemented_traits: Vec<Trait<'t>>,
 }
 
+/// Returns the name of an identifier, used to build the generated identifiers.
+/// Raw identifiers have their `r#` prefix stripped, as it can't appear in the middle of an
+/// identifier, e.g. a variant of `Type` implementing `r#match` is named `Typematch`.
+///
+/// # Arguments
+///
+/// * `ident` - The identifier to name
+///
+/// # Returns
+///
+/// The identifier as a string, without the raw prefix
+pub fn ident_name(ident: &Ident) -> String {
+    ident.unraw().to_string()
+}
+
 /// Analyzes the AST and constructs a Model containing all the necessary information
 /// for code generation. This is the main entry point for the analysis phase.
 ///
@@ -54,21 +131,187 @@ pub struct EnumVariant<'t> {
 /// # Returns
 ///
 /// A Model containing the processed information ready for code generation
-pub fn analyze(ast: &Ast) -> Model {
+pub fn analyze(ast: &Ast) -> Model<'_> {
     let traits = extract_traits(ast);
-    let enum_variants = generate_enum_variants(ast, &traits);
-    let wrap_ident = Ident::new(&format!("Wrap{}", ast.name), Span::call_site());
+    let implications = extract_implications(ast, &traits);
+    let types = ast.types.as_ref().map(|types| {
+        check_types(ast);
+        extract_types(types)
+    });
+    if ast.options.mode == Mode::PriorityChain {
+        check_priority_chain(ast);
+    }
+    if ast.options.mode == Mode::Auto {
+        check_auto(ast);
+    }
+    // A single trait needs no multiplexing, so auto mode resolves it at compile time instead.
+    let monomorphic = ast.options.mode == Mode::Auto
+        && traits.len() == 1
+        && !ast.options.bitset
+        && types.is_none();
+    let enum_variants = if types.is_some() {
+        vec![]
+    } else if ast.options.bitset {
+        check_bitset(ast, &traits);
+        vec![]
+    } else if monomorphic {
+        check_without_variants(ast, "monomorphic");
+        vec![]
+    } else if ast.options.mode == Mode::PriorityChain {
+        generate_priority_variants(ast, &traits)
+    } else {
+        generate_enum_variants(ast, &traits, &implications)
+    };
+
+    // The traits of a variant are looked up as a `u32` mask, with a bit per trait, which can't be
+    // computed for more traits.
+    if !enum_variants.is_empty() && traits.len() > MAX_MASK_TRAITS {
+        proc_macro_error::abort!(
+            ast.name.span(),
+            "at most {} traits are supported, got {}",
+            MAX_MASK_TRAITS,
+            traits.len()
+        );
+    }
+    // Variants are identified by a `u16` discriminant.
+    if enum_variants.len() > MAX_VARIANTS {
+        proc_macro_error::emit_error!(
+            ast.name.span(),
+            "at most {} variants are supported, got {}, consider bitset mode",
+            MAX_VARIANTS,
+            enum_variants.len()
+        );
+    }
+    if let Some(repr) = &ast.options.repr {
+        check_repr(ast, repr, enum_variants.len());
+    }
+    if let Some(common) = &ast.options.common {
+        check_common(common, &traits);
+    }
+    if ast.options.opaque {
+        check_opaque(ast);
+    }
+    if let Some(trait_ref) = &ast.options.trait_ref {
+        check_trait_ref(trait_ref);
+    }
+    // The selection steps take the state of the previous steps as a generic parameter named `S`.
+    if let Some(generic) = &ast.options.value_generic {
+        if generic == "S" {
+            proc_macro_error::emit_error!(
+                generic.span(),
+                "the value generic can't be named `S`, which is used by the selection steps"
+            );
+        }
+    }
+    if ast.options.copy_accessors && ast.types.is_none() {
+        proc_macro_error::emit_error!(
+            ast.name.span(),
+            "`copy_accessors` is only supported in types mode"
+        );
+    }
+    let combined = extract_combined(ast);
+    if ast.types.is_none() {
+        check_derives(ast, &traits);
+    }
+    if traits.iter().any(|t| uses_enum_lifetime(t.path))
+        || combined
+            .iter()
+            .flat_map(|c| &c.traits)
+            .any(|t| uses_enum_lifetime(t.path))
+    {
+        check_enum_lifetime(ast);
+    }
+    // Without mutable traits the enum already holds shared references, and the bitset, types and
+    // monomorphic modes report the option themselves.
+    if ast.options.shared_view
+        && !traits.iter().any(|t| t.mutable)
+        && !ast.options.bitset
+        && !monomorphic
+        && ast.types.is_none()
+    {
+        proc_macro_error::emit_error!(
+            ast.name.span(),
+            "`shared_view` requires a `mut` trait, as the enum holds shared references otherwise"
+        );
+    }
+
+    let wrap_ident = Ident::new(&format!("Wrap{}", ident_name(&ast.name)), Span::call_site());
+    let inner_ident = ast.options.opaque.then(|| {
+        Ident::new(
+            &format!("{}Inner", ident_name(&ast.name)),
+            Span::call_site(),
+        )
+    });
+
+    let primary = ast
+        .options
+        .primary
+        .as_ref()
+        .and_then(|primary| extract_primary(primary, &traits));
+    let error = ast
+        .options
+        .error_impl
+        .then(|| extract_error(ast, &traits))
+        .flatten();
 
     Model {
+        options: &ast.options,
         enum_ident: &ast.name,
         enum_variants,
         wrap_ident,
+        inner_ident,
         traits,
+        types,
+        primary,
+        error,
+        combined,
+        monomorphic,
     }
 }
 
+/// Extracts the parenthesized combinations of traits from the given AST, each generating a trait
+/// with the combined traits as supertraits.
+///
+/// # Arguments
+///
+/// * `ast` - The AST to extract the combinations from
+///
+/// # Returns
+///
+/// A CombinedTrait for every parenthesized entry, in declaration order
+fn extract_combined(ast: &Ast) -> Vec<CombinedTrait<'_>> {
+    ast.traits
+        .iter()
+        .filter(|entry| !entry.combined.is_empty())
+        .filter_map(|entry| {
+            let traits = entry
+                .combined
+                .iter()
+                .filter_map(|path| {
+                    Some(Trait {
+                        ident: &path.segments.last()?.ident,
+                        path,
+                        bounds: &[],
+                        mutable: false,
+                        docs: &[],
+                    })
+                })
+                .collect();
+
+            Some(CombinedTrait {
+                name: entry.path.get_ident()?,
+                traits,
+            })
+        })
+        .collect()
+}
+
 /// Extracts traits from the given AST and converts them to the Trait model.
-/// Emits an error if a path is empty or malformed.
+/// The trait declared with the `universal` option is added, unless it's already listed.
+/// A trait listed twice is merged into a single mutable trait if either entry is `mut`, or is an
+/// error with the `no_duplicates` option.
+/// Emits an error if a path is empty or malformed, if two different traits have the same name, or
+/// if a trait is listed twice with different auto-trait bounds.
 ///
 /// # Arguments
 ///
@@ -76,11 +319,14 @@ pub fn analyze(ast: &Ast) -> Model {
 ///
 /// # Returns
 ///
-/// A vector of Trait structs sorted alphabetically by their identifiers
-fn extract_traits(ast: &Ast) -> Vec<Trait> {
-    let mut traits = vec![];
+/// A vector of Trait structs sorted alphabetically by their names, see `generate_enum_variants` for
+/// the ordering details
+fn extract_traits(ast: &Ast) -> Vec<Trait<'_>> {
+    let mut traits: Vec<Trait> = vec![];
+
+    for entry in &ast.traits {
+        let path = &entry.path;
 
-    for path in &ast.paths {
         if path.segments.is_empty() {
             proc_macro_error::emit_error!(
                 path.span(),
@@ -89,36 +335,893 @@ fn extract_traits(ast: &Ast) -> Vec<Trait> {
         }
 
         // Unwrap safety: checked that segments is not empty.
+        let ident = match &entry.alias {
+            Some(alias) => alias,
+            None => &path.segments.last().unwrap().ident,
+        };
+
+        let normalized = normalize_path(path);
+        if let Some(existing) = traits
+            .iter_mut()
+            .find(|t| t.ident == ident || normalize_path(t.path) == normalized)
+        {
+            if normalize_path(existing.path) != normalized {
+                proc_macro_error::emit_error!(
+                    path,
+                    "another listed trait is already named `{}`", ident;
+                    help = "rename one of the traits with `as`"
+                );
+            } else if ast.options.no_duplicates {
+                proc_macro_error::emit_error!(
+                    path,
+                    "the trait `{}` is listed more than once",
+                    ident
+                );
+            } else if normalize_bounds(existing.bounds) != normalize_bounds(&entry.bounds) {
+                proc_macro_error::emit_error!(
+                    path,
+                    "the trait `{}` is listed with different auto-trait bounds",
+                    ident
+                );
+            } else {
+                existing.mutable |= entry.mutability.is_some();
+            }
+            continue;
+        }
+
         traits.push(Trait {
-            ident: &path.segments.last().unwrap().ident,
+            ident,
             path,
+            bounds: &entry.bounds,
+            mutable: entry.mutability.is_some(),
+            docs: &entry.docs,
         });
     }
 
-    // Sort traits alphabetically by their identifier.
-    traits.sort_by_key(|t| t.ident.to_string());
+    // The universal trait may be listed as well, e.g. to request mutable access to it.
+    if let Some(universal) = &ast.options.universal {
+        if find_trait(&traits, universal).is_none() {
+            // Unwrap safety: a parsed path has at least one segment.
+            traits.push(Trait {
+                ident: &universal.segments.last().unwrap().ident,
+                path: universal,
+                bounds: &[],
+                mutable: false,
+                docs: &[],
+            });
+        }
+    }
+
+    // Sort traits alphabetically by their name.
+    traits.sort_by_key(|t| ident_name(t.ident));
 
     traits
 }
 
+/// Normalizes a trait path for comparison, ignoring a leading `::` and the spacing of its tokens.
+///
+/// # Arguments
+///
+/// * `path` - The path to normalize
+///
+/// # Returns
+///
+/// The normalized path, e.g. `std :: fmt :: Debug`
+fn normalize_path(path: &Path) -> String {
+    path.segments.to_token_stream().to_string()
+}
+
+/// Normalizes the auto-trait bounds of an entry for comparison, ignoring their order.
+///
+/// # Arguments
+///
+/// * `bounds` - The bounds to normalize
+///
+/// # Returns
+///
+/// The sorted normalized bounds, see `normalize_path`
+fn normalize_bounds(bounds: &[Path]) -> Vec<String> {
+    let mut bounds: Vec<_> = bounds.iter().map(normalize_path).collect();
+    bounds.sort();
+    bounds
+}
+
+/// Checks whether a trait path has the enum lifetime `'t` as a lifetime argument, e.g. in place of
+/// an elided lifetime, as in `Named<'_>`.
+///
+/// # Arguments
+///
+/// * `path` - The path of the trait
+///
+/// # Returns
+///
+/// True if any segment of the path has `'t` as a lifetime argument
+pub fn uses_enum_lifetime(path: &Path) -> bool {
+    path.segments.iter().any(|segment| match &segment.arguments {
+        PathArguments::AngleBracketed(arguments) => arguments.args.iter().any(
+            |argument| matches!(argument, GenericArgument::Lifetime(lifetime) if lifetime.ident == "t"),
+        ),
+        _ => false,
+    })
+}
+
+/// The maximal number of enum variants, limited by the width of the `u16` discriminant.
+const MAX_VARIANTS: usize = u16::MAX as usize + 1;
+
+/// The maximal number of traits of the enum or the bitset struct, limited by the width of the
+/// `u32` trait masks.
+const MAX_MASK_TRAITS: usize = 32;
+
+/// Checks that the listed traits can be used in bitset mode, where each trait is detected
+/// separately instead of generating enum variants.
+/// Emits an error for every unsupported feature that was requested.
+///
+/// # Arguments
+///
+/// * `ast` - The AST containing the enum name and options
+/// * `traits` - The listed traits
+fn check_bitset(ast: &Ast, traits: &[Trait]) {
+    if traits.len() > MAX_MASK_TRAITS {
+        proc_macro_error::emit_error!(
+            ast.name.span(),
+            "bitset mode supports at most {} traits, got {}",
+            MAX_MASK_TRAITS,
+            traits.len()
+        );
+    }
+
+    check_without_variants(ast, "bitset");
+}
+
+/// Checks that the options can be used in a mode generating a struct instead of the enum, i.e.
+/// bitset mode or the monomorphic struct of auto mode, which have no variants.
+/// Emits an error for every unsupported feature that was requested.
+///
+/// # Arguments
+///
+/// * `ast` - The AST containing the enum name and options
+/// * `mode` - The name of the mode, used in the error messages
+fn check_without_variants(ast: &Ast, mode: &str) {
+    // The value is only held through a shared pointer or reference, so it can't be borrowed
+    // mutably.
+    for entry in &ast.traits {
+        if let Some(mutability) = entry.mutability {
+            proc_macro_error::emit_error!(
+                mutability.span(),
+                "`mut` traits are not supported in {} mode",
+                mode
+            );
+        }
+
+        if let Some(bound) = entry.bounds.first() {
+            proc_macro_error::emit_error!(
+                bound,
+                "auto-trait bounds are not supported in {} mode",
+                mode
+            );
+        }
+    }
+
+    if ast.options.test_helpers {
+        proc_macro_error::emit_error!(
+            ast.name.span(),
+            "`test_helpers` is not supported in {} mode, as there are no variants",
+            mode
+        );
+    }
+
+    if ast.options.owned {
+        proc_macro_error::emit_error!(
+            ast.name.span(),
+            "`owned` is not supported in {} mode, as there are no variants",
+            mode
+        );
+    }
+
+    if let Some(wrapper) = &ast.options.wrapper {
+        proc_macro_error::emit_error!(
+            wrapper.span(),
+            "`wrapper` is not supported in {} mode",
+            mode
+        );
+    }
+
+    if let Some(common) = &ast.options.common {
+        proc_macro_error::emit_error!(common.span(), "`common` is not supported in {} mode", mode);
+    }
+
+    if ast.options.error_impl {
+        proc_macro_error::emit_error!(
+            ast.name.span(),
+            "`error_impl` is not supported in {} mode, as there are no variants",
+            mode
+        );
+    }
+
+    if let Some(marker) = &ast.options.marker_super {
+        proc_macro_error::emit_error!(
+            marker.span(),
+            "`marker_super` is not supported in {} mode",
+            mode
+        );
+    }
+
+    if let Some(compat) = &ast.options.compat {
+        proc_macro_error::emit_error!(compat.span(), "`compat` is not supported in {} mode", mode);
+    }
+
+    if let Some(repr) = &ast.options.repr {
+        proc_macro_error::emit_error!(repr.span(), "`repr` is not supported in {} mode", mode);
+    }
+
+    for attr in &ast.options.enum_attrs {
+        proc_macro_error::emit_error!(
+            attr,
+            "`{}` attributes are not supported in {} mode, as there is no enum",
+            attr.path.to_token_stream(),
+            mode
+        );
+    }
+
+    if ast.options.eq_by_variant {
+        proc_macro_error::emit_error!(
+            ast.name.span(),
+            "`eq_by_variant` is not supported in {} mode, as there are no variants",
+            mode
+        );
+    }
+
+    if ast.options.strict {
+        proc_macro_error::emit_error!(
+            ast.name.span(),
+            "`strict` is not supported in {} mode, as there is no variant without traits",
+            mode
+        );
+    }
+
+    if ast.options.expose_table {
+        proc_macro_error::emit_error!(
+            ast.name.span(),
+            "`expose_table` is not supported in {} mode, as there are no variants",
+            mode
+        );
+    }
+
+    if ast.options.lifetime_accessors {
+        proc_macro_error::emit_error!(
+            ast.name.span(),
+            "`lifetime_accessors` is not supported in {} mode",
+            mode
+        );
+    }
+
+    if ast.options.unchecked_accessors {
+        proc_macro_error::emit_error!(
+            ast.name.span(),
+            "`unchecked_accessors` is not supported in {} mode",
+            mode
+        );
+    }
+
+    if ast.options.debug_json {
+        proc_macro_error::emit_error!(
+            ast.name.span(),
+            "`debug_json` is not supported in {} mode",
+            mode
+        );
+    }
+
+    if ast.options.unsafe_erased {
+        proc_macro_error::emit_error!(
+            ast.name.span(),
+            "`unsafe_erased` is not supported in {} mode, as there are no variants",
+            mode
+        );
+    }
+
+    if ast.options.static_accessors {
+        proc_macro_error::emit_error!(
+            ast.name.span(),
+            "`static` is not supported in {} mode",
+            mode
+        );
+    }
+
+    if ast.options.named_accessors {
+        proc_macro_error::emit_error!(
+            ast.name.span(),
+            "`named_accessors` is not supported in {} mode",
+            mode
+        );
+    }
+
+    if ast.options.collection {
+        proc_macro_error::emit_error!(
+            ast.name.span(),
+            "`collection` is not supported in {} mode",
+            mode
+        );
+    }
+
+    if ast.options.opaque {
+        proc_macro_error::emit_error!(
+            ast.name.span(),
+            "`opaque` is not supported in {} mode, as there are no variants",
+            mode
+        );
+    }
+
+    if ast.options.self_test {
+        proc_macro_error::emit_error!(
+            ast.name.span(),
+            "`self_test` is not supported in {} mode, as there are no variants",
+            mode
+        );
+    }
+
+    if ast.options.shared_view {
+        proc_macro_error::emit_error!(
+            ast.name.span(),
+            "`shared_view` is not supported in {} mode, as there are no variants",
+            mode
+        );
+    }
+
+    if ast.options.variant_macro {
+        proc_macro_error::emit_error!(
+            ast.name.span(),
+            "`variant_macro` is not supported in {} mode, as there are no variants",
+            mode
+        );
+    }
+
+    if ast.options.counter {
+        proc_macro_error::emit_error!(
+            ast.name.span(),
+            "`counter` is not supported in {} mode, as there are no variants",
+            mode
+        );
+    }
+
+    if let Some(trait_ref) = &ast.options.trait_ref {
+        proc_macro_error::emit_error!(
+            trait_ref.path.span(),
+            "`trait_ref` is not supported in {} mode",
+            mode
+        );
+    }
+}
+
+/// Checks that the options can be used in priority chain mode, where every variant holds a single
+/// trait, the first listed one the value implements.
+/// Emits an error for every unsupported mode or option that was requested.
+///
+/// # Arguments
+///
+/// * `ast` - The AST containing the enum name and options
+fn check_priority_chain(ast: &Ast) {
+    let options = &ast.options;
+    let unsupported = [("bitset", options.bitset), ("types", ast.types.is_some())];
+
+    for (mode, _) in unsupported.iter().filter(|(_, requested)| *requested) {
+        proc_macro_error::emit_error!(
+            ast.name.span(),
+            "`mode = priority_chain` is not supported in {} mode",
+            mode
+        );
+    }
+
+    // Implications only prune combinations of traits, and the universal trait would replace the
+    // variant without traits, neither of which exist in this mode.
+    if let Some(implication) = options.implies.first() {
+        proc_macro_error::emit_error!(
+            implication.from.span(),
+            "`implies` and `aggregate` are not supported in priority chain mode"
+        );
+    }
+    if let Some(universal) = &options.universal {
+        proc_macro_error::emit_error!(
+            universal.span(),
+            "`universal` is not supported in priority chain mode"
+        );
+    }
+}
+
+/// Checks that the options can be used in auto mode, which only chooses between the enum and the
+/// monomorphic struct.
+/// Emits an error for every unsupported mode that was requested.
+///
+/// # Arguments
+///
+/// * `ast` - The AST containing the enum name and options
+fn check_auto(ast: &Ast) {
+    let unsupported = [
+        ("bitset", ast.options.bitset),
+        ("types", ast.types.is_some()),
+    ];
+
+    for (mode, _) in unsupported.iter().filter(|(_, requested)| *requested) {
+        proc_macro_error::emit_error!(
+            ast.name.span(),
+            "`mode = auto` is not supported in {} mode",
+            mode
+        );
+    }
+}
+
+/// Checks that the options can be used in opaque mode, where the enum is private and only the
+/// accessors are forwarded by the wrapping struct.
+/// Emits an error for every option whose generated items would be unreachable.
+///
+/// # Arguments
+///
+/// * `ast` - The AST containing the enum name and options
+fn check_opaque(ast: &Ast) {
+    let options = &ast.options;
+    let unsupported = [
+        ("expose_table", options.expose_table),
+        ("lifetime_accessors", options.lifetime_accessors),
+        ("unchecked_accessors", options.unchecked_accessors),
+        ("debug_json", options.debug_json),
+        ("unsafe_erased", options.unsafe_erased),
+        ("static", options.static_accessors),
+        ("named_accessors", options.named_accessors),
+        ("compat", options.compat.is_some()),
+        ("trait_ref", options.trait_ref.is_some()),
+        ("shared_view", options.shared_view),
+        ("variant_macro", options.variant_macro),
+        ("owned", options.owned),
+    ];
+
+    for (option, _) in unsupported.iter().filter(|(_, requested)| *requested) {
+        proc_macro_error::emit_error!(
+            ast.name.span(),
+            "`{}` is not supported in opaque mode",
+            option
+        );
+    }
+}
+
+/// Checks that the traits derived with `#[derive(...)]` can be derived for an enum holding
+/// references to trait objects.
+/// Comparing or hashing the variants would compare or hash the trait objects themselves, which
+/// trait objects don't support, so these derives are reported along with `eq_by_variant`.
+/// References to `mut` traits can't be copied, so `Clone` and `Copy` are reported with them.
+/// `error_impl` implements `Debug` itself, so deriving it is reported along with the option.
+///
+/// # Arguments
+///
+/// * `ast` - The AST containing the attributes
+/// * `traits` - The listed traits
+fn check_derives(ast: &Ast, traits: &[Trait]) {
+    let derived = ast
+        .options
+        .enum_attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("derive"))
+        .filter_map(|attr| {
+            attr.parse_args_with(Punctuated::<Path, Comma>::parse_terminated)
+                .ok()
+        })
+        .flatten();
+
+    for path in derived {
+        let Some(segment) = path.segments.last() else {
+            continue;
+        };
+        let name = segment.ident.to_string();
+
+        match name.as_str() {
+            "PartialEq" | "Eq" | "Hash" => proc_macro_error::emit_error!(
+                path,
+                "`{}` can't be derived, as the variants hold trait objects, which can't be compared",
+                name;
+                help = "enable `eq_by_variant` to compare and hash the values by their variant"
+            ),
+            "PartialOrd" | "Ord" => proc_macro_error::emit_error!(
+                path,
+                "`{}` can't be derived, as the variants hold trait objects, which can't be compared",
+                name;
+                help = "compare the `discriminant()` of the values instead"
+            ),
+            "Clone" | "Copy" if traits.iter().any(|t| t.mutable) => proc_macro_error::emit_error!(
+                path,
+                "`{}` can't be derived with `mut` traits, as their variants hold mutable references",
+                name
+            ),
+            "Debug" if ast.options.error_impl => proc_macro_error::emit_error!(
+                path,
+                "`Debug` can't be derived with `error_impl`, which implements it for the enum";
+                help = "remove `Debug` from the derives, the implementation delegates to the error"
+            ),
+            _ => {}
+        }
+    }
+}
+
+/// Checks that the options can be used with traits bound by the enum lifetime `'t`, which is only
+/// in scope of the items generated for the enum itself.
+/// Emits an error for every option generating items without the enum lifetime.
+///
+/// # Arguments
+///
+/// * `ast` - The AST containing the enum name and options
+fn check_enum_lifetime(ast: &Ast) {
+    let options = &ast.options;
+    let unsupported = [
+        ("bitset", options.bitset),
+        ("owned", options.owned),
+        ("static", options.static_accessors),
+        ("unsafe_erased", options.unsafe_erased),
+        ("wrapper", options.wrapper.is_some()),
+        ("access_trait", options.access_trait),
+        ("shared_view", options.shared_view),
+    ];
+
+    for (option, _) in unsupported.iter().filter(|(_, requested)| *requested) {
+        proc_macro_error::emit_error!(
+            ast.name.span(),
+            "`{}` is not supported with traits bound by the enum lifetime, e.g. `Trait<'_>`",
+            option
+        );
+    }
+
+    if ast.types.is_some() {
+        proc_macro_error::emit_error!(
+            ast.name.span(),
+            "traits bound by the enum lifetime, e.g. `Trait<'_>`, are not supported in types mode"
+        );
+    }
+
+    // The traits generated for combinations are referred to without the enum lifetime.
+    for entry in ast.traits.iter().filter(|entry| !entry.combined.is_empty()) {
+        proc_macro_error::emit_error!(
+            entry.path.span(),
+            "combined traits are not supported with traits bound by the enum lifetime, e.g. `Trait<'_>`"
+        );
+    }
+}
+
+/// Checks that the listed traits and options can be used in types mode, where the enum holds the
+/// listed concrete types instead of trait objects.
+/// Emits an error for every unsupported feature that was requested.
+///
+/// # Arguments
+///
+/// * `ast` - The AST containing the enum name and options
+fn check_types(ast: &Ast) {
+    for entry in &ast.traits {
+        if let Some(mutability) = entry.mutability {
+            proc_macro_error::emit_error!(
+                mutability.span(),
+                "`mut` traits are not supported in types mode"
+            );
+        }
+
+        if let Some(bound) = entry.bounds.first() {
+            proc_macro_error::emit_error!(
+                bound,
+                "auto-trait bounds are not supported in types mode"
+            );
+        }
+
+        if !entry.combined.is_empty() {
+            proc_macro_error::emit_error!(
+                entry.path.span(),
+                "combined traits are not supported in types mode"
+            );
+        }
+    }
+
+    let options = &ast.options;
+    let unsupported = [
+        ("implies", !options.implies.is_empty()),
+        ("test_helpers", options.test_helpers),
+        ("bitset", options.bitset),
+        ("owned", options.owned),
+        ("expose_table", options.expose_table),
+        ("wrapper", options.wrapper.is_some()),
+        ("scoped_macros", options.scoped_macros),
+        ("common", options.common.is_some()),
+        ("strict", options.strict),
+        ("repr", options.repr.is_some()),
+        ("extension", !options.extension.is_empty()),
+        ("universal", options.universal.is_some()),
+        ("lifetime_accessors", options.lifetime_accessors),
+        ("unchecked_accessors", options.unchecked_accessors),
+        ("debug_json", options.debug_json),
+        ("unsafe_erased", options.unsafe_erased),
+        ("static", options.static_accessors),
+        ("collection", options.collection),
+        ("named_accessors", options.named_accessors),
+        ("compat", options.compat.is_some()),
+        ("opaque", options.opaque),
+        ("self_test", options.self_test),
+        ("trait_ref", options.trait_ref.is_some()),
+        ("shared_view", options.shared_view),
+        ("variant_macro", options.variant_macro),
+        ("counter", options.counter),
+        ("dispatch_array", options.dispatch_array),
+        ("dispatch_eq", options.dispatch_eq),
+        ("dispatch_tuple", options.dispatch_tuple),
+        ("into_opt_result", options.into_opt_result),
+        ("marker_super", options.marker_super.is_some()),
+        ("error_impl", options.error_impl),
+        ("eq_by_variant", options.eq_by_variant),
+    ];
+
+    for (option, _) in unsupported.iter().filter(|(_, requested)| *requested) {
+        proc_macro_error::emit_error!(
+            ast.name.span(),
+            "`{}` is not supported in types mode",
+            option
+        );
+    }
+
+    // The value is returned by itself, so its type must be the same for every variant.
+    if let Some(types) = &ast.types {
+        if options.copy_accessors && types.len() != 1 {
+            proc_macro_error::emit_error!(
+                ast.name.span(),
+                "`copy_accessors` requires exactly one type, as the value is returned by copy"
+            );
+        }
+    }
+}
+
+/// Extracts the concrete types listed in types mode, naming their variants.
+/// Emits an error if a type isn't a path, or if two types would have the same variant name.
+///
+/// # Arguments
+///
+/// * `types` - The listed types
+///
+/// # Returns
+///
+/// A vector of ConcreteType structs, in the listed order
+fn extract_types(types: &Punctuated<Type, Comma>) -> Vec<ConcreteType<'_>> {
+    let mut concrete_types: Vec<ConcreteType> = vec![];
+
+    for ty in types {
+        let segment = match ty {
+            Type::Path(path) if path.qself.is_none() => path.path.segments.last(),
+            _ => None,
+        };
+        let Some(segment) = segment else {
+            proc_macro_error::emit_error!(ty.span(), "expected a type path, e.g. `String`");
+            continue;
+        };
+
+        let name = ident_name(&segment.ident).to_case(Case::UpperCamel);
+        let ident = Ident::new(&name, segment.ident.span());
+
+        if concrete_types.iter().any(|t| t.ident == ident) {
+            proc_macro_error::emit_error!(
+                ty.span(),
+                "another listed type already has the variant name `{}`",
+                ident
+            );
+            continue;
+        }
+
+        concrete_types.push(ConcreteType { ident, ty });
+    }
+
+    concrete_types
+}
+
+/// Checks that the variants fit in the primitive representation set by the `repr` option, as they
+/// get implicit discriminants counting up from 0, which the compiler would otherwise report as an
+/// overflowed discriminant without naming the option.
+/// Aborts if they don't.
+///
+/// # Arguments
+///
+/// * `ast` - The AST containing the enum name
+/// * `repr` - The primitive representation of the enum
+/// * `variants` - The number of enum variants
+fn check_repr(ast: &Ast, repr: &Ident, variants: usize) {
+    // The wider types hold more discriminants than the `u16` discriminant allows variants.
+    let capacity: usize = match repr.to_string().as_str() {
+        "u8" => 1 << 8,
+        "i8" => 1 << 7,
+        "u16" => 1 << 16,
+        "i16" => 1 << 15,
+        _ => return,
+    };
+
+    if variants > capacity {
+        proc_macro_error::abort!(
+            repr.span(),
+            "the {} variants of `{}` don't fit in `#[repr({})]`, which holds at most {}",
+            variants,
+            ast.name,
+            repr,
+            capacity
+        );
+    }
+}
+
+/// Checks that the trait declared with the `common` option isn't one of the listed traits, as
+/// the variants without it wouldn't implement it.
+/// Emits an error if it is.
+///
+/// # Arguments
+///
+/// * `common` - The declared common trait
+/// * `traits` - The listed traits
+fn check_common(common: &Path, traits: &[Trait]) {
+    if find_trait(traits, common).is_some() {
+        proc_macro_error::emit_error!(
+            common.span(),
+            "the common trait can't be listed, as the variants without it wouldn't implement it"
+        );
+    }
+}
+
+/// Checks that a trait reference enum defined by this enum is named by a plain identifier, as
+/// only a reused enum can be referred to by a path.
+/// Emits an error otherwise.
+///
+/// # Arguments
+///
+/// * `trait_ref` - The name of the trait reference enum
+fn check_trait_ref(trait_ref: &TraitRefName) {
+    if !trait_ref.reused && trait_ref.path.get_ident().is_none() {
+        proc_macro_error::emit_error!(
+            trait_ref.path.span(),
+            "expected an identifier, use `trait_ref = use path::Name` to reuse an existing enum"
+        );
+    }
+}
+
+/// Resolves the trait declared with the `primary` option against the listed traits.
+/// Emits an error if it isn't listed.
+///
+/// # Arguments
+///
+/// * `primary` - The declared primary trait
+/// * `traits` - The listed traits
+///
+/// # Returns
+///
+/// The listed trait, or None if it isn't listed
+fn extract_primary<'t>(primary: &Path, traits: &[Trait<'t>]) -> Option<Trait<'t>> {
+    resolve_listed(traits, primary, "the primary trait")
+}
+
+/// Finds the listed `std::error::Error` trait the `error_impl` option delegates to, by the last
+/// segment of its path, so it may be renamed with `as`.
+/// Emits an error if it isn't listed.
+///
+/// # Arguments
+///
+/// * `ast` - The AST containing the enum name
+/// * `traits` - The listed traits
+///
+/// # Returns
+///
+/// The listed trait, or None if it isn't listed
+fn extract_error<'t>(ast: &Ast, traits: &[Trait<'t>]) -> Option<Trait<'t>> {
+    let found = traits
+        .iter()
+        .find(|t| t.path.segments.last().is_some_and(|s| s.ident == "Error"))
+        .copied();
+    if found.is_none() {
+        proc_macro_error::emit_error!(
+            ast.name.span(),
+            "`error_impl` requires `std::error::Error` in the trait list"
+        );
+    }
+
+    found
+}
+
+/// Resolves a trait an option refers to against the listed traits, see `find_trait`.
+/// Emits an error spanning the whole path if it isn't listed, naming the listed traits.
+///
+/// # Arguments
+///
+/// * `traits` - The listed traits
+/// * `path` - The path referring to one of the listed traits
+/// * `role` - What the option declares the trait as, e.g. `the primary trait`
+///
+/// # Returns
+///
+/// The listed trait, or None if it isn't listed
+fn resolve_listed<'t>(traits: &[Trait<'t>], path: &Path, role: &str) -> Option<Trait<'t>> {
+    let found = find_trait(traits, path);
+    if found.is_none() {
+        let listed: Vec<_> = traits.iter().map(|t| format!("`{}`", t.ident)).collect();
+        proc_macro_error::emit_error!(
+            path,
+            "{} `{}` is not in the trait list",
+            role,
+            path.to_token_stream().to_string().replace(' ', "");
+            help = "the listed traits are {}", listed.join(", ")
+        );
+    }
+
+    found
+}
+
+/// Finds the listed trait a path refers to.
+/// Paths are matched by their last segment, so both `Display` and `std::fmt::Display` refer to a
+/// listed `fmt::Display`. Traits renamed with `as` are referred to by their alias.
+///
+/// # Arguments
+///
+/// * `traits` - The listed traits to search
+/// * `path` - The path referring to one of the listed traits
+///
+/// # Returns
+///
+/// The matching trait, or None if the path doesn't refer to any of the listed traits
+fn find_trait<'t>(traits: &[Trait<'t>], path: &Path) -> Option<Trait<'t>> {
+    let ident = &path.segments.last()?.ident;
+
+    traits.iter().find(|t| t.ident == ident).copied()
+}
+
+/// Resolves the implications declared through the `implies(...)` option against the listed traits.
+/// Emits an error if an implication refers to a trait that isn't listed.
+///
+/// # Arguments
+///
+/// * `ast` - The AST containing the declared implications
+/// * `traits` - The listed traits
+///
+/// # Returns
+///
+/// A vector of the resolved implications
+fn extract_implications<'t>(ast: &Ast, traits: &[Trait<'t>]) -> Vec<Implication<'t>> {
+    let mut implications = vec![];
+
+    for implication in &ast.options.implies {
+        let from = resolve_listed(traits, &implication.from, "the implying trait");
+        let to = resolve_listed(traits, &implication.to, "the implied trait");
+
+        if let (Some(from), Some(to)) = (from, to) {
+            implications.push(Implication { from, to });
+        }
+    }
+
+    implications
+}
+
 /// Generates all possible enum variants from the given traits.
+/// Combinations violating one of the declared implications can never occur, so they are skipped.
+/// The same goes for combinations without the trait declared with the `universal` option, which
+/// every type implements, so there is no variant without traits.
 /// The variants are sorted by descending length and then alphabetically.
-/// The order is very important for later stages, as we want to generate code
-/// with the most specific trait constraints first, and relax the constraints as
-/// we go down, if the order was incorrect, autoref specialization won't work
-/// properly.
+/// Names are compared by their UTF-8 bytes, so non-ASCII names are ordered by their code points
+/// (e.g. `Zeta` < `Ärger` < `Ωmega`), independently of the platform and locale. Raw identifiers
+/// are ordered by their name without the `r#` prefix.
+/// The order determines the discriminants, and keeps the generated code stable. The variant a
+/// value is converted into doesn't depend on it, as the selection steps name the exact
+/// combination of traits the value implements.
 ///
 /// # Arguments
 ///
 /// * `ast` - The AST containing the enum name
 /// * `traits` - A vector of Trait structs to generate permutations from
+/// * `implications` - The declared implications used to prune impossible combinations
 ///
 /// # Returns
 ///
 /// A vector of EnumVariant structs representing all possible trait combinations
-fn generate_enum_variants<'t>(ast: &Ast, traits: &Vec<Trait<'t>>) -> Vec<EnumVariant<'t>> {
+fn generate_enum_variants<'t>(
+    ast: &Ast,
+    traits: &Vec<Trait<'t>>,
+    implications: &[Implication<'t>],
+) -> Vec<EnumVariant<'t>> {
     let mut permutations = Vec::new();
     let n = traits.len();
+    let universal = ast
+        .options
+        .universal
+        .as_ref()
+        .and_then(|universal| find_trait(traits, universal));
 
     // Create all possible permutations of the trait names.
     // We have 2^n possible permutations.
@@ -130,27 +1233,24 @@ fn generate_enum_variants<'t>(ast: &Ast, traits: &Vec<Trait<'t>>) -> Vec<EnumVar
                 permutation.push(*r#trait);
             }
         }
+
+        let contains = |t: Trait| permutation.iter().any(|p| core::ptr::eq(p.path, t.path));
+        if implications
+            .iter()
+            .any(|imp| contains(imp.from) && !contains(imp.to))
+            || universal.is_some_and(|u| !contains(u))
+        {
+            continue;
+        }
+
         permutations.push(permutation);
     }
 
     let mut variants = permutations
         .iter()
-        .map(|variant| {
-            let variant_name = if variant.is_empty() {
-                "None".to_string()
-            } else {
-                variant
-                    .iter()
-                    .map(|t| t.ident.to_string())
-                    .collect::<String>()
-            };
-
-            let variant_name = format!("{}{}", ast.name, variant_name);
-
-            EnumVariant {
-                ident: Ident::new(&variant_name, Span::call_site()),
-                implemented_traits: variant.to_vec(),
-            }
+        .map(|variant| EnumVariant {
+            ident: variant_ident(ast, variant),
+            implemented_traits: variant.to_vec(),
         })
         .collect::<Vec<_>>();
 
@@ -160,6 +1260,7 @@ fn generate_enum_variants<'t>(ast: &Ast, traits: &Vec<Trait<'t>>) -> Vec<EnumVar
     let n_chars = n.to_string().len();
 
     // Sort by the length of implemented traits (descending), then alphabetically (ascending).
+    // The key is a String, which is ordered by its bytes, keeping the order deterministic.
     variants.sort_by_key(|e| {
         format!(
             "{:0width$} {}",
@@ -172,21 +1273,116 @@ fn generate_enum_variants<'t>(ast: &Ast, traits: &Vec<Trait<'t>>) -> Vec<EnumVar
     variants
 }
 
+/// Generates the enum variants of priority chain mode, one per trait in declaration order,
+/// followed by the variant without traits.
+/// A value is converted into the variant of the first listed trait it implements, so the
+/// variants hold a single trait each, and the order determines both the discriminants and the
+/// selection.
+///
+/// # Arguments
+///
+/// * `ast` - The AST containing the enum name and the trait entries in declaration order
+/// * `traits` - The extracted traits, with duplicate entries merged
+///
+/// # Returns
+///
+/// A vector of EnumVariant structs, one per trait and one without traits
+fn generate_priority_variants<'t>(ast: &Ast, traits: &[Trait<'t>]) -> Vec<EnumVariant<'t>> {
+    let mut chain: Vec<Trait> = vec![];
+
+    // A trait listed twice keeps the priority of its first entry.
+    for entry in &ast.traits {
+        let Some(ident) = entry
+            .alias
+            .as_ref()
+            .or_else(|| entry.path.segments.last().map(|s| &s.ident))
+        else {
+            continue;
+        };
+        if let Some(r#trait) = traits.iter().find(|t| t.ident == ident) {
+            if !chain.iter().any(|t| t.ident == ident) {
+                chain.push(*r#trait);
+            }
+        }
+    }
+
+    chain
+        .iter()
+        .map(|r#trait| vec![*r#trait])
+        .chain([vec![]])
+        .map(|variant| EnumVariant {
+            ident: variant_ident(ast, &variant),
+            implemented_traits: variant,
+        })
+        .collect()
+}
+
+/// Builds the identifier of the variant implementing the given traits, by concatenating the enum
+/// name and the trait names, e.g. `TypeDebugDisplay`, or `TypeNone` for the variant without traits.
+/// Emits an error if the concatenation isn't a valid identifier, e.g. `Self` formed by `S{elf}`.
+///
+/// # Arguments
+///
+/// * `ast` - The AST containing the enum name
+/// * `variant` - The traits implemented by the variant
+///
+/// # Returns
+///
+/// The variant identifier, or a placeholder if it's invalid
+fn variant_ident(ast: &Ast, variant: &[Trait]) -> Ident {
+    let traits_name = if variant.is_empty() {
+        "None".to_string()
+    } else {
+        variant
+            .iter()
+            .map(|t| ident_name(t.ident))
+            .collect::<String>()
+    };
+
+    let name = format!("{}{}", ident_name(&ast.name), traits_name);
+
+    match syn::parse_str::<Ident>(&name) {
+        Ok(ident) => ident,
+        Err(_) => {
+            let span = variant.last().map_or(ast.name.span(), |t| t.ident.span());
+            let traits = variant
+                .iter()
+                .map(|t| format!("`{}`", ident_name(t.ident)))
+                .collect::<Vec<_>>()
+                .join(", ");
+            proc_macro_error::emit_error!(
+                span,
+                "the variant name `{}`, formed from `{}` and {}, is not a valid identifier",
+                name,
+                ident_name(&ast.name),
+                traits;
+                help = "rename the trait with `as`"
+            );
+
+            Ident::new("__invalid", Span::call_site())
+        }
+    }
+}
+
 #[cfg(test)]
+#[allow(clippy::cmp_owned)]
 mod tests {
     use super::*;
+    use crate::options;
     use syn::{parse_quote, punctuated::Punctuated};
 
     #[test]
     fn test_generate_enum_variants_empty() {
         // Test with no traits
         let ast = Ast {
+            options: Options::default(),
             name: Ident::new("Test", Span::call_site()),
-            paths: Punctuated::new(),
+            types: None,
+            traits: Punctuated::new(),
         };
 
         let traits = extract_traits(&ast);
-        let variants = generate_enum_variants(&ast, &traits);
+        let variants = generate_enum_variants(&ast, &traits, &[]);
 
         assert_eq!(variants.len(), 1);
 
@@ -198,12 +1394,14 @@ mod tests {
     fn test_generate_enum_variants_single_trait() {
         // Test with a single trait
         let ast = Ast {
+            options: Options::default(),
             name: Ident::new("Test", Span::call_site()),
-            paths: parse_quote!(Debug),
+            types: None,
+            traits: parse_quote!(Debug),
         };
 
         let traits = extract_traits(&ast);
-        let variants = generate_enum_variants(&ast, &traits);
+        let variants = generate_enum_variants(&ast, &traits, &[]);
 
         assert_eq!(variants.len(), 2);
 
@@ -218,12 +1416,14 @@ mod tests {
     fn test_generate_enum_variants_multiple_traits() {
         // Test with multiple traits
         let ast = Ast {
+            options: Options::default(),
             name: Ident::new("Type", Span::call_site()),
-            paths: parse_quote!(Debug, Display, Clone),
+            types: None,
+            traits: parse_quote!(Debug, Display, Clone),
         };
 
         let traits = extract_traits(&ast);
-        let variants = generate_enum_variants(&ast, &traits);
+        let variants = generate_enum_variants(&ast, &traits, &[]);
 
         // Should have 2^3 = 8 variants
         assert_eq!(variants.len(), 8);
@@ -257,12 +1457,38 @@ mod tests {
         assert!(one_trait_variants[1].ident.to_string() <= one_trait_variants[2].ident.to_string());
     }
 
+    #[test]
+    fn test_generate_enum_variants_many_traits() {
+        // Test that the trait counts are zero padded when sorting, so with 10 or more traits a
+        // variant with 10 traits isn't ordered after variants with fewer traits
+        let ast = Ast {
+            options: Options::default(),
+            name: Ident::new("Type", Span::call_site()),
+            types: None,
+            traits: parse_quote!(A, B, C, D, E, F, G, H, I, J, K),
+        };
+
+        let traits = extract_traits(&ast);
+        let variants = generate_enum_variants(&ast, &traits, &[]);
+
+        assert_eq!(variants.len(), 1 << 11);
+        assert_eq!(variants[0].ident.to_string(), "TypeABCDEFGHIJK");
+        assert!(
+            variants
+                .windows(2)
+                .all(|w| w[0].implemented_traits.len() >= w[1].implemented_traits.len())
+        );
+        assert_eq!(variants[variants.len() - 1].ident.to_string(), "TypeNone");
+    }
+
     #[test]
     fn test_extract_traits_sorting() {
         // Test that traits are sorted alphabetically
         let ast = Ast {
+            options: Options::default(),
             name: Ident::new("Test", Span::call_site()),
-            paths: parse_quote!(Zzz, Aaa, Mmm),
+            types: None,
+            traits: parse_quote!(Zzz, Aaa, Mmm),
         };
 
         let traits = extract_traits(&ast);
@@ -272,4 +1498,358 @@ mod tests {
         assert_eq!(traits[1].ident.to_string(), "Mmm");
         assert_eq!(traits[2].ident.to_string(), "Zzz");
     }
+
+    #[test]
+    fn test_extract_traits_mutability() {
+        // Test that `mut` entries are recorded as mutable traits
+        let ast = Ast {
+            options: Options::default(),
+            name: Ident::new("Test", Span::call_site()),
+            types: None,
+            traits: parse_quote!(mut Write, Debug),
+        };
+
+        let traits = extract_traits(&ast);
+
+        assert_eq!(traits[0].ident.to_string(), "Debug");
+        assert!(!traits[0].mutable);
+        assert_eq!(traits[1].ident.to_string(), "Write");
+        assert!(traits[1].mutable);
+    }
+
+    #[test]
+    fn test_extract_traits_duplicates() {
+        // Test that a trait listed twice is merged, keeping it mutable if either entry is
+        let ast = Ast {
+            options: Options::default(),
+            name: Ident::new("Test", Span::call_site()),
+            types: None,
+            traits: parse_quote!(Write, ::std::fmt::Debug, std::fmt::Debug, mut Write),
+        };
+
+        let traits = extract_traits(&ast);
+
+        assert_eq!(traits.len(), 2);
+        assert_eq!(traits[0].ident.to_string(), "Debug");
+        assert!(traits[0].path.leading_colon.is_some());
+        assert_eq!(traits[1].ident.to_string(), "Write");
+        assert!(traits[1].mutable);
+    }
+
+    #[test]
+    fn test_extract_traits_bounds() {
+        // Test that the bounds are kept per trait, and that a duplicate may list them in any order
+        let ast = Ast {
+            options: Options::default(),
+            name: Ident::new("Test", Span::call_site()),
+            types: None,
+            traits: parse_quote!(Debug + Send + Sync, Display, Debug + Sync + Send),
+        };
+
+        let traits = extract_traits(&ast);
+
+        assert_eq!(traits.len(), 2);
+        assert_eq!(normalize_bounds(traits[0].bounds), ["Send", "Sync"]);
+        assert!(traits[1].bounds.is_empty());
+    }
+
+    #[test]
+    fn test_extract_traits_alias() {
+        // Test that an alias replaces the name, while the full path is kept
+        let ast = Ast {
+            options: Options::default(),
+            name: Ident::new("Test", Span::call_site()),
+            types: None,
+            traits: parse_quote!(very::long::path::Trait as ShortName, Debug),
+        };
+
+        let traits = extract_traits(&ast);
+
+        assert_eq!(traits[1].ident.to_string(), "ShortName");
+        assert_eq!(traits[1].path.segments.len(), 4);
+        assert_eq!(traits[1].path.segments[3].ident.to_string(), "Trait");
+
+        let variants = generate_enum_variants(&ast, &traits, &[]);
+        assert!(variants.iter().any(|v| v.ident == "TestDebugShortName"));
+    }
+
+    #[test]
+    fn test_generate_enum_variants_raw_and_non_ascii() {
+        // Test that raw identifiers are named without `r#`, and that names are ordered by bytes
+        let ast = Ast {
+            options: Options::default(),
+            name: Ident::new("Test", Span::call_site()),
+            types: None,
+            traits: parse_quote!(Ωmega, r#match, Ärger, Zeta),
+        };
+
+        let traits = extract_traits(&ast);
+        let names: Vec<_> = traits.iter().map(|t| ident_name(t.ident)).collect();
+        assert_eq!(names, ["Zeta", "match", "Ärger", "Ωmega"]);
+        assert_eq!(traits[1].ident.to_string(), "r#match");
+
+        let variants = generate_enum_variants(&ast, &traits, &[]);
+        let names: Vec<_> = variants.iter().map(|v| v.ident.to_string()).collect();
+        assert_eq!(names[0], "TestZetamatchÄrgerΩmega");
+        assert!(names.contains(&"Testmatch".to_string()));
+        assert!(names.contains(&"TestmatchΩmega".to_string()));
+    }
+
+    #[test]
+    fn test_extract_traits_associated_types() {
+        // Test that bindings are kept in the path, but not in the name
+        let ast = Ast {
+            options: Options::default(),
+            name: Ident::new("Test", Span::call_site()),
+            types: None,
+            traits: parse_quote!(mut Iterator<Item = u8>, Debug),
+        };
+
+        let traits = extract_traits(&ast);
+
+        assert_eq!(traits[1].ident.to_string(), "Iterator");
+        let path = traits[1].path;
+        assert_eq!(
+            quote::quote!(#path).to_string(),
+            quote::quote!(Iterator<Item = u8>).to_string()
+        );
+
+        let variants = generate_enum_variants(&ast, &traits, &[]);
+        assert_eq!(variants[0].ident.to_string(), "TestDebugIterator");
+    }
+
+    #[test]
+    fn test_generate_enum_variants_implies() {
+        // Test that combinations violating a declared implication are pruned
+        let ast = Ast {
+            options: Options {
+                implies: vec![options::Implication {
+                    from: parse_quote!(DisplayExt),
+                    to: parse_quote!(std::fmt::Display),
+                }],
+                ..Default::default()
+            },
+            name: Ident::new("Test", Span::call_site()),
+            types: None,
+            traits: parse_quote!(Debug, Display, DisplayExt),
+        };
+
+        let traits = extract_traits(&ast);
+        let implications = extract_implications(&ast, &traits);
+        let variants = generate_enum_variants(&ast, &traits, &implications);
+
+        let names: Vec<_> = variants.iter().map(|v| v.ident.to_string()).collect();
+
+        // 2^3 = 8 combinations, minus the 2 that have `DisplayExt` without `Display`
+        assert_eq!(
+            names,
+            [
+                "TestDebugDisplayDisplayExt",
+                "TestDebugDisplay",
+                "TestDisplayDisplayExt",
+                "TestDebug",
+                "TestDisplay",
+                "TestNone",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_generate_enum_variants_universal() {
+        // Test that the universal trait is added to every variant, leaving no variant without
+        // traits
+        let ast = Ast {
+            options: Options {
+                universal: Some(parse_quote!(any::Universal)),
+                ..Default::default()
+            },
+            name: Ident::new("Test", Span::call_site()),
+            types: None,
+            traits: parse_quote!(Debug, Display),
+        };
+
+        let traits = extract_traits(&ast);
+        assert_eq!(traits.len(), 3);
+        assert_eq!(traits[2].path.segments.len(), 2);
+
+        let variants = generate_enum_variants(&ast, &traits, &[]);
+        let names: Vec<_> = variants.iter().map(|v| v.ident.to_string()).collect();
+        assert_eq!(
+            names,
+            [
+                "TestDebugDisplayUniversal",
+                "TestDebugUniversal",
+                "TestDisplayUniversal",
+                "TestUniversal",
+            ]
+        );
+
+        // Listing the universal trait doesn't add it twice.
+        let ast = Ast {
+            options: Options {
+                universal: Some(parse_quote!(Universal)),
+                ..Default::default()
+            },
+            name: Ident::new("Test", Span::call_site()),
+            types: None,
+            traits: parse_quote!(Debug, mut Universal),
+        };
+
+        let traits = extract_traits(&ast);
+        assert_eq!(traits.len(), 2);
+        assert!(traits[1].mutable);
+        assert_eq!(generate_enum_variants(&ast, &traits, &[]).len(), 2);
+    }
+
+    #[test]
+    fn test_analyze_raw_enum_name() {
+        // Test that the identifiers derived from a raw enum name don't contain the `r#` prefix
+        let ast = Ast {
+            options: Options::default(),
+            name: parse_quote!(r#match),
+            types: None,
+            traits: parse_quote!(Debug),
+        };
+
+        let model = analyze(&ast);
+
+        assert_eq!(model.wrap_ident.to_string(), "Wrapmatch");
+        let names: Vec<_> = model
+            .enum_variants
+            .iter()
+            .map(|v| v.ident.to_string())
+            .collect();
+        assert_eq!(names, ["matchDebug", "matchNone"]);
+    }
+
+    #[test]
+    fn test_analyze_types() {
+        // Test that types mode names a variant after every type, without trait combinations
+        let ast = Ast {
+            options: Options::default(),
+            name: Ident::new("Test", Span::call_site()),
+            types: Some(parse_quote!(String, i32, std::path::PathBuf, Vec<u8>)),
+            traits: parse_quote!(Debug, Display),
+        };
+
+        let model = analyze(&ast);
+
+        let names: Vec<_> = model
+            .types
+            .unwrap()
+            .iter()
+            .map(|t| t.ident.to_string())
+            .collect();
+        assert_eq!(names, ["String", "I32", "PathBuf", "Vec"]);
+        assert_eq!(model.traits.len(), 2);
+        assert!(model.enum_variants.is_empty());
+    }
+
+    #[test]
+    fn test_analyze_bitset() {
+        // Test that no variants are generated in bitset mode, even for many traits
+        let ast = Ast {
+            options: Options {
+                bitset: true,
+                ..Default::default()
+            },
+            name: Ident::new("Test", Span::call_site()),
+            types: None,
+            traits: parse_quote!(A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T),
+        };
+
+        let model = analyze(&ast);
+
+        assert_eq!(model.traits.len(), 20);
+        assert!(model.enum_variants.is_empty());
+    }
+
+    #[test]
+    fn test_analyze_auto() {
+        // Test that auto mode only replaces the enum for a single trait
+        let single: Ast = parse_quote!(
+            #[trait_mux(mode = auto)]
+            D { Debug }
+        );
+        let model = analyze(&single);
+        assert!(model.monomorphic);
+        assert!(model.enum_variants.is_empty());
+
+        let multiple: Ast = parse_quote!(
+            #[trait_mux(mode = auto)]
+            D { Debug, Display }
+        );
+        let model = analyze(&multiple);
+        assert!(!model.monomorphic);
+        assert_eq!(model.enum_variants.len(), 4);
+    }
+
+    #[test]
+    fn test_analyze_combined() {
+        // Test that a combination of traits is a single trait, with no variant per combined trait
+        let ast: Ast = parse_quote!(D{(Debug + Display), Clone});
+
+        let model = analyze(&ast);
+        let mut variants = model
+            .enum_variants
+            .iter()
+            .map(|v| v.ident.to_string())
+            .collect::<Vec<_>>();
+        variants.sort();
+
+        assert_eq!(
+            variants,
+            ["DClone", "DCloneDebugDisplay", "DDebugDisplay", "DNone"]
+        );
+        assert_eq!(model.combined.len(), 1);
+        assert_eq!(model.combined[0].name.to_string(), "DDebugDisplay");
+        let combined = model.combined[0]
+            .traits
+            .iter()
+            .map(|t| t.ident.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(combined, ["Debug", "Display"]);
+    }
+
+    #[test]
+    fn test_analyze_priority_chain() {
+        // Test that a variant is generated per trait in declaration order, plus one without traits
+        let ast = Ast {
+            options: Options {
+                mode: Mode::PriorityChain,
+                ..Default::default()
+            },
+            name: Ident::new("Test", Span::call_site()),
+            types: None,
+            traits: parse_quote!(Display, Debug, Display, Binary),
+        };
+
+        let model = analyze(&ast);
+        let variants = model
+            .enum_variants
+            .iter()
+            .map(|v| v.ident.to_string())
+            .collect::<Vec<_>>();
+
+        assert_eq!(model.traits.len(), 3);
+        assert_eq!(
+            variants,
+            ["TestDisplay", "TestDebug", "TestBinary", "TestNone"]
+        );
+        assert!(
+            model
+                .enum_variants
+                .iter()
+                .all(|v| v.implemented_traits.len() <= 1)
+        );
+    }
+
+    #[test]
+    fn test_uses_enum_lifetime() {
+        assert!(uses_enum_lifetime(&parse_quote!(Named<'t>)));
+        assert!(uses_enum_lifetime(&parse_quote!(module::Both<'static, 't>)));
+        assert!(!uses_enum_lifetime(&parse_quote!(Named<'static>)));
+        assert!(!uses_enum_lifetime(&parse_quote!(Iterator<Item = &'t str>)));
+        assert!(!uses_enum_lifetime(&parse_quote!(std::fmt::Debug)));
+    }
 }
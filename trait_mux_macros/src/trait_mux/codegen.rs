@@ -1,32 +1,207 @@
 //! This module is responsible for generating Rust code from the lowered intermediate
 //! representation (IR) produced during the macro processing phase.
 
-use proc_macro2::TokenStream;
+use proc_macro2::{Ident, Literal, Span, TokenStream, TokenTree};
 use quote::quote;
+use syn::ext::IdentExt;
+use syn::{LitStr, Path};
 
-use crate::lower::{AutorefSpecializer, Constraint, EnumVariant, Function, Ir, TraitAggregate};
+use crate::analyze::ConcreteType;
+use crate::lower::{
+    Access, AccessMethod, AutorefSpecializer, Bitset, BitsetTrait, Collection, Constraint,
+    EnumVariant, Extension, Formatting, Function, Ir, IterItem, MacroIdents, MapArm, Monomorphic,
+    Owned, Primary, Reexport, SelectionStep, Strict, TraitAggregate, TraitRef, Types,
+    VariantsTableRow, WithAccessor,
+};
+use crate::parse::{TraitSet, TraitSetSplice, TraitSetUse};
 
-/// Creates a TokenStream containing a sequence of `n` reference operators (`&`).
+/// Creates the expression selecting the tag of the variant a value is converted into.
+/// Every selection step wraps the state left by the previous one, starting from `()`, using a
+/// two-level autoref specialization on the wrap struct. The final state then returns the tag of
+/// the variant implementing exactly the detected traits.
 ///
 /// # Arguments
 ///
-/// * `n` - The number of reference operators to generate
+/// * `ir` - The intermediate representation containing the selection steps
+/// * `value` - The value to select the tag for, e.g. `$var`
 ///
 /// # Returns
 ///
-/// A TokenStream containing `n` consecutive `&` operators
-fn refs(n: usize) -> TokenStream {
-    let mut refs = TokenStream::new();
-    for _ in 0..n {
-        refs.extend(quote![&]);
+/// A TokenStream for the expression, e.g.
+/// `(&&Wrap(&$var)).into_type_display_step((&&Wrap(&$var)).into_type_debug_step(())).into_tag()`
+fn select_tag(ir: &Ir, value: TokenStream) -> TokenStream {
+    let wrap = ir.wrap_ident;
+    let into_tag = &ir.into_tag;
+
+    let state = ir.steps.iter().fold(quote! {()}, |state, step| {
+        let into_step = &step.into_step;
+        quote! {(&&#wrap(&#value)).#into_step(#state)}
+    });
+
+    quote! {#state.#into_tag()}
+}
+
+/// Creates the type of the state the selection steps leave for a variant.
+///
+/// # Arguments
+///
+/// * `ir` - The intermediate representation containing the selection steps
+/// * `implemented` - Whether the variant implements the trait of each step, for the last steps if
+///   there are fewer of them, leaving the state of the first steps generic as `S`
+///
+/// # Returns
+///
+/// A TokenStream for the state type, e.g. `TypeDisplayNoneStep<TypeDebugStep<()>>`
+fn selection_state(ir: &Ir, implemented: &[bool]) -> TokenStream {
+    let skipped = ir.steps.len() - implemented.len();
+    let initial = if skipped == 0 {
+        quote! {()}
+    } else {
+        quote! {S}
+    };
+
+    ir.steps[skipped..]
+        .iter()
+        .zip(implemented)
+        .fold(initial, |state, (step, implemented)| {
+            let step = if *implemented {
+                &step.step
+            } else {
+                &step.none_step
+            };
+            quote! {#step<#state>}
+        })
+}
+
+/// Creates a TokenStream containing the borrow operator used to pass the value into the enum.
+///
+/// # Arguments
+///
+/// * `borrow_mut` - Whether the value is borrowed mutably
+///
+/// # Returns
+///
+/// A TokenStream containing either `&mut` or `&`
+fn borrow(borrow_mut: bool) -> TokenStream {
+    if borrow_mut { quote![&mut] } else { quote![&] }
+}
+
+/// Creates the `non_camel_case_types` allowance for a type named by, or with variants named by,
+/// identifiers not starting with an uppercase letter, e.g. `r#match`.
+///
+/// # Arguments
+///
+/// * `idents` - The identifiers naming the type and its variants
+///
+/// # Returns
+///
+/// A TokenStream containing the `#[allow(non_camel_case_types)]` attribute, or an empty
+/// TokenStream if every identifier starts with an uppercase letter
+fn allow_non_camel_case<'a>(idents: impl IntoIterator<Item = &'a Ident>) -> TokenStream {
+    let lowercase = idents.into_iter().any(|ident| {
+        !ident
+            .unraw()
+            .to_string()
+            .starts_with(|c: char| c.is_uppercase())
+    });
+
+    if lowercase {
+        quote! {#[allow(non_camel_case_types)]}
+    } else {
+        TokenStream::new()
+    }
+}
+
+/// Creates a TokenStream for a trait object type, parenthesized along with its auto-trait bounds so
+/// it can be referenced.
+///
+/// # Arguments
+///
+/// * `path` - The path to the trait
+/// * `bounds` - The auto-trait bounds of the trait entry
+///
+/// # Returns
+///
+/// A TokenStream containing the trait object type, e.g. `dyn Debug` or `(dyn Debug + Send)`
+fn object(path: &Path, bounds: &[Path]) -> TokenStream {
+    if bounds.is_empty() {
+        quote! {dyn #path}
+    } else {
+        quote! {(dyn #path #(+ #bounds)*)}
+    }
+}
+
+/// Creates a TokenStream for an enum payload, stored in the wrapper type if one is set.
+///
+/// # Arguments
+///
+/// * `ir` - The intermediate representation containing the wrapper type
+/// * `reference` - The trait object reference, e.g. `&'t dyn Debug`
+///
+/// # Returns
+///
+/// A TokenStream containing the parenthesized payload, e.g. `(Guard<&'t dyn Debug>)`
+fn wrap_payload(ir: &Ir, reference: TokenStream) -> TokenStream {
+    match ir.wrapper {
+        Some(wrapper) => quote! {(#wrapper<#reference>)},
+        None => quote! {(#reference)},
+    }
+}
+
+/// Generates a helper macro, defined under its inner name and re-exported under its public name.
+/// By default the macro is exported at the crate root, with `scoped_macros` it only lives in the
+/// module the enum is generated in.
+///
+/// # Arguments
+///
+/// * `ir` - The intermediate representation
+/// * `attrs` - Attributes applied to both the definition and the re-export, e.g. `#[cfg(test)]`
+/// * `inner` - The identifier the macro is defined with
+/// * `public` - The identifier the macro is re-exported as
+/// * `rules` - The rules of the macro
+///
+/// # Returns
+///
+/// A TokenStream for the macro definition and its re-export
+fn generate_macro(
+    ir: &Ir,
+    attrs: TokenStream,
+    inner: &Ident,
+    public: &Ident,
+    rules: TokenStream,
+) -> TokenStream {
+    // Scoped macros can't be re-exported publicly. An exported macro defined in a function body is
+    // still exported at the crate root, which is what the macro is invoked there for.
+    let (export, vis) = if ir.scoped_macros {
+        (quote! {}, quote! {pub(crate)})
+    } else {
+        (
+            quote! {#[macro_export] #[allow(non_local_definitions)]},
+            quote! {pub},
+        )
+    };
+
+    // The macros may be left unused, e.g. when only the accessors of the enum are needed, and so
+    // may their re-exports, e.g. in a private module or a doctest.
+    quote! {
+        #attrs
+        #export
+        #[allow(unused_macros)]
+        macro_rules! #inner {
+            #rules
+        }
+        #attrs
+        #[allow(unused_imports)]
+        #vis use #inner as #public;
     }
-    refs
 }
 
 /// Generates the complete Rust code from the intermediate representation.
 ///
 /// This function orchestrates the code generation by combining all the different
 /// code elements created by the specialized generator functions.
+/// Every section is emitted in the order of the vectors in the IR, which is derived from the
+/// declaration order and the sorted variants, so identical input produces identical output.
 ///
 /// # Arguments
 ///
@@ -36,419 +211,6463 @@ fn refs(n: usize) -> TokenStream {
 ///
 /// A TokenStream containing all the generated code
 pub fn codegen(ir: Ir) -> TokenStream {
+    let items = generate_items(&ir);
+
+    match &ir.reexport {
+        Some(reexport) => generate_reexport(reexport, items),
+        None => items,
+    }
+}
+
+/// Wraps the generated items in a module and re-exports them into the invocation scope, for the
+/// `reexport_at` option.
+/// A macro can only emit items where it's invoked, so the re-export can't be placed at another
+/// path, e.g. the crate root. The option instead controls the visibility of the re-exported items,
+/// while the module keeps the imports of the invocation scope through `use super::*`.
+/// The macros defined with `#[macro_export]` are still exported at the crate root.
+///
+/// # Arguments
+///
+/// * `reexport` - The name of the wrapping module and the visibility of the re-export
+/// * `items` - The generated items
+///
+/// # Returns
+///
+/// A TokenStream for the module and the glob re-export of its public items
+fn generate_reexport(reexport: &Reexport, items: TokenStream) -> TokenStream {
+    let Reexport { module, vis } = reexport;
+
+    // Not every import of the invocation scope is used by the items, nor every item re-exported.
+    quote! {
+        #[doc(hidden)]
+        mod #module {
+            #[allow(unused_imports)]
+            use super::*;
+
+            #items
+        }
+        #[allow(unused_imports)]
+        #vis use #module::*;
+    }
+}
+
+/// Generates the items for the intermediate representation, in the invocation scope or in the
+/// module wrapping them.
+///
+/// # Arguments
+///
+/// * `ir` - The intermediate representation to generate code from
+///
+/// # Returns
+///
+/// A TokenStream containing all the generated items
+fn generate_items(ir: &Ir) -> TokenStream {
     let mut result = TokenStream::new();
 
-    result.extend(generate_wrap(&ir));
-    result.extend(generate_trait_aggregates(&ir));
-    result.extend(generate_enum(&ir));
-    result.extend(generate_enum_impl(&ir));
-    result.extend(generate_autoref_specializers(&ir));
+    result.extend(generate_helpers(ir));
+    result.extend(generate_primary(ir));
+    result.extend(generate_trait_list(ir));
+    result.extend(generate_write_to(ir));
+    result.extend(generate_access(ir));
+    result.extend(generate_with_accessors(ir));
+
+    // In types mode the values are moved into the enum directly, so no macros are generated.
+    if let Some(types) = &ir.types {
+        result.extend(generate_types(ir, types));
+        return result;
+    }
+
+    // In bitset and monomorphic modes the only aggregates are the traits generated for
+    // combinations of traits.
+    result.extend(generate_trait_aggregates(ir));
+    match (&ir.bitset, &ir.monomorphic) {
+        (Some(bitset), _) => result.extend(generate_bitset(ir, bitset)),
+        (None, Some(monomorphic)) => result.extend(generate_monomorphic(ir, monomorphic)),
+        (None, None) => {
+            result.extend(generate_enum(ir));
+            result.extend(generate_enum_impl(ir));
+            match ir.opaque {
+                Some(opaque) => result.extend(generate_opaque(ir, opaque)),
+                None => result.extend(generate_trait_ref(ir)),
+            }
+            result.extend(generate_erased(ir));
+            result.extend(generate_shared(ir));
+            result.extend(generate_error_impl(ir));
+            result.extend(generate_eq_by_variant(ir));
+        }
+    }
+
+    if let Some(owned) = &ir.owned {
+        result.extend(generate_owned(ir, owned));
+    }
 
     let into = &ir.into;
     let inner_into = &ir.inner_into;
-    let into_tag = &ir.into_tag;
-    let refs = refs(ir.wrap_derefs);
-    let wrap = &ir.wrap_ident;
+    let into_expr = match (&ir.bitset, &ir.monomorphic) {
+        (Some(bitset), _) => generate_bitset_into_expr(ir, bitset),
+        (None, Some(monomorphic)) => generate_monomorphic_into_expr(ir, monomorphic),
+        (None, None) => {
+            let tag = select_tag(ir, quote! {$var});
+            let borrow = borrow(ir.borrow_mut);
+
+            quote! {
+                #tag.#into(#borrow $var)
+            }
+        }
+    };
 
     // Generate a helper macro to convert values into the enum
-    result.extend(quote! {
-        #[macro_export]
-        macro_rules! #inner_into {
+    result.extend(generate_macro(
+        ir,
+        quote! {},
+        inner_into,
+        into,
+        quote! {
             ($var:tt) => {
-                (#refs #wrap(&$var)).#into_tag().#into(&$var)
+                #into_expr
             }
-        }
-        pub use #inner_into as #into;
-    });
+        },
+    ));
+
+    result.extend(generate_strict(ir));
+    result.extend(generate_extension(ir));
+    result.extend(generate_from_any_as(ir));
+    result.extend(generate_dispatch_array(ir));
+    result.extend(generate_dispatch_eq(ir));
+    result.extend(generate_dispatch_tuple(ir));
+    result.extend(generate_into_opt_result(ir));
+    result.extend(generate_collection(ir));
+    result.extend(generate_counter(ir));
+    result.extend(generate_matches_variant(ir));
+    result.extend(generate_variant_traits(ir));
+    result.extend(generate_assert_dispatch(ir));
+    result.extend(generate_self_test(ir));
 
     result
 }
 
-/// Generates the wrapper struct that holds a reference to the original value.
-/// The wrapper is necessary to support proper specialization for the original
-/// type, and not its reference.
+/// Writes the generated code to the file set by the `dump_to` option, for inspection.
+/// Relative paths are resolved from the root of the crate invoking the macro.
+/// Emits an error if the file can't be written.
 ///
 /// # Arguments
 ///
-/// * `ir` - The intermediate representation containing the wrap identifier
+/// * `ts` - The generated code
+/// * `path` - The path of the file
+pub fn dump(ts: &TokenStream, path: &LitStr) {
+    let mut file =
+        std::path::PathBuf::from(std::env::var_os("CARGO_MANIFEST_DIR").unwrap_or_default());
+    file.push(path.value());
+
+    if let Err(e) = write_dump(ts, &file) {
+        proc_macro_error::emit_error!(
+            path,
+            "failed to write the generated code to `{}`: {}",
+            file.display(),
+            e
+        );
+    }
+}
+
+/// Writes the generated code to a file, creating its parent directories if needed.
+///
+/// # Arguments
+///
+/// * `ts` - The generated code
+/// * `file` - The path of the file
+///
+/// # Errors
+///
+/// Returns the error of the failed file system operation
+fn write_dump(ts: &TokenStream, file: &std::path::Path) -> std::io::Result<()> {
+    if let Some(parent) = file.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(file, format!("{}\n", ts))
+}
+
+/// Generates the `macro_rules!` macro of a trait set registered with `trait_mux_traits!`, e.g.
+/// `fmt_traits!`. It appends the trait list to its input and passes it to `trait_mux!`, which
+/// must be in scope where the set is used.
+/// When the set is spliced into a trait list, the macro instead receives the tokens preceding the
+/// list and its other entries, and prepends its traits to those entries.
+///
+/// # Arguments
+///
+/// * `set` - The registered trait set
 ///
 /// # Returns
 ///
-/// A TokenStream for the wrapper struct definition
-fn generate_wrap(ir: &Ir) -> TokenStream {
-    let wrap = ir.wrap_ident;
+/// A TokenStream for the macro definition
+pub fn generate_trait_set(set: &TraitSet) -> TokenStream {
+    let TraitSet { name, traits } = set;
 
+    // The spliced entries follow the set's, which need a trailing comma to be separated from them.
+    let mut entries = traits.stream();
+    let separated = match entries.clone().into_iter().last() {
+        None => true,
+        Some(TokenTree::Punct(p)) => p.as_char() == ',',
+        Some(_) => false,
+    };
+    if !separated {
+        entries.extend(quote! {,});
+    }
+
+    // The set may be registered for later use only.
     quote! {
-        pub struct #wrap<'t, T>(pub &'t T);
+        #[allow(unused_macros)]
+        macro_rules! #name {
+            (@splice [$($head:tt)*] {$($rest:tt)*}) => {
+                trait_mux!($($head)* {#entries $($rest)*});
+            };
+            ($($input:tt)*) => {
+                trait_mux!($($input)* #traits);
+            };
+        }
     }
 }
 
-/// Generates trait aggregates that combine multiple traits into a single trait.
+/// Generates the use of a registered trait set, invoking the set's macro with the attributes and
+/// the name, e.g. `fmt_traits!(#[trait_mux(owned)] SomeName);` for
+/// `#[trait_mux(owned)] SomeName = @fmt_traits`.
 ///
 /// # Arguments
 ///
-/// * `ir` - The intermediate representation containing trait aggregates
+/// * `set_use` - The use of the trait set
 ///
 /// # Returns
 ///
-/// A TokenStream for all trait aggregate definitions and their implementations
-fn generate_trait_aggregates(ir: &Ir) -> TokenStream {
-    let mut trait_aggregates = TokenStream::new();
+/// A TokenStream for the invocation of the set's macro
+pub fn generate_trait_set_use(set_use: &TraitSetUse) -> TokenStream {
+    let TraitSetUse { attrs, name, set } = set_use;
 
-    ir.trait_aggregates
-        .iter()
-        .map(|TraitAggregate { name, traits }| {
-            let traits: Vec<_> = traits.iter().map(|t| t.path).collect();
+    quote! {
+        #set!(#(#attrs)* #name);
+    }
+}
 
-            trait_aggregates.extend(quote! {
-                pub trait #name: #(#traits)+* {}
-                impl<T: #(#traits)+*> #name for T {}
-            });
-        })
-        .count();
+/// Generates the splice of a registered trait set into a trait list, invoking the set's macro with
+/// the tokens preceding the list and its other entries, e.g.
+/// `fmt_traits!(@splice [SomeName] {Clone});` for `SomeName{@fmt_traits, Clone}`.
+///
+/// # Arguments
+///
+/// * `splice` - The trait list split around the spliced set
+///
+/// # Returns
+///
+/// A TokenStream for the invocation of the set's macro
+pub fn generate_trait_set_splice(splice: &TraitSetSplice) -> TokenStream {
+    let TraitSetSplice { head, set, rest } = splice;
 
-    trait_aggregates
+    quote! {
+        #set!(@splice [#head] {#rest});
+    }
 }
 
-/// Generates the enum definition based on the intermediate representation.
+/// Generates the extension trait, e.g. `IntoMyMux`, converting a value into the enum with a
+/// method, e.g. `value.as_my_mux()`, and implements it for every listed type, along with
+/// `From<&'t T>` for the enum.
+///
+/// Neither can have a blanket implementation: autoref specialization selects the variant from
+/// the bounds known where the into macro is expanded, and inside `impl<T> IntoMyMux for T` or
+/// `impl<'t, T> From<&'t T>` nothing is known about `T`, so every value would end up in the
+/// variant without traits.
+/// Each listed concrete type instead gets its own implementations, expanding the into macro for
+/// that type, so the conversions select the same maximal variant as the macro, without
+/// overlapping.
+/// If any trait is mutable, the method takes `&mut self`, and the conversion `&'t mut T`.
 ///
 /// # Arguments
 ///
-/// * `ir` - The intermediate representation containing the enum definition
+/// * `ir` - The intermediate representation containing the extension trait
 ///
 /// # Returns
 ///
-/// A TokenStream for the enum definition
-fn generate_enum(ir: &Ir) -> TokenStream {
-    let enum_name = ir.r#enum.name;
+/// A TokenStream for the extension trait and the implementations, or an empty TokenStream if no
+/// type is listed
+fn generate_extension(ir: &Ir) -> TokenStream {
+    let Some(Extension {
+        name,
+        method,
+        types,
+    }) = &ir.extension
+    else {
+        return TokenStream::new();
+    };
+    let enum_name = public_name(ir);
+    let inner_into = &ir.inner_into;
+    let receiver = borrow(ir.borrow_mut);
+    let reference = if ir.borrow_mut {
+        quote! {&'t mut}
+    } else {
+        quote! {&'t}
+    };
 
-    let mut enum_fields = TokenStream::new();
+    quote! {
+        pub trait #name {
+            fn #method(#receiver self) -> #enum_name<'_>;
+        }
 
-    for EnumVariant { ident, constraint } in &ir.r#enum.variants {
-        let constraint = match constraint {
-            Constraint::None => quote! {},
-            Constraint::Path(path) => quote! {(&'t dyn #path)},
-            Constraint::Ident(ident) => quote! {(&'t dyn #ident)},
-        };
+        #(
+            impl #name for #types {
+                fn #method(#receiver self) -> #enum_name<'_> {
+                    #inner_into!((*self))
+                }
+            }
 
-        enum_fields.extend(quote! {
-            #ident #constraint,
-        });
+            impl<'t> ::core::convert::From<#reference #types> for #enum_name<'t> {
+                fn from(value: #reference #types) -> Self {
+                    #inner_into!((*value))
+                }
+            }
+        )*
     }
+}
+
+/// Generates the strict into macro, e.g. `into_my_mux_strict!(value)`, and the marker trait
+/// making it fail to compile for values implementing none of the traits.
+///
+/// The macro selects a tag exactly like the into macro, but calls the tags' strict function.
+/// The strict function of the tag without traits requires the value to implement the marker
+/// trait, which no type implements, so selecting that tag results in an unsatisfied trait bound
+/// at the macro call site, reported with the message set by `diagnostic::on_unimplemented`.
+///
+/// # Arguments
+///
+/// * `ir` - The intermediate representation containing the strict macro identifiers
+///
+/// # Returns
+///
+/// A TokenStream for the marker trait and the strict macro, or an empty TokenStream if strict
+/// mode is disabled
+fn generate_strict(ir: &Ir) -> TokenStream {
+    let Some(Strict {
+        r#macro,
+        into,
+        r#trait,
+    }) = &ir.strict
+    else {
+        return TokenStream::new();
+    };
+    let tag = select_tag(ir, quote! {$var});
+    let borrow = borrow(ir.borrow_mut);
+    let message = format!(
+        "`{{Self}}` implements none of the traits of `{}`",
+        ir.r#enum.name
+    );
+
+    let strict_macro = generate_macro(
+        ir,
+        quote! {},
+        &r#macro.inner,
+        &r#macro.public,
+        quote! {
+            ($var:tt) => {
+                #tag.#into(#borrow $var)
+            }
+        },
+    );
 
     quote! {
-        pub enum #enum_name<'t> {
-            #enum_fields
-        }
+        #[doc(hidden)]
+        #[diagnostic::on_unimplemented(message = #message)]
+        pub trait #r#trait {}
+
+        #strict_macro
     }
 }
 
-/// Generates the implementation of the enum, including methods for accessing
-/// the enum variants.
+/// Generates the struct used in bitset mode instead of the enum, along with its implementation.
+///
+/// The struct holds an erased pointer to the value and a mask with a bit set for every implemented
+/// trait, in the sorted trait order. Producing a `&dyn Trait` requires the trait's vtable, so the
+/// struct also refers to a table of casts back into every trait object, a constant built once per
+/// concrete type out of the tags selected for it. Each trait is detected separately, so the
+/// generated code grows linearly with the number of traits rather than exponentially.
 ///
 /// # Arguments
 ///
-/// * `ir` - The intermediate representation containing the enum implementation
+/// * `ir` - The intermediate representation containing the struct name
+/// * `bitset` - The separately detected traits and the name of the table of casts
 ///
 /// # Returns
 ///
-/// A TokenStream for the enum implementation
-fn generate_enum_impl(ir: &Ir) -> TokenStream {
+/// A TokenStream for the struct definition, its implementation and the table of casts
+fn generate_bitset(ir: &Ir, bitset: &Bitset) -> TokenStream {
     let enum_name = ir.r#enum.name;
+    let generic = &ir.value_generic;
+    let Bitset { traits, vtables } = bitset;
 
-    let mut fns = TokenStream::new();
+    let paths: Vec<_> = traits.iter().map(|t| t.path).collect();
+    let erases: Vec<_> = traits.iter().map(|t| &t.erase).collect();
+    let accessors = traits.iter().map(|t| &t.accessor);
+    let docs = traits.iter().map(|t| t.docs);
+    let indices = (0..traits.len()).map(syn::Index::from);
+    let bits: Vec<_> = (0..traits.len()).map(|i| i as u32).collect();
+    let params: Vec<_> = (0..traits.len())
+        .map(|i| Ident::new(&format!("__E{}", i), Span::call_site()))
+        .collect();
 
-    for Function {
-        name,
-        result_path,
-        matching_variants,
-    } in &ir.enum_impl.functions
+    let casts = quote! {
+        (#(unsafe fn(*const ()) -> *const (dyn #paths + 'static),)*)
+    };
+
+    let mut erase_impls = TokenStream::new();
+    for BitsetTrait {
+        path,
+        tag,
+        none_tag,
+        erase,
+        ..
+    } in traits
     {
-        fns.extend(quote! {
-            pub fn #name(&self) -> ::core::option::Option<&dyn #result_path> {
-                match self {
-                    #(#enum_name::#matching_variants (v) => Some(*v),)*
-                    _ => None,
+        erase_impls.extend(quote! {
+            #[doc(hidden)]
+            pub trait #erase<#generic> {
+                const IMPLEMENTED: bool;
+
+                /// # Safety
+                ///
+                /// `value` must point to a live `T`.
+                unsafe fn cast(value: *const ()) -> *const (dyn #path + 'static);
+            }
+
+            impl<#generic: #path> #erase<#generic> for #tag {
+                const IMPLEMENTED: bool = true;
+
+                unsafe fn cast(value: *const ()) -> *const (dyn #path + 'static) {
+                    let object: *const (dyn #path + '_) = value.cast::<#generic>();
+                    // SAFETY: only the lifetime bound of the trait object is changed, not its
+                    // layout. The struct holding it doesn't outlive the value.
+                    unsafe { ::core::mem::transmute(object) }
+                }
+            }
+
+            impl<#generic> #erase<#generic> for #none_tag {
+                const IMPLEMENTED: bool = false;
+
+                unsafe fn cast(_: *const ()) -> *const (dyn #path + 'static) {
+                    ::core::unreachable!()
                 }
             }
         });
     }
 
+    let allow = allow_non_camel_case([enum_name]);
+
     quote! {
+        #allow
+        pub struct #enum_name<'t> {
+            value: *const (),
+            casts: &'static #casts,
+            mask: u32,
+            lifetime: ::core::marker::PhantomData<&'t ()>,
+        }
+
+        #erase_impls
+
+        #[doc(hidden)]
+        pub struct #vtables<#generic, #(#params),*>(
+            ::core::marker::PhantomData<(fn(&#generic), #(#params),*)>,
+        );
+
+        impl<#generic, #(#params: #erases<#generic>),*> #vtables<#generic, #(#params),*> {
+            const CASTS: &'static #casts = &(#(<#params as #erases<#generic>>::cast,)*);
+            const MASK: u32 = 0 #(| (<#params as #erases<#generic>>::IMPLEMENTED as u32) << #bits)*;
+        }
+
+        #[allow(deprecated)]
         impl<'t> #enum_name<'t> {
-            #fns
+            /// # Safety
+            ///
+            /// The casts of the tags must convert a pointer to the value back into their trait
+            /// object, as the ones of the generated tags do.
+            #[doc(hidden)]
+            pub unsafe fn __new<#generic, #(#params: #erases<#generic>),*>(value: &'t #generic, #(_: #params),*) -> Self {
+                Self {
+                    value: (value as *const #generic).cast(),
+                    casts: #vtables::<#generic, #(#params),*>::CASTS,
+                    mask: #vtables::<#generic, #(#params),*>::MASK,
+                    lifetime: ::core::marker::PhantomData,
+                }
+            }
+
+            pub fn traits_mask(&self) -> u32 {
+                self.mask
+            }
+
+            pub fn is_none(&self) -> bool {
+                self.mask == 0
+            }
+
+            #(
+                #(#docs)*
+                pub fn #accessors(&self) -> ::core::option::Option<&dyn #paths> {
+                    if self.mask & (1 << #bits) == 0 {
+                        return ::core::option::Option::None;
+                    }
+                    // SAFETY: the bit is only set if the value implements the trait, so the cast
+                    // is the tag's one, converting the pointer to the value, which outlives `'t`.
+                    ::core::option::Option::Some(unsafe { &*(self.casts.#indices)(self.value) })
+                }
+            )*
         }
     }
 }
 
-/// Generates the autoref specializers, which are responsible for automatically
-/// referencing values and converting them into the appropriate enum variants.
+/// Generates the autoref specializers detecting each trait separately, used in bitset and types
+/// modes. Every trait gets a tag returning the trait object when the trait is implemented, and a
+/// tag returning None otherwise.
 ///
 /// # Arguments
 ///
-/// * `ir` - The intermediate representation containing the autoref specializers
+/// * `ir` - The intermediate representation containing the wrap identifier
+/// * `bitset` - The separately detected traits
 ///
 /// # Returns
 ///
-/// A TokenStream for all autoref specializer definitions and their implementations
-fn generate_autoref_specializers(ir: &Ir) -> TokenStream {
-    let mut autoref_specializers = TokenStream::new();
-
-    let enum_name = ir.r#enum.name;
+/// A TokenStream for the tags and match traits of every trait
+fn generate_bitset_specializers(ir: &Ir, bitset: &[BitsetTrait]) -> TokenStream {
+    let generic = &ir.value_generic;
     let wrap = ir.wrap_ident;
     let into = &ir.into;
-    let into_tag = &ir.into_tag;
+    let derive = ir.show_internals.then(|| quote! {#[derive(Debug)]});
+    let mut result = TokenStream::new();
 
-    ir.autoref_specializers
-        .iter()
-        .map(
-            |AutorefSpecializer {
-                 tag,
-                 r#match,
-                 deref_count,
-                 variant,
-                 constraint,
-             }| {
-                let refs = refs(*deref_count);
+    for BitsetTrait {
+        path,
+        tag,
+        none_tag,
+        r#match,
+        none_match,
+        into_tag,
+        ..
+    } in bitset
+    {
+        result.extend(quote! {
+            #derive
+            #[doc(hidden)]
+            pub struct #tag;
+            impl #tag {
+                pub fn #into<#generic: #path>(self, v: &#generic) -> ::core::option::Option<&dyn #path> {
+                    ::core::option::Option::Some(v)
+                }
+            }
 
-                let t_constraint = match constraint {
-                    Constraint::None => quote! {},
-                    Constraint::Path(path) => quote! {: #path},
-                    Constraint::Ident(ident) => quote! {: #ident},
-                };
+            #derive
+            #[doc(hidden)]
+            pub struct #none_tag;
+            impl #none_tag {
+                pub fn #into<#generic>(self, _: &#generic) -> ::core::option::Option<&dyn #path> {
+                    ::core::option::Option::None
+                }
+            }
 
-                let param = match constraint {
-                    Constraint::None => quote! {},
-                    Constraint::Path(_) | Constraint::Ident(_) => quote! {(v)},
+            #[doc(hidden)]
+
+            pub trait #r#match<#generic> {
+                fn #into_tag(&self) -> #tag;
+            }
+            impl<'t, #generic: #path> #r#match<#generic> for &#wrap<'t, #generic> {
+                fn #into_tag(&self) -> #tag {
+                    #tag
+                }
+            }
+
+            #[doc(hidden)]
+
+            pub trait #none_match<#generic> {
+                fn #into_tag(&self) -> #none_tag;
+            }
+            impl<'t, #generic> #none_match<#generic> for #wrap<'t, #generic> {
+                fn #into_tag(&self) -> #none_tag {
+                    #none_tag
+                }
+            }
+        });
+    }
+
+    result
+}
+
+/// Generates the enum used in types mode, holding the listed concrete types, along with its
+/// implementation.
+///
+/// As the types are known, each accessor detects whether the type of every variant implements the
+/// trait, like the bitset mode into macro does for a single value.
+/// Every type also gets a `From` implementation wrapping it in its variant.
+/// With `copy_accessors`, every accessor gets a `_copy` counterpart returning the single listed
+/// type by value, which fails to compile unless the type is `Copy`.
+///
+/// # Arguments
+///
+/// * `ir` - The intermediate representation containing the enum name
+/// * `types` - The listed concrete types and the separately detected traits
+///
+/// # Returns
+///
+/// A TokenStream for the enum definition, its implementation and the `From` implementations
+fn generate_types(ir: &Ir, types: &Types) -> TokenStream {
+    let enum_name = ir.r#enum.name;
+    let attrs = ir.r#enum.attrs;
+    let wrap = ir.wrap_ident;
+    let into = &ir.into;
+
+    let variants: Vec<_> = types.variants.iter().map(|t| &t.ident).collect();
+    let variant_names = variants.iter().map(|v| v.to_string());
+    let tys: Vec<_> = types.variants.iter().map(|t| t.ty).collect();
+    let variant_count = Literal::usize_unsuffixed(variants.len());
+
+    let mut fns = quote! {
+        pub const VARIANT_COUNT: usize = #variant_count;
+
+        pub fn variant_name(&self) -> &'static str {
+            match self {
+                #(#enum_name::#variants(..) => #variant_names,)*
+            }
+        }
+    };
+
+    for BitsetTrait {
+        path,
+        accessor,
+        docs,
+        into_tag,
+        ..
+    } in &types.traits
+    {
+        fns.extend(quote! {
+            #(#docs)*
+            pub fn #accessor(&self) -> ::core::option::Option<&dyn #path> {
+                match self {
+                    #(#enum_name::#variants(v) => (&&#wrap(v)).#into_tag().#into(v),)*
+                }
+            }
+        });
+
+        if let Some(ConcreteType { ident, ty }) = types.copy {
+            let copy_accessor = Ident::new(&format!("{}_copy", accessor), accessor.span());
+            fns.extend(quote! {
+                #(#docs)*
+                pub fn #copy_accessor(&self) -> ::core::option::Option<#ty> where #ty: ::core::marker::Copy {
+                    let #enum_name::#ident(v) = self;
+                    self.#accessor().map(|_| *v)
+                }
+            });
+        }
+    }
+
+    let allow = allow_non_camel_case([enum_name]);
+
+    quote! {
+        #allow
+        #(#attrs)*
+        pub enum #enum_name {
+            #(#variants(#tys),)*
+        }
+
+        #[allow(deprecated)]
+        impl #enum_name {
+            #fns
+        }
+
+        #(
+            impl ::core::convert::From<#tys> for #enum_name {
+                fn from(v: #tys) -> Self {
+                    #enum_name::#variants(v)
+                }
+            }
+        )*
+    }
+}
+
+/// Generates the expression used by the into macro in bitset mode, detecting each trait
+/// separately and building the struct from the selected tags.
+///
+/// # Arguments
+///
+/// * `ir` - The intermediate representation containing the struct name
+/// * `bitset` - The separately detected traits
+///
+/// # Returns
+///
+/// A TokenStream for the expression converting `$var` into the struct
+fn generate_bitset_into_expr(ir: &Ir, bitset: &Bitset) -> TokenStream {
+    let enum_name = ir.r#enum.name;
+    let wrap = ir.wrap_ident;
+    let into_tags = bitset.traits.iter().map(|t| &t.into_tag);
+
+    // The value is borrowed outside of the unsafe block, which only covers the constructor.
+    quote! {
+        match &$var {
+            value => unsafe { #enum_name::__new(value, #((&&#wrap(value)).#into_tags(),)*) },
+        }
+    }
+}
+
+/// Generates the generic struct used in auto mode for a single trait instead of the enum, along
+/// with its implementation.
+///
+/// The struct holds the value and, as a type parameter, the tag selected for its type, so the
+/// accessor is resolved when the struct is monomorphized, rather than by matching on a variant.
+/// Both tags implement a trait converting the value into the optional trait object, and the
+/// accessor keeps the name it has on the enum.
+///
+/// # Arguments
+///
+/// * `ir` - The intermediate representation containing the struct name
+/// * `monomorphic` - The single detected trait and the name of the conversion trait
+///
+/// # Returns
+///
+/// A TokenStream for the struct definition, its implementation and the conversion trait
+fn generate_monomorphic(ir: &Ir, monomorphic: &Monomorphic) -> TokenStream {
+    let enum_name = ir.r#enum.name;
+    let generic = &ir.value_generic;
+    let Monomorphic {
+        r#trait:
+            BitsetTrait {
+                path,
+                accessor,
+                docs,
+                tag,
+                none_tag,
+                ..
+            },
+        cast,
+    } = monomorphic;
+    let (generics, public) = public_type(ir);
+
+    let allow = allow_non_camel_case([enum_name]);
+
+    quote! {
+        #allow
+        pub struct #enum_name<'t, #generic, S> {
+            value: &'t #generic,
+            tag: ::core::marker::PhantomData<S>,
+        }
+
+        #[doc(hidden)]
+        pub trait #cast<#generic> {
+            const IMPLEMENTED: bool;
+
+            fn cast(value: &#generic) -> ::core::option::Option<&dyn #path>;
+        }
+
+        impl<#generic: #path> #cast<#generic> for #tag {
+            const IMPLEMENTED: bool = true;
+
+            fn cast(value: &#generic) -> ::core::option::Option<&dyn #path> {
+                ::core::option::Option::Some(value)
+            }
+        }
+
+        impl<#generic> #cast<#generic> for #none_tag {
+            const IMPLEMENTED: bool = false;
+
+            fn cast(_: &#generic) -> ::core::option::Option<&dyn #path> {
+                ::core::option::Option::None
+            }
+        }
+
+        #[allow(deprecated)]
+        impl #generics #public {
+            #[doc(hidden)]
+            pub fn __new(value: &'t #generic, _: S) -> Self {
+                Self {
+                    value,
+                    tag: ::core::marker::PhantomData,
+                }
+            }
+
+            pub fn traits_mask(&self) -> u32 {
+                S::IMPLEMENTED as u32
+            }
+
+            pub fn is_none(&self) -> bool {
+                !S::IMPLEMENTED
+            }
+
+            #(#docs)*
+            pub fn #accessor(&self) -> ::core::option::Option<&dyn #path> {
+                S::cast(self.value)
+            }
+        }
+    }
+}
+
+/// Generates the expression used by the into macro in monomorphic mode, building the struct from
+/// the value and the tag selected for its type.
+///
+/// # Arguments
+///
+/// * `ir` - The intermediate representation containing the struct name
+/// * `monomorphic` - The single detected trait
+///
+/// # Returns
+///
+/// A TokenStream for the expression converting `$var` into the struct
+fn generate_monomorphic_into_expr(ir: &Ir, monomorphic: &Monomorphic) -> TokenStream {
+    let enum_name = ir.r#enum.name;
+    let wrap = ir.wrap_ident;
+    let into_tag = &monomorphic.r#trait.into_tag;
+
+    quote! {
+        match &$var {
+            value => #enum_name::__new(value, (&&#wrap(value)).#into_tag()),
+        }
+    }
+}
+
+/// Generates a helper macro that recovers a concrete type from a `&dyn Any` and converts it into
+/// the enum.
+///
+/// Autoref specialization only works when the concrete type is known at the call site, so this
+/// can't be a generic function, instead the caller names the type to downcast to, e.g.
+/// `from_any_as_my_mux!(any, String)`.
+/// If any trait is mutable, the macro expects a `&mut dyn Any`.
+///
+/// # Arguments
+///
+/// * `ir` - The intermediate representation containing the macro identifiers
+///
+/// # Returns
+///
+/// A TokenStream for the `from_any_as` macro definition
+fn generate_from_any_as(ir: &Ir) -> TokenStream {
+    let inner_into = &ir.inner_into;
+    let MacroIdents {
+        inner: inner_from_any_as,
+        public: from_any_as,
+    } = &ir.from_any_as;
+    let downcast = if ir.borrow_mut {
+        quote!(downcast_mut)
+    } else {
+        quote!(downcast_ref)
+    };
+
+    generate_macro(
+        ir,
+        quote! {},
+        inner_from_any_as,
+        from_any_as,
+        quote! {
+            ($any:expr, $ty:ty) => {
+                match ($any).#downcast::<$ty>() {
+                    ::core::option::Option::Some(v) => ::core::option::Option::Some(#inner_into!((*v))),
+                    ::core::option::Option::None => ::core::option::Option::None,
+                }
+            }
+        },
+    )
+}
+
+/// Generates a helper macro converting every element of an array into the enum, e.g.
+/// `dispatch_array_my_mux!(array)`, producing an array of the same length without allocating.
+///
+/// The elements are borrowed from the array, which must be a place (e.g. a variable) outliving the
+/// result. All elements have the array's element type, so the variant is selected once for that
+/// type, inside the closure mapping each element.
+/// If any trait is mutable, the array is borrowed mutably.
+///
+/// # Arguments
+///
+/// * `ir` - The intermediate representation containing the macro identifiers
+///
+/// # Returns
+///
+/// A TokenStream for the `dispatch_array` macro definition, or an empty TokenStream if
+/// `dispatch_array` isn't enabled
+fn generate_dispatch_array(ir: &Ir) -> TokenStream {
+    let Some(MacroIdents {
+        inner: inner_dispatch_array,
+        public: dispatch_array,
+    }) = &ir.dispatch_array
+    else {
+        return TokenStream::new();
+    };
+    let inner_into = &ir.inner_into;
+    let each = if ir.borrow_mut {
+        quote!(each_mut)
+    } else {
+        quote!(each_ref)
+    };
+
+    generate_macro(
+        ir,
+        quote! {},
+        inner_dispatch_array,
+        dispatch_array,
+        quote! {
+            ($array:tt) => {
+                ($array).#each().map(|v| #inner_into!((*v)))
+            }
+        },
+    )
+}
+
+/// Generates a helper macro checking whether two values are converted into the same variant, e.g.
+/// `dispatch_eq_my_mux!(a, b)`, by comparing their discriminants. The values may be of different
+/// types, so the variant is selected for each of them where the macro is invoked.
+/// In bitset and monomorphic modes there are no variants, and the trait masks are compared instead.
+///
+/// # Arguments
+///
+/// * `ir` - The intermediate representation containing the macro identifiers
+///
+/// # Returns
+///
+/// A TokenStream for the `dispatch_eq` macro definition, or an empty TokenStream if `dispatch_eq`
+/// isn't enabled
+fn generate_dispatch_eq(ir: &Ir) -> TokenStream {
+    let Some(MacroIdents { inner, public }) = &ir.dispatch_eq else {
+        return TokenStream::new();
+    };
+    let inner_into = &ir.inner_into;
+    let key = if ir.bitset.is_some() || ir.monomorphic.is_some() {
+        quote!(traits_mask)
+    } else {
+        quote!(discriminant)
+    };
+
+    // Each value is converted in turn, so the same value may be passed twice even when borrowed
+    // mutably.
+    generate_macro(
+        ir,
+        quote! {},
+        inner,
+        public,
+        quote! {
+            ($a:tt, $b:tt) => {{
+                let a = #inner_into!($a).#key();
+                a == #inner_into!($b).#key()
+            }}
+        },
+    )
+}
+
+/// Generates a helper macro converting every element of a tuple expression into the enum, e.g.
+/// `dispatch_tuple_my_mux!((a, b))`, producing a tuple of the same arity.
+///
+/// The elements may be of different types, so the variant is selected for each of them, like the
+/// into macro does for a single value. There's an arm for every arity from 2 to 8, matching the
+/// elements of a parenthesized tuple, which are borrowed like the value passed to the into macro.
+///
+/// # Arguments
+///
+/// * `ir` - The intermediate representation containing the macro identifiers
+///
+/// # Returns
+///
+/// A TokenStream for the `dispatch_tuple` macro definition, or an empty TokenStream if
+/// `dispatch_tuple` isn't enabled
+fn generate_dispatch_tuple(ir: &Ir) -> TokenStream {
+    let Some(MacroIdents { inner, public }) = &ir.dispatch_tuple else {
+        return TokenStream::new();
+    };
+    let inner_into = &ir.inner_into;
+
+    let arms = (2..=8).map(|arity| {
+        let elements: Vec<_> = (0..arity)
+            .map(|i| Ident::new(&format!("e{}", i), Span::call_site()))
+            .collect();
+
+        quote! {
+            ((#($#elements:tt),* $(,)?)) => {
+                (#(#inner_into!($#elements),)*)
+            };
+        }
+    });
+
+    generate_macro(ir, quote! {}, inner, public, quote! {#(#arms)*})
+}
+
+/// Generates helper macros converting the value inside an `Option` or a `Result` into the enum,
+/// e.g. `into_my_mux_opt!(&option)` and `into_my_mux_result!(&result)`, borrowing the value from
+/// the referenced wrapper.
+/// `None` is kept as is, and so is the error of a `Result`, which is borrowed along.
+/// If any trait is mutable, the wrapper must be borrowed mutably.
+///
+/// # Arguments
+///
+/// * `ir` - The intermediate representation containing the macro identifiers
+///
+/// # Returns
+///
+/// A TokenStream for the `into_opt` and `into_result` macro definitions, or an empty TokenStream if
+/// `into_opt_result` isn't enabled
+fn generate_into_opt_result(ir: &Ir) -> TokenStream {
+    let inner_into = &ir.inner_into;
+    let as_ref = if ir.borrow_mut {
+        quote!(as_mut)
+    } else {
+        quote!(as_ref)
+    };
+
+    let mut result = TokenStream::new();
+    for (idents, wrapper) in [
+        (&ir.into_opt, quote!(::core::option::Option)),
+        (&ir.into_result, quote!(::core::result::Result)),
+    ] {
+        let Some(MacroIdents { inner, public }) = idents else {
+            continue;
+        };
+        result.extend(generate_macro(
+            ir,
+            quote! {},
+            inner,
+            public,
+            quote! {
+                ($value:expr) => {
+                    #wrapper::#as_ref($value).map(|v| #inner_into!((*v)))
+                }
+            },
+        ));
+    }
+
+    result
+}
+
+/// Generates the collection type, if `collection` is enabled, e.g. `MyMuxVec<'t>` wrapping a
+/// `Vec<MyMux<'t>>`, and a helper macro converting values into the enum and pushing them into it,
+/// e.g. `push_my_mux!(collection, a, b)`.
+///
+/// # Arguments
+///
+/// * `ir` - The intermediate representation containing the collection identifiers
+///
+/// # Returns
+///
+/// A TokenStream for the collection type, its implementations and the push macro, or an empty
+/// TokenStream if the option is disabled
+fn generate_collection(ir: &Ir) -> TokenStream {
+    let Some(Collection {
+        name,
+        push: MacroIdents {
+            inner: inner_push,
+            public: push,
+        },
+    }) = &ir.collection
+    else {
+        return TokenStream::new();
+    };
+    let enum_name = public_name(ir);
+    let inner_into = &ir.inner_into;
+
+    // The collection is only used as the receiver of `push`, so it can be given as a place or a
+    // mutable reference.
+    let push_macro = generate_macro(
+        ir,
+        quote! {},
+        inner_push,
+        push,
+        quote! {
+            ($collection:expr, $($var:tt),+ $(,)?) => {
+                {
+                    $(($collection).push(#inner_into!($var));)+
+                }
+            }
+        },
+    );
+
+    quote! {
+        #[derive(Default)]
+        pub struct #name<'t>(pub ::std::vec::Vec<#enum_name<'t>>);
+
+        impl<'t> #name<'t> {
+            pub fn new() -> Self {
+                Self(::std::vec::Vec::new())
+            }
+        }
+
+        impl<'t> ::core::ops::Deref for #name<'t> {
+            type Target = ::std::vec::Vec<#enum_name<'t>>;
+
+            fn deref(&self) -> &Self::Target {
+                &self.0
+            }
+        }
+
+        impl<'t> ::core::ops::DerefMut for #name<'t> {
+            fn deref_mut(&mut self) -> &mut Self::Target {
+                &mut self.0
+            }
+        }
+
+        impl<'t> ::core::iter::IntoIterator for #name<'t> {
+            type Item = #enum_name<'t>;
+            type IntoIter = ::std::vec::IntoIter<#enum_name<'t>>;
+
+            fn into_iter(self) -> Self::IntoIter {
+                self.0.into_iter()
+            }
+        }
+
+        impl<'a, 't> ::core::iter::IntoIterator for &'a #name<'t> {
+            type Item = &'a #enum_name<'t>;
+            type IntoIter = ::core::slice::Iter<'a, #enum_name<'t>>;
+
+            fn into_iter(self) -> Self::IntoIter {
+                self.0.iter()
+            }
+        }
+
+        #push_macro
+    }
+}
+
+/// Generates the struct counting the recorded values per variant, if `counter` is enabled, e.g.
+/// `MyMuxCounter` with a slot per discriminant, to collect dispatch statistics.
+///
+/// # Arguments
+///
+/// * `ir` - The intermediate representation containing the enum variants and the counter name
+///
+/// # Returns
+///
+/// A TokenStream for the counter struct and its implementations, or an empty TokenStream if the
+/// option is disabled
+fn generate_counter(ir: &Ir) -> TokenStream {
+    let Some(name) = &ir.counter else {
+        return TokenStream::new();
+    };
+    let enum_name = public_name(ir);
+    let variant_count = Literal::usize_unsuffixed(ir.r#enum.variants.len());
+    let variant_names = ir.r#enum.variants.iter().map(|v| v.ident.to_string());
+
+    // Arrays only implement `Default` up to 32 elements, so it's implemented by hand.
+    quote! {
+        #[derive(Clone, Debug)]
+        pub struct #name {
+            pub counts: [usize; #variant_count],
+        }
+
+        impl #name {
+            pub fn new() -> Self {
+                Self {
+                    counts: [0; #variant_count],
+                }
+            }
+
+            pub fn record(&mut self, value: &#enum_name<'_>) {
+                self.counts[value.discriminant() as usize] += 1;
+            }
+
+            pub fn report(&self) -> ::std::vec::Vec<(&'static str, usize)> {
+                [#(#variant_names),*].into_iter().zip(self.counts).collect()
+            }
+        }
+
+        impl ::core::default::Default for #name {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+    }
+}
+
+/// Generates a helper macro checking whether a value of the enum is exactly the variant of a trait
+/// combination, e.g. `matches_variant_my_mux!(value, [Debug, Display])`, so the variant names don't
+/// have to be spelled out.
+///
+/// The traits may be listed in any order: every trait name is bound to a constant holding its bit,
+/// and the mask of the list is compared to the mask of the value's variant. A list naming an
+/// unknown trait, the same trait twice, or a combination without a variant fails to compile.
+///
+/// # Arguments
+///
+/// * `ir` - The intermediate representation containing the enum variants and the macro identifiers
+///
+/// # Returns
+///
+/// A TokenStream for the `matches_variant` macro definition, or an empty TokenStream in bitset mode
+fn generate_matches_variant(ir: &Ir) -> TokenStream {
+    let Some(MacroIdents { inner, public }) = &ir.matches_variant else {
+        return TokenStream::new();
+    };
+    let enum_name = ir.r#enum.name;
+    let message = format!(
+        "is not a trait combination of `{}`, expected the traits of a variant",
+        enum_name
+    );
+
+    let traits: Vec<_> = ir.r#enum.traits.iter().map(|t| t.ident).collect();
+    let bits = (0..traits.len()).map(|i| Literal::u32_unsuffixed(1 << i));
+    let (variants, masks): (Vec<_>, Vec<_>) = ir
+        .r#enum
+        .variants
+        .iter()
+        .map(|v| (v.ident, Literal::u32_unsuffixed(v.mask)))
+        .unzip();
+
+    // The constants are declared in the initializer of the mask, so they can't shadow the names
+    // used by the value expression.
+    generate_macro(
+        ir,
+        quote! {},
+        inner,
+        public,
+        quote! {
+            ($value:expr, [$($traits:ident),* $(,)?]) => {{
+                const __MASK: u32 = {
+                    #(
+                        #[allow(dead_code, non_upper_case_globals)]
+                        const #traits: u32 = #bits;
+                    )*
+                    let mask: u32 = 0 $(| $traits)*;
+                    let count: u32 = 0 $(+ { let _ = $traits; 1 })*;
+                    ::core::assert!(
+                        mask.count_ones() == count && ::core::matches!(mask, #(#masks)|*),
+                        ::core::concat!(
+                            "`[", ::core::stringify!($($traits),*), "]` ", #message
+                        )
+                    );
+                    mask
+                };
+                match $value {
+                    #(#enum_name::#variants { .. } => __MASK == #masks,)*
+                }
+            }};
+            ($value:expr, [$($traits:tt)*]) => {
+                ::core::compile_error!(::core::concat!(
+                    "`[", ::core::stringify!($($traits)*), "]` ", #message
+                ))
+            };
+        },
+    )
+}
+
+/// Generates a helper macro mapping a variant to the traits it implements, if `variant_macro` is
+/// enabled, the reverse of the `matches_variant` macro.
+///
+/// `my_mux_variant_traits!(MyMuxDebugDisplay)` expands to the paths of the traits of the variant,
+/// as listed and in the sorted order of the variant name, e.g. `Debug, Display`. The paths are used
+/// rather than the `as` aliases, so they can be used as bounds. A macro's expansion can't be passed
+/// to another macro, so `my_mux_variant_traits!(MyMuxDebugDisplay => callback)` expands to
+/// `callback! { Debug, Display }` instead, for further code generation, in expression or item
+/// position. Other idents fail to compile.
+///
+/// # Arguments
+///
+/// * `ir` - The intermediate representation containing the enum variants and the macro identifiers
+///
+/// # Returns
+///
+/// A TokenStream for the `variant_traits` macro definition, or an empty TokenStream if the option
+/// is disabled
+fn generate_variant_traits(ir: &Ir) -> TokenStream {
+    let Some(MacroIdents { inner, public }) = &ir.variant_traits else {
+        return TokenStream::new();
+    };
+    let message = format!("is not a variant of `{}`", ir.r#enum.name);
+
+    let arms = ir
+        .r#enum
+        .variants
+        .iter()
+        .map(|EnumVariant { ident, mask, .. }| {
+            let traits: Vec<_> = ir
+                .r#enum
+                .traits
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| mask & 1 << i != 0)
+                .map(|(_, t)| t.path)
+                .collect();
+            quote! {
+                (#ident) => {
+                    #(#traits),*
+                };
+                (#ident => $callback:ident) => {
+                    $callback! { #(#traits),* }
+                };
+            }
+        });
+
+    generate_macro(
+        ir,
+        quote! {},
+        inner,
+        public,
+        quote! {
+            #(#arms)*
+            ($variant:ident $($rest:tt)*) => {
+                ::core::compile_error!(::core::concat!(
+                    "`", ::core::stringify!($variant), "` ", #message
+                ))
+            };
+        },
+    )
+}
+
+/// Generates the type-erased reference type, if `unsafe_erased` is enabled, e.g. `MyMuxDynRef`.
+/// It mirrors the enum variants, holding the trait objects as raw pointers whose lifetime is
+/// erased, so it can be stored without borrowing the original value. The trait objects can be
+/// recovered through unsafe accessors, whose callers guarantee that the value is still alive.
+///
+/// # Arguments
+///
+/// * `ir` - The intermediate representation containing the enum and its accessors
+///
+/// # Returns
+///
+/// A TokenStream for the type definition, its accessors and the conversion from the enum, or an
+/// empty TokenStream if the option is disabled
+fn generate_erased(ir: &Ir) -> TokenStream {
+    let Some(name) = &ir.erased else {
+        return TokenStream::new();
+    };
+    let enum_name = ir.r#enum.name;
+    let derefs = derefs(ir.wrapper.is_some());
+
+    let mut fields = TokenStream::new();
+    let mut erase_arms = TokenStream::new();
+    let mut data_arms = TokenStream::new();
+
+    for EnumVariant {
+        ident, constraint, ..
+    } in &ir.r#enum.variants
+    {
+        let object = match constraint {
+            Constraint::None => {
+                fields.extend(quote! { #ident, });
+                erase_arms.extend(quote! { #enum_name::#ident { .. } => #name::#ident, });
+                data_arms.extend(quote! { #name::#ident => ::core::ptr::null(), });
+                continue;
+            }
+            Constraint::Path(path, bounds) => quote! { dyn #path #(+ #bounds)* },
+            Constraint::Ident(ident) => quote! { dyn #ident },
+        };
+
+        fields.extend(quote! { #ident(*const (#object + 'static)), });
+        // SAFETY: only the lifetime bound of the trait object is changed, not its layout.
+        erase_arms.extend(quote! {
+            #enum_name::#ident(v) => #name::#ident(unsafe {
+                ::core::mem::transmute::<*const (#object + 't), *const (#object + 'static)>(&#derefs v)
+            }),
+        });
+        data_arms.extend(quote! { #name::#ident(p) => p.cast::<()>(), });
+    }
+
+    let accessors = ir
+        .enum_impl
+        .functions
+        .iter()
+        .chain(&ir.enum_impl.common)
+        .map(
+            |Function {
+                 name: accessor,
+                 trait_name,
+                 result_path,
+                 result_bounds,
+                 matching_variants,
+                 ..
+             }| {
+                let object = object(result_path, result_bounds);
+                let safety = format!(
+                    " The value must still be alive, and not mutably borrowed, for `'a`. Returns \
+                 None if the value doesn't implement `{}`.",
+                    trait_name
+                );
+                let fallback = fallback_arm(ir, matching_variants, quote! {::core::option::Option::None});
+
+                quote! {
+                    /// # Safety
+                    ///
+                    #[doc = #safety]
+                    pub unsafe fn #accessor<'a>(self) -> ::core::option::Option<&'a #object> {
+                        match self {
+                            #(#name::#matching_variants (p) => ::core::option::Option::Some(unsafe { &*p }),)*
+                            #fallback
+                        }
+                    }
+                }
+            },
+        );
+
+    quote! {
+        #[derive(Clone, Copy)]
+        pub enum #name {
+            #fields
+        }
+
+        #[allow(deprecated)]
+        impl #name {
+            pub fn data(self) -> *const () {
+                match self {
+                    #data_arms
+                }
+            }
+
+            #(#accessors)*
+        }
+
+        impl<'t> #enum_name<'t> {
+            pub fn as_dyn_ref(&self) -> #name {
+                match self {
+                    #erase_arms
+                }
+            }
+        }
+    }
+}
+
+/// Generates the shared view enum, if `shared_view` is enabled, e.g. `SharedMyMux<'t>`, along with
+/// the `as_shared()` method of the enum converting into it.
+/// The view mirrors the enum variants, holding shared references to the same trait objects, so
+/// it's `Copy` and only has the accessors taking `&self`.
+/// `as_shared()` reborrows the mutable references of the enum as shared ones for the borrow of the
+/// enum: the enum can't be mutated while the view is alive, and is usable again once it's dropped.
+///
+/// # Arguments
+///
+/// * `ir` - The intermediate representation containing the enum and its accessors
+///
+/// # Returns
+///
+/// A TokenStream for the view definition, its accessors and the conversion from the enum, or an
+/// empty TokenStream if the option is disabled
+fn generate_shared(ir: &Ir) -> TokenStream {
+    let Some(name) = &ir.shared else {
+        return TokenStream::new();
+    };
+    let enum_name = ir.r#enum.name;
+    let derefs = derefs(ir.wrapper.is_some());
+
+    let mut fields = TokenStream::new();
+    let mut arms = TokenStream::new();
+
+    for EnumVariant {
+        ident, constraint, ..
+    } in &ir.r#enum.variants
+    {
+        let object = match constraint {
+            Constraint::None => {
+                fields.extend(quote! { #ident, });
+                arms.extend(quote! { #enum_name::#ident { .. } => #name::#ident, });
+                continue;
+            }
+            Constraint::Path(path, bounds) => object(path, bounds),
+            Constraint::Ident(ident) => quote! { dyn #ident },
+        };
+
+        fields.extend(quote! { #ident(&'t #object), });
+        arms.extend(quote! { #enum_name::#ident(v) => #name::#ident(&#derefs v), });
+    }
+
+    let fns = generate_accessors(ir, name, false, false);
+
+    quote! {
+        #[derive(Clone, Copy)]
+        pub enum #name<'t> {
+            #fields
+        }
+
+        #[allow(deprecated)]
+        impl<'t> #name<'t> {
+            #fns
+        }
+
+        impl<'t> #enum_name<'t> {
+            pub fn as_shared(&self) -> #name<'_> {
+                match self {
+                    #arms
+                }
+            }
+        }
+    }
+}
+
+/// Generates the helpers of the autoref specialization: the wrap struct, the tags, the selection
+/// steps and the match traits. They're generated next to the enum rather than in a module, so the
+/// listed trait paths resolve as they do at the invocation, even inside a function body. They're
+/// named after the enum, so they don't collide with the user's items, and hidden from the docs.
+///
+/// # Arguments
+///
+/// * `ir` - The intermediate representation containing the specializers
+///
+/// # Returns
+///
+/// A TokenStream for the helper definitions
+fn generate_helpers(ir: &Ir) -> TokenStream {
+    let wrap = generate_wrap(ir);
+
+    let specializers = match (&ir.types, &ir.bitset, &ir.monomorphic) {
+        (Some(Types { traits, .. }), _, _) | (None, Some(Bitset { traits, .. }), _) => {
+            generate_bitset_specializers(ir, traits)
+        }
+        (None, None, Some(Monomorphic { r#trait, .. })) => {
+            generate_bitset_specializers(ir, std::slice::from_ref(r#trait))
+        }
+        (None, None, None) => {
+            let mut specializers = generate_selection_steps(ir);
+            specializers.extend(generate_autoref_specializers(ir));
+            specializers
+        }
+    };
+
+    // The accessor trait is sealed by a supertrait only nameable next to the enum. The module
+    // names nothing of the invocation scope, so it can be declared in a function body.
+    let sealed = ir.access.as_ref().map(|Access { sealed, .. }| {
+        let (generics, public) = public_type(ir);
+        quote! {
+            mod #sealed {
+                pub trait Sealed {}
+            }
+            impl #generics #sealed::Sealed for #public {}
+        }
+    });
+
+    quote! {
+        #wrap
+        #specializers
+        #sealed
+    }
+}
+
+/// Generates the wrapper struct that holds a reference to the original value.
+/// The wrapper is necessary to support proper specialization for the original
+/// type, and not its reference.
+/// It's `repr(transparent)`, so it has the same layout as the reference.
+/// The value must be `Sized`, as only sized types can be coerced into trait objects, unsized values
+/// (e.g. `str`) are dispatched through their references instead.
+///
+/// # Arguments
+///
+/// * `ir` - The intermediate representation containing the wrap identifier
+///
+/// # Returns
+///
+/// A TokenStream for the wrapper struct definition
+fn generate_wrap(ir: &Ir) -> TokenStream {
+    let generic = &ir.value_generic;
+    let wrap = ir.wrap_ident;
+
+    quote! {
+        #[repr(transparent)]
+        #[doc(hidden)]
+        pub struct #wrap<'t, #generic>(pub &'t #generic);
+    }
+}
+
+/// Generates a test helper macro asserting which variant a value is converted into, e.g.
+/// `assert_dispatch_my_mux!(value => MyMuxDebug)`.
+/// The macro is only generated with the `test_helpers` option, and only exists under `cfg(test)`.
+///
+/// # Arguments
+///
+/// * `ir` - The intermediate representation containing the macro identifiers
+///
+/// # Returns
+///
+/// A TokenStream for the `assert_dispatch` macro definition, or an empty TokenStream if test
+/// helpers are disabled
+fn generate_assert_dispatch(ir: &Ir) -> TokenStream {
+    let Some(MacroIdents { inner, public }) = &ir.assert_dispatch else {
+        return TokenStream::new();
+    };
+    let inner_into = &ir.inner_into;
+
+    generate_macro(
+        ir,
+        quote! {#[cfg(test)]},
+        inner,
+        public,
+        quote! {
+            ($var:tt => $variant:ident) => {
+                ::core::assert_eq!(#inner_into!($var).variant_name(), ::core::stringify!($variant))
+            }
+        },
+    )
+}
+
+/// Generates the `#[cfg(test)]` module of the `self_test` option, checking the variant selection
+/// of the autoref specialization.
+///
+/// The listed traits can't be implemented by the macro, so the module instead generates a shadow
+/// enum of the same name, over marker traits without items aliased to the listed trait names,
+/// giving it the same variants and specialization order. A value type is then generated for every
+/// variant, implementing the marker traits of the variant's traits, and the test asserts that each
+/// is converted into the variant of the same name. In priority chain mode, the shadow enum lists
+/// the traits in the same order, and each value also implements the traits listed after the trait
+/// of its variant, which mustn't be selected instead.
+///
+/// # Arguments
+///
+/// * `ir` - The intermediate representation containing the variants and traits
+///
+/// # Returns
+///
+/// A TokenStream for the test module, or an empty TokenStream if `self_test` is disabled
+fn generate_self_test(ir: &Ir) -> TokenStream {
+    let Some(module) = &ir.self_test else {
+        return TokenStream::new();
+    };
+    let enum_name = public_name(ir);
+    let into = &ir.into;
+    let marker = |name: &Ident| Ident::new(&format!("{}Marker", name.unraw()), Span::call_site());
+
+    let mut traits = ir.trait_ref.traits.clone();
+    if ir.priority_chain {
+        traits.sort_by_key(|t| {
+            ir.r#enum
+                .variants
+                .iter()
+                .position(|v| v.traits.first() == Some(&t.ident))
+        });
+    }
+    let markers: Vec<_> = traits.iter().map(|t| marker(t.ident)).collect();
+    let entries = traits.iter().zip(&markers).map(|(t, marker)| {
+        let mutability = t.mutable.then(|| quote! {mut});
+        let name = t.ident;
+        quote! {#mutability #marker as #name}
+    });
+    let mode = ir.priority_chain.then(|| quote! {, mode = priority_chain});
+
+    // The shadow enum goes through the whole pipeline, with its macros scoped to the module.
+    let shadow = crate::parse::parse(quote! {
+        #[trait_mux(scoped_macros #mode)]
+        #enum_name { #(#entries),* }
+    });
+    let model = crate::analyze::analyze(&shadow);
+    let shadow = codegen(crate::lower::lower(&model));
+
+    let mutability = ir.borrow_mut.then(|| quote! {mut});
+    let mut values = TokenStream::new();
+    let mut assertions = TokenStream::new();
+
+    for (i, EnumVariant { ident, traits, .. }) in ir.r#enum.variants.iter().enumerate() {
+        let value = Ident::new(&format!("{}Value", ident.unraw()), Span::call_site());
+        let traits = if ir.priority_chain && !traits.is_empty() {
+            ir.r#enum.variants[i..]
+                .iter()
+                .flat_map(|v| v.traits.iter().map(|t| marker(t)))
+                .collect::<Vec<_>>()
+        } else {
+            traits.iter().map(|t| marker(t)).collect()
+        };
+        let variant_name = ident.to_string();
+
+        values.extend(quote! {
+            struct #value;
+            #(impl #traits for #value {})*
+        });
+
+        assertions.extend(quote! {
+            let #mutability value = #value;
+            ::core::assert_eq!(#into!(value).variant_name(), #variant_name);
+        });
+    }
+
+    // Unlike the enum itself, the shadow enum is linted by clippy for its prefixed variant names.
+    quote! {
+        #[cfg(test)]
+        #[allow(dead_code, clippy::enum_variant_names)]
+        mod #module {
+            #(pub trait #markers {})*
+
+            #shadow
+
+            #values
+
+            #[test]
+            fn variant_selection() {
+                #assertions
+            }
+        }
+    }
+}
+
+/// Creates a TokenStream referring to a trait aggregate, with the enum lifetime as its argument if
+/// the traits are bound by it.
+///
+/// # Arguments
+///
+/// * `ir` - The intermediate representation
+/// * `name` - The name of the trait aggregate
+///
+/// # Returns
+///
+/// A TokenStream containing the trait aggregate, e.g. `TypeDebugDisplay` or `TypeDebugNamed<'t>`
+fn aggregate(ir: &Ir, name: &Ident) -> TokenStream {
+    if ir.enum_lifetime {
+        quote! {#name<'t>}
+    } else {
+        quote! {#name}
+    }
+}
+
+/// Generates trait aggregates that combine multiple traits into a single trait.
+/// If the traits are bound by the enum lifetime, the trait aggregates declare it as well, e.g.
+/// `pub trait TypeDebugNamed<'t>: Debug + Named<'t> {}`.
+/// The auto-trait bounds of the traits follow them once each, e.g.
+/// `pub trait TypeDebugDisplay: Debug + Display + Send {}`.
+///
+/// # Arguments
+///
+/// * `ir` - The intermediate representation containing trait aggregates
+///
+/// # Returns
+///
+/// A TokenStream for all trait aggregate definitions and their implementations
+fn generate_trait_aggregates(ir: &Ir) -> TokenStream {
+    let generic = &ir.value_generic;
+    let mut trait_aggregates = TokenStream::new();
+
+    ir.trait_aggregates
+        .iter()
+        .map(|TraitAggregate { name, traits }| {
+            // The aggregate is bound by the auto-trait bounds of every trait, so the trait object
+            // of each trait can be taken with its own.
+            let mut bounds: Vec<_> = vec![];
+            for bound in traits.iter().flat_map(|t| t.bounds) {
+                let name = quote! {#bound}.to_string();
+                if !bounds.iter().any(|(other, _)| *other == name) {
+                    bounds.push((name, bound));
+                }
+            }
+            let traits: Vec<_> = traits
+                .iter()
+                .map(|t| t.path)
+                .chain(bounds.into_iter().map(|(_, bound)| bound))
+                .collect();
+            let aggregate = aggregate(ir, name);
+            let lifetime = ir.enum_lifetime.then(|| quote! {'t,});
+
+            trait_aggregates.extend(quote! {
+                pub trait #aggregate: #(#traits)+* {}
+                impl<#lifetime #generic: #(#traits)+*> #aggregate for #generic {}
+            });
+        })
+        .count();
+
+    trait_aggregates
+}
+
+/// Generates the enum definition based on the intermediate representation.
+/// With a `repr`, the enum gets the primitive representation, and a compile-time assertion that
+/// all the variants fit in it.
+///
+/// # Arguments
+///
+/// * `ir` - The intermediate representation containing the enum definition
+///
+/// # Returns
+///
+/// A TokenStream for the enum definition
+fn generate_enum(ir: &Ir) -> TokenStream {
+    let enum_name = ir.r#enum.name;
+    let attrs = ir.r#enum.attrs;
+
+    let mut enum_fields = TokenStream::new();
+
+    for EnumVariant {
+        ident,
+        constraint,
+        mutable,
+        ..
+    } in &ir.r#enum.variants
+    {
+        let reference = if *mutable {
+            quote! {&'t mut}
+        } else {
+            quote! {&'t}
+        };
+
+        let constraint = match constraint {
+            Constraint::None => quote! {},
+            Constraint::Path(path, bounds) => {
+                let object = object(path, bounds);
+                wrap_payload(ir, quote! {#reference #object})
+            }
+            Constraint::Ident(ident) => {
+                let aggregate = aggregate(ir, ident);
+                wrap_payload(ir, quote! {#reference dyn #aggregate})
+            }
+        };
+
+        enum_fields.extend(quote! {
+            #ident #constraint,
+        });
+    }
+
+    // In opaque mode the enum is private, only reachable through the wrapping struct.
+    let vis = ir.opaque.is_none().then(|| quote! {pub});
+    let allow = allow_non_camel_case(
+        std::iter::once(enum_name).chain(ir.r#enum.variants.iter().map(|v| v.ident)),
+    );
+
+    let Some(repr) = ir.r#enum.repr else {
+        return quote! {
+            #allow
+            #(#attrs)*
+            #vis enum #enum_name<'t> {
+                #enum_fields
+            }
+        };
+    };
+
+    // The analysis checks that the implicit discriminants, counting up from 0, fit in the type.
+    quote! {
+        #[repr(#repr)]
+        #allow
+        #(#attrs)*
+        #vis enum #enum_name<'t> {
+            #enum_fields
+        }
+    }
+}
+
+/// Finds the variant without traits, the one without a constraint, whatever its name.
+///
+/// # Arguments
+///
+/// * `ir` - The intermediate representation containing the enum definition
+///
+/// # Returns
+///
+/// The identifier of the variant without traits, if the enum has one
+fn none_variant<'t>(ir: &Ir<'t>) -> Option<&'t Ident> {
+    ir.r#enum
+        .variants
+        .iter()
+        .find(|v| matches!(v.constraint, Constraint::None))
+        .map(|v| v.ident)
+}
+
+/// Finds the type values are converted into: the struct wrapping the enum in opaque mode, or the
+/// enum itself otherwise.
+///
+/// # Arguments
+///
+/// * `ir` - The intermediate representation containing the enum definition
+///
+/// # Returns
+///
+/// The identifier of the public type
+fn public_name<'t>(ir: &Ir<'t>) -> &'t Ident {
+    ir.opaque.unwrap_or(ir.r#enum.name)
+}
+
+/// Returns the public type along with the generic parameters its implementations declare, e.g.
+/// `<'t>` and `Type<'t>`.
+/// The enum of types mode holds the values, so it has no lifetime, while the monomorphic struct is
+/// generic over the value and the tag selected for it.
+///
+/// # Arguments
+///
+/// * `ir` - The intermediate representation containing the enum definition
+///
+/// # Returns
+///
+/// The generic parameters of the implementations, and the public type using them
+fn public_type(ir: &Ir) -> (TokenStream, TokenStream) {
+    let public = public_name(ir);
+    let generic = &ir.value_generic;
+
+    match (&ir.types, &ir.monomorphic) {
+        (Some(_), _) => (quote! {}, quote! {#public}),
+        (None, Some(Monomorphic { cast, .. })) => (
+            quote! {<'t, #generic, S: #cast<#generic>>},
+            quote! {#public<'t, #generic, S>},
+        ),
+        (None, None) => (quote! {<'t>}, quote! {#public<'t>}),
+    }
+}
+
+/// Generates the implementation of the enum, including methods for accessing
+/// the enum variants and `variant_name`, returning the name of the current variant.
+/// Mutable traits additionally get a `try_as_*_mut` accessor taking `&mut self`.
+///
+/// # Arguments
+///
+/// * `ir` - The intermediate representation containing the enum implementation
+///
+/// # Returns
+///
+/// A TokenStream for the enum implementation
+fn generate_enum_impl(ir: &Ir) -> TokenStream {
+    let enum_name = ir.r#enum.name;
+    let mut fns = TokenStream::new();
+
+    if let Some(rows) = &ir.enum_impl.variants_table {
+        let rows = rows.iter().map(|VariantsTableRow { variant, traits }| {
+            let variant = variant.to_string();
+            quote! { (#variant, &[#(#traits),*]) }
+        });
+
+        fns.extend(quote! {
+            pub const VARIANTS_TABLE: &'static [(&'static str, &'static [&'static str])] = &[#(#rows),*];
+        });
+    }
+
+    fns.extend(generate_accessors(
+        ir,
+        enum_name,
+        ir.wrapper.is_some(),
+        true,
+    ));
+
+    // The trait names are sorted the same way for every enum, so the slices can be compared.
+    if let Some(compat) = ir.enum_impl.compat {
+        fns.extend(quote! {
+            pub fn same_capabilities(&self, other: &#compat) -> bool {
+                self.trait_names() == other.trait_names()
+            }
+        });
+    }
+
+    fns.extend(generate_lifetime_accessors(ir));
+    fns.extend(generate_named_accessors(ir));
+
+    let variant_names = ir.r#enum.variants.iter().map(|v| v.ident.to_string());
+    let trait_counts = ir
+        .r#enum
+        .variants
+        .iter()
+        .map(|v| Literal::usize_unsuffixed(v.traits.len()));
+    fns.extend(quote! {
+        pub fn for_each_variant<F: ::core::ops::FnMut(&'static str, usize)>(mut f: F) {
+            #(f(#variant_names, #trait_counts);)*
+        }
+    });
+
+    for Function {
+        name,
+        objects_name,
+        result_path,
+        result_bounds,
+        ..
+    } in ir.enum_impl.functions.iter().chain(&ir.enum_impl.common)
+    {
+        let object = object(result_path, result_bounds);
+        fns.extend(quote! {
+            pub fn #objects_name<'a, I>(iter: I) -> impl ::core::iter::Iterator<Item = &'a #object> + use<'a, 't, I>
+            where
+                I: ::core::iter::IntoIterator<Item = &'a Self>,
+                't: 'a,
+            {
+                iter.into_iter().filter_map(Self::#name)
+            }
+        });
+    }
+
+    // Collects the common trait objects of a slice of values, skipping the variant without traits.
+    if let Some(Function {
+        name, result_path, ..
+    }) = &ir.enum_impl.common
+    {
+        fns.extend(quote! {
+            pub fn as_common_vec<'a>(items: &'a [Self]) -> ::std::vec::Vec<&'a dyn #result_path>
+            where
+                't: 'a,
+            {
+                items.iter().filter_map(Self::#name).collect()
+            }
+        });
+    }
+
+    fns.extend(generate_debug_json(ir));
+
+    // The variant without traits holds no reference, so it can be created in const contexts.
+    if let Some(none) = none_variant(ir) {
+        fns.extend(quote! {
+            pub const fn none() -> Self {
+                #enum_name::#none
+            }
+        });
+    }
+
+    // The private enum of opaque mode only needs the methods forwarded by the wrapping struct.
+    if ir.opaque.is_some() {
+        return quote! {
+            #[allow(deprecated, dead_code)]
+            impl<'t> #enum_name<'t> {
+                #fns
+            }
+        };
+    }
+
+    let common_from = generate_common_from(ir);
+    let static_accessors = generate_static_accessors(ir);
+    let as_ref = generate_as_ref(ir);
+
+    // The accessors of `#[deprecated]` trait entries are called by other generated methods, only
+    // their callers should be warned.
+    quote! {
+        #[allow(deprecated)]
+        impl<'t> #enum_name<'t> {
+            #fns
+        }
+
+        #static_accessors
+        #as_ref
+        #common_from
+    }
+}
+
+/// Generates the `#[non_exhaustive]` struct of opaque mode, e.g. `pub struct Type<'t>(TypeInner<'t>)`,
+/// wrapping the private enum so its variants can't be named or matched on, and forwarding the
+/// accessors of the enum to it.
+///
+/// # Arguments
+///
+/// * `ir` - The intermediate representation containing the enum implementation
+/// * `name` - The name of the struct
+///
+/// # Returns
+///
+/// A TokenStream for the struct definition and its implementation
+fn generate_opaque(ir: &Ir, name: &Ident) -> TokenStream {
+    let enum_name = ir.r#enum.name;
+    let variant_count = Literal::usize_unsuffixed(ir.r#enum.variants.len());
+    let bits = &ir.enum_impl.bits;
+    let counts = ir.enum_impl.functions.iter().map(|f| &f.count_name);
+    let mut fns = TokenStream::new();
+
+    if none_variant(ir).is_some() {
+        fns.extend(quote! {
+            pub const fn none() -> Self {
+                Self(#enum_name::none())
+            }
+
+            pub fn is_none(&self) -> bool {
+                self.0.is_none()
+            }
+        });
+    }
+
+    for Function {
+        name,
+        panicking_name,
+        result_path,
+        result_bounds,
+        docs,
+        ..
+    } in ir.enum_impl.functions.iter().chain(&ir.enum_impl.common)
+    {
+        let object = object(result_path, result_bounds);
+        fns.extend(quote! {
+            #(#docs)*
+            pub fn #name(&self) -> ::core::option::Option<&#object> {
+                self.0.#name()
+            }
+
+            #(#docs)*
+            #[track_caller]
+            pub fn #panicking_name(&self) -> &#object {
+                self.0.#panicking_name()
+            }
+        });
+    }
+
+    if let Some(marker) = ir.enum_impl.marker {
+        fns.extend(quote! {
+            pub fn as_marker(&self) -> ::core::option::Option<&dyn #marker> {
+                self.0.as_marker()
+            }
+        });
+    }
+
+    for Function {
+        name,
+        panicking_name,
+        modify_name,
+        result_path,
+        result_bounds,
+        docs,
+        ..
+    } in &ir.enum_impl.mut_functions
+    {
+        let object = object(result_path, result_bounds);
+        fns.extend(quote! {
+            #(#docs)*
+            pub fn #name(&mut self) -> ::core::option::Option<&mut #object> {
+                self.0.#name()
+            }
+
+            #(#docs)*
+            #[track_caller]
+            pub fn #panicking_name(&mut self) -> &mut #object {
+                self.0.#panicking_name()
+            }
+
+            #(#docs)*
+            pub fn #modify_name<U>(&mut self, f: impl FnOnce(&mut #object) -> U) -> ::core::option::Option<U> {
+                self.0.#modify_name(f)
+            }
+        });
+    }
+
+    quote! {
+        #[non_exhaustive]
+        pub struct #name<'t>(#enum_name<'t>);
+
+        #[allow(deprecated)]
+        impl<'t> #name<'t> {
+            pub const VARIANT_COUNT: usize = #variant_count;
+
+            pub const DISCRIMINANT_MASKS: [u32; #variant_count] = #enum_name::DISCRIMINANT_MASKS;
+
+            pub fn variant_name(&self) -> &'static str {
+                self.0.variant_name()
+            }
+
+            pub fn trait_names(&self) -> &'static [&'static str] {
+                self.0.trait_names()
+            }
+
+            pub fn discriminant(&self) -> u16 {
+                self.0.discriminant()
+            }
+
+            pub fn traits_mask(&self) -> u32 {
+                self.0.traits_mask()
+            }
+
+            #(pub const #bits: u32 = #enum_name::#bits;)*
+
+            #(pub const #counts: usize = #enum_name::#counts;)*
+
+            pub fn traits_bitmask(&self) -> u32 {
+                self.0.traits_bitmask()
+            }
+
+            pub fn variant_name_of(discriminant: u16) -> ::core::option::Option<&'static str> {
+                #enum_name::variant_name_of(discriminant)
+            }
+
+            pub const fn variant_traits(discriminant: u16) -> &'static [&'static str] {
+                #enum_name::variant_traits(discriminant)
+            }
+
+            pub fn for_each_variant<F: ::core::ops::FnMut(&'static str, usize)>(f: F) {
+                #enum_name::for_each_variant(f)
+            }
+
+            #fns
+        }
+    }
+}
+
+/// Generates `as_primary()`, returning the trait object of the trait declared with the `primary`
+/// option, under a name independent of the trait's. It delegates to the trait's fallible accessor,
+/// which every mode generates, including the opaque struct.
+///
+/// # Arguments
+///
+/// * `ir` - The intermediate representation containing the primary trait
+///
+/// # Returns
+///
+/// A TokenStream for the `as_primary` function, or an empty TokenStream if no primary trait is
+/// declared
+fn generate_primary(ir: &Ir) -> TokenStream {
+    let Some(Primary {
+        accessor,
+        path,
+        bounds,
+    }) = &ir.primary
+    else {
+        return TokenStream::new();
+    };
+    let object = object(path, bounds);
+
+    let (generics, public) = public_type(ir);
+    let target = quote! {impl #generics #public};
+
+    // The accessor is deprecated along with a deprecated trait entry.
+    quote! {
+        #[allow(deprecated)]
+        #target {
+            pub fn as_primary(&self) -> ::core::option::Option<&#object> {
+                self.#accessor()
+            }
+        }
+    }
+}
+
+/// Generates the `Debug`, `Display` and `std::error::Error` implementations of the public type if
+/// `error_impl` is enabled, delegating to the value of the variants implementing the listed
+/// `std::error::Error` trait. The other variants are formatted as their variant name, and have no
+/// source.
+///
+/// # Arguments
+///
+/// * `ir` - The intermediate representation containing the accessor of the `Error` trait
+///
+/// # Returns
+///
+/// A TokenStream for the implementations, or an empty TokenStream if `error_impl` isn't enabled
+fn generate_error_impl(ir: &Ir) -> TokenStream {
+    let Some(accessor) = &ir.error_impl else {
+        return TokenStream::new();
+    };
+    let public = public_name(ir);
+
+    // `description()` is deprecated, but still forwarded for the callers relying on it.
+    quote! {
+        #[allow(deprecated)]
+        impl<'t> ::core::fmt::Debug for #public<'t> {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                match self.#accessor() {
+                    ::core::option::Option::Some(error) => ::core::fmt::Debug::fmt(error, f),
+                    ::core::option::Option::None => f.write_str(self.variant_name()),
+                }
+            }
+        }
+
+        #[allow(deprecated)]
+        impl<'t> ::core::fmt::Display for #public<'t> {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                match self.#accessor() {
+                    ::core::option::Option::Some(error) => ::core::fmt::Display::fmt(error, f),
+                    ::core::option::Option::None => f.write_str(self.variant_name()),
+                }
+            }
+        }
+
+        #[allow(deprecated)]
+        impl<'t> ::std::error::Error for #public<'t> {
+            fn source(&self) -> ::core::option::Option<&(dyn ::std::error::Error + 'static)> {
+                self.#accessor().and_then(|error| error.source())
+            }
+
+            fn description(&self) -> &str {
+                match self.#accessor() {
+                    ::core::option::Option::Some(error) => error.description(),
+                    ::core::option::Option::None => self.variant_name(),
+                }
+            }
+        }
+    }
+}
+
+/// Generates the `PartialEq`, `Eq` and `Hash` implementations of the public type if
+/// `eq_by_variant` is enabled, comparing and hashing the discriminants of the values, so two values
+/// are equal if they implement the same traits, whatever the values themselves.
+///
+/// # Arguments
+///
+/// * `ir` - The intermediate representation
+///
+/// # Returns
+///
+/// A TokenStream for the implementations, or an empty TokenStream if `eq_by_variant` isn't enabled
+fn generate_eq_by_variant(ir: &Ir) -> TokenStream {
+    if !ir.eq_by_variant {
+        return TokenStream::new();
+    }
+    let public = public_name(ir);
+
+    quote! {
+        impl<'t> ::core::cmp::PartialEq for #public<'t> {
+            fn eq(&self, other: &Self) -> bool {
+                self.discriminant() == other.discriminant()
+            }
+        }
+
+        impl<'t> ::core::cmp::Eq for #public<'t> {}
+
+        impl<'t> ::core::hash::Hash for #public<'t> {
+            fn hash<H: ::core::hash::Hasher>(&self, state: &mut H) {
+                ::core::hash::Hash::hash(&self.discriminant(), state);
+            }
+        }
+    }
+}
+
+/// Generates `traits()`, returning the names of all listed traits in the sorted trait order,
+/// regardless of the variant, unlike `trait_names()`.
+///
+/// # Arguments
+///
+/// * `ir` - The intermediate representation containing the trait names
+///
+/// # Returns
+///
+/// A TokenStream for the `traits` function
+fn generate_trait_list(ir: &Ir) -> TokenStream {
+    let names = &ir.trait_names;
+
+    let (generics, public) = public_type(ir);
+    let target = quote! {impl #generics #public};
+
+    quote! {
+        #target {
+            pub fn traits() -> &'static [&'static str] {
+                &[#(#names),*]
+            }
+        }
+    }
+}
+
+/// Generates `write_to`, writing the value into a `core::fmt::Write` without allocating, with the
+/// first listed formatting trait it implements, preferring `Display` over `Debug`. Nothing is
+/// written if the value implements neither.
+///
+/// # Arguments
+///
+/// * `ir` - The intermediate representation containing the formatting trait accessors
+///
+/// # Returns
+///
+/// A TokenStream for the `write_to` function, or an empty TokenStream if neither `Debug` nor
+/// `Display` is listed
+fn generate_write_to(ir: &Ir) -> TokenStream {
+    let Some(Formatting { debug, display }) = &ir.write_to else {
+        return TokenStream::new();
+    };
+
+    let (generics, public) = public_type(ir);
+    let target = quote! {impl #generics #public};
+
+    let display = display.iter().map(|display| {
+        quote! {
+            if let ::core::option::Option::Some(v) = self.#display() {
+                return ::core::fmt::Write::write_fmt(w, ::core::format_args!("{}", v));
+            }
+        }
+    });
+    let debug = debug.iter().map(|debug| {
+        quote! {
+            if let ::core::option::Option::Some(v) = self.#debug() {
+                return ::core::fmt::Write::write_fmt(w, ::core::format_args!("{:?}", v));
+            }
+        }
+    });
+
+    // The writer is called through the trait, in case `std::io::Write` is in scope as well.
+    // The accessors are deprecated along with a deprecated trait entry.
+    quote! {
+        #[allow(deprecated)]
+        #target {
+            pub fn write_to<W: ::core::fmt::Write + ?::core::marker::Sized>(&self, w: &mut W) -> ::core::fmt::Result {
+                #(#display)*
+                #(#debug)*
+                ::core::result::Result::Ok(())
+            }
+        }
+    }
+}
+
+/// Generates the accessor trait of the `access_trait` option, e.g. `MyMuxAccess`, mirroring the
+/// fallible accessors, and implements it for the enum by delegating to them. Generic code can then
+/// be bounded on the trait instead of naming the enum. The trait can't be implemented outside of
+/// the generated code, as its supertrait is defined in a private module.
+///
+/// # Arguments
+///
+/// * `ir` - The intermediate representation containing the accessor trait
+///
+/// # Returns
+///
+/// A TokenStream for the trait and its implementation, or an empty TokenStream if the option is
+/// disabled
+fn generate_access(ir: &Ir) -> TokenStream {
+    let Some(Access {
+        name,
+        sealed,
+        methods,
+    }) = &ir.access
+    else {
+        return TokenStream::new();
+    };
+    let (generics, public) = public_type(ir);
+    let target = quote! {impl #generics #name for #public};
+
+    let signatures: Vec<_> = methods
+        .iter()
+        .map(
+            |AccessMethod {
+                 name,
+                 path,
+                 bounds,
+                 mutable,
+                 ..
+             }| {
+                let object = object(path, bounds);
+                if *mutable {
+                    quote! {fn #name(&mut self) -> ::core::option::Option<&mut #object>}
+                } else {
+                    quote! {fn #name(&self) -> ::core::option::Option<&#object>}
+                }
+            },
+        )
+        .collect();
+    let docs = methods.iter().map(|m| m.docs);
+    let names = methods.iter().map(|m| &m.name);
+
+    // The inherent accessors take precedence over the trait's in `Self::` paths, and are
+    // deprecated along with a deprecated trait entry.
+    quote! {
+        pub trait #name: #sealed::Sealed {
+            #(
+                #(#docs)*
+                #signatures;
+            )*
+        }
+
+        #[allow(deprecated)]
+        #target {
+            #(
+                #signatures {
+                    Self::#names(self)
+                }
+            )*
+        }
+    }
+}
+
+/// Generates the accessors of the `with_accessors` option, e.g. `with_debug(|v| ...)`, passing the
+/// trait object to a closure and returning its result, or None if the trait isn't implemented.
+/// They delegate to the fallible accessors, which every mode generates.
+///
+/// # Arguments
+///
+/// * `ir` - The intermediate representation containing the accessors
+///
+/// # Returns
+///
+/// A TokenStream for the accessors, or an empty TokenStream if the option is disabled
+fn generate_with_accessors(ir: &Ir) -> TokenStream {
+    if ir.with_accessors.is_empty() {
+        return TokenStream::new();
+    }
+
+    let (generics, public) = public_type(ir);
+    let target = quote! {impl #generics #public};
+
+    let fns = ir.with_accessors.iter().map(
+        |WithAccessor {
+             name,
+             accessor,
+             path,
+             bounds,
+             docs,
+         }| {
+            let object = object(path, bounds);
+            quote! {
+                #(#docs)*
+                pub fn #name<R>(&self, f: impl ::core::ops::FnOnce(&#object) -> R) -> ::core::option::Option<R> {
+                    self.#accessor().map(f)
+                }
+            }
+        },
+    );
+
+    // The accessors are deprecated along with a deprecated trait entry.
+    quote! {
+        #[allow(deprecated)]
+        #target {
+            #(#fns)*
+        }
+    }
+}
+
+/// Generates the conversion of the enum into an optional trait object of the trait declared with
+/// the `common` option, e.g. `impl<'t> From<Type<'t>> for Option<&'t dyn CommonTrait>`.
+/// Wrapped payloads are dropped along with the enum, so the conversion is only generated without
+/// a wrapper.
+///
+/// # Arguments
+///
+/// * `ir` - The intermediate representation containing the enum definition
+///
+/// # Returns
+///
+/// A TokenStream for the `From` implementation, or an empty TokenStream if no common trait is
+/// declared
+fn generate_common_from(ir: &Ir) -> TokenStream {
+    let (Some(common), None) = (&ir.enum_impl.common, ir.wrapper) else {
+        return TokenStream::new();
+    };
+    let enum_name = ir.r#enum.name;
+    let common = common.result_path;
+
+    let arms = ir.r#enum.variants.iter().map(
+        |EnumVariant {
+             ident, constraint, ..
+         }| {
+            match constraint {
+                Constraint::None => quote! {#enum_name::#ident => ::core::option::Option::None,},
+                Constraint::Path(..) | Constraint::Ident(_) => {
+                    quote! {#enum_name::#ident(v) => ::core::option::Option::Some(&*v),}
+                }
+            }
+        },
+    );
+
+    quote! {
+        impl<'t> ::core::convert::From<#enum_name<'t>> for ::core::option::Option<&'t dyn #common> {
+            fn from(value: #enum_name<'t>) -> Self {
+                match value {
+                    #(#arms)*
+                }
+            }
+        }
+    }
+}
+
+/// Generates `AsRef<dyn Trait>` and `Borrow<dyn Trait>` implementations for the traits implemented
+/// by every variant, e.g. the universal trait, as their methods can't fail.
+///
+/// # Arguments
+///
+/// * `ir` - The intermediate representation containing the enum implementation
+///
+/// # Returns
+///
+/// A TokenStream for the implementations, empty if no trait is implemented by every variant
+fn generate_as_ref(ir: &Ir) -> TokenStream {
+    let enum_name = ir.r#enum.name;
+    let derefs = derefs(ir.wrapper.is_some());
+    let mut impls = TokenStream::new();
+
+    for Function {
+        result_path,
+        result_bounds,
+        matching_variants,
+        ..
+    } in ir.enum_impl.functions.iter().chain(&ir.enum_impl.common)
+    {
+        if fallback_arm(ir, matching_variants, quote! {}).is_some() {
+            continue;
+        }
+
+        impls.extend(quote! {
+            impl<'t> ::core::convert::AsRef<dyn #result_path #(+ #result_bounds)* + 't> for #enum_name<'t> {
+                fn as_ref(&self) -> &(dyn #result_path #(+ #result_bounds)* + 't) {
+                    match self {
+                        #(#enum_name::#matching_variants (v) => &#derefs v,)*
+                    }
+                }
+            }
+
+            impl<'t> ::core::borrow::Borrow<dyn #result_path #(+ #result_bounds)* + 't> for #enum_name<'t> {
+                fn borrow(&self) -> &(dyn #result_path #(+ #result_bounds)* + 't) {
+                    ::core::convert::AsRef::as_ref(self)
+                }
+            }
+        });
+    }
+
+    impls
+}
+
+/// Generates the `try_as_trait_name_for` accessors of the enum, if `lifetime_accessors` is enabled.
+/// Unlike the default accessors, whose trait objects are bound by the borrow of the enum, these keep
+/// the enum lifetime `'t` as the bound, so they can be stored alongside other `'t` bound objects.
+///
+/// # Arguments
+///
+/// * `ir` - The intermediate representation containing the enum implementation
+///
+/// # Returns
+///
+/// A TokenStream for the accessor methods, or an empty one if the option is disabled
+fn generate_lifetime_accessors(ir: &Ir) -> TokenStream {
+    if !ir.enum_impl.lifetime_accessors {
+        return TokenStream::new();
+    }
+
+    let enum_name = ir.r#enum.name;
+    let wrapped = ir.wrapper.is_some();
+    let mut fns = TokenStream::new();
+
+    for Function {
+        lifetime_name,
+        result_path,
+        result_bounds,
+        docs,
+        matching_variants,
+        ..
+    } in ir.enum_impl.functions.iter().chain(&ir.enum_impl.common)
+    {
+        let body = accessor_match(ir, enum_name, matching_variants, wrapped, false, |_, v| v);
+
+        fns.extend(quote! {
+            #(#docs)*
+            pub fn #lifetime_name<'a>(&'a self) -> ::core::option::Option<&'a (dyn #result_path #(+ #result_bounds)* + 't)> {
+                #body
+            }
+        });
+    }
+
+    for Function {
+        lifetime_name,
+        result_path,
+        result_bounds,
+        docs,
+        matching_variants,
+        ..
+    } in &ir.enum_impl.mut_functions
+    {
+        let body = accessor_match(ir, enum_name, matching_variants, wrapped, true, |_, v| v);
+
+        fns.extend(quote! {
+            #(#docs)*
+            pub fn #lifetime_name<'a>(&'a mut self) -> ::core::option::Option<&'a mut (dyn #result_path #(+ #result_bounds)* + 't)> {
+                #body
+            }
+        });
+    }
+
+    fns
+}
+
+/// Generates the `try_as_trait_name_named` accessors of the enum, if `named_accessors` is enabled.
+/// They return the name of the variant along with the trait object, out of a single match.
+///
+/// # Arguments
+///
+/// * `ir` - The intermediate representation containing the enum implementation
+///
+/// # Returns
+///
+/// A TokenStream for the accessor methods, or an empty one if the option is disabled
+fn generate_named_accessors(ir: &Ir) -> TokenStream {
+    if !ir.enum_impl.named_accessors {
+        return TokenStream::new();
+    }
+
+    let enum_name = ir.r#enum.name;
+    let wrapped = ir.wrapper.is_some();
+    let named = |variant: &Ident, v| {
+        let variant_name = variant.to_string();
+        quote! {(#variant_name, #v)}
+    };
+    let mut fns = TokenStream::new();
+
+    for Function {
+        named_name,
+        result_path,
+        result_bounds,
+        docs,
+        matching_variants,
+        ..
+    } in ir.enum_impl.functions.iter().chain(&ir.enum_impl.common)
+    {
+        let object = object(result_path, result_bounds);
+        let body = accessor_match(ir, enum_name, matching_variants, wrapped, false, named);
+
+        fns.extend(quote! {
+            #(#docs)*
+            pub fn #named_name(&self) -> ::core::option::Option<(&'static str, &#object)> {
+                #body
+            }
+        });
+    }
+
+    for Function {
+        named_name,
+        result_path,
+        result_bounds,
+        docs,
+        matching_variants,
+        ..
+    } in &ir.enum_impl.mut_functions
+    {
+        let object = object(result_path, result_bounds);
+        let body = accessor_match(ir, enum_name, matching_variants, wrapped, true, named);
+
+        fns.extend(quote! {
+            #(#docs)*
+            pub fn #named_name(&mut self) -> ::core::option::Option<(&'static str, &mut #object)> {
+                #body
+            }
+        });
+    }
+
+    fns
+}
+
+/// Generates the `try_as_trait_name_static` accessors of the enum, if `static` is enabled.
+/// They're only implemented for enums holding `'static` values, returning `'static` bound trait
+/// objects, as required by some APIs.
+///
+/// # Arguments
+///
+/// * `ir` - The intermediate representation containing the enum implementation
+///
+/// # Returns
+///
+/// A TokenStream for the `impl` block of the accessors, or an empty one if the option is disabled
+fn generate_static_accessors(ir: &Ir) -> TokenStream {
+    if !ir.enum_impl.static_accessors {
+        return TokenStream::new();
+    }
+
+    let enum_name = ir.r#enum.name;
+    let wrapped = ir.wrapper.is_some();
+    let mut fns = TokenStream::new();
+
+    for Function {
+        static_name,
+        result_path,
+        result_bounds,
+        docs,
+        matching_variants,
+        ..
+    } in ir.enum_impl.functions.iter().chain(&ir.enum_impl.common)
+    {
+        let body = accessor_match(ir, enum_name, matching_variants, wrapped, false, |_, v| v);
+
+        fns.extend(quote! {
+            #(#docs)*
+            pub fn #static_name(&self) -> ::core::option::Option<&(dyn #result_path #(+ #result_bounds)* + 'static)> {
+                #body
+            }
+        });
+    }
+
+    for Function {
+        static_name,
+        result_path,
+        result_bounds,
+        docs,
+        matching_variants,
+        ..
+    } in &ir.enum_impl.mut_functions
+    {
+        let body = accessor_match(ir, enum_name, matching_variants, wrapped, true, |_, v| v);
+
+        fns.extend(quote! {
+            #(#docs)*
+            pub fn #static_name(&mut self) -> ::core::option::Option<&mut (dyn #result_path #(+ #result_bounds)* + 'static)> {
+                #body
+            }
+        });
+    }
+
+    quote! {
+        #[allow(deprecated)]
+        impl #enum_name<'static> {
+            #fns
+        }
+    }
+}
+
+/// Generates the `to_debug_json` method of the enum, if `debug_json` is enabled.
+/// The value is rendered with the first listed formatting trait it implements, preferring `Debug`
+/// over `Display`, and is omitted if it implements neither.
+///
+/// # Arguments
+///
+/// * `ir` - The intermediate representation containing the formatting trait accessors
+///
+/// # Returns
+///
+/// A TokenStream for the method, or an empty one if the option is disabled
+fn generate_debug_json(ir: &Ir) -> TokenStream {
+    let Some(Formatting { debug, display }) = &ir.enum_impl.debug_json else {
+        return TokenStream::new();
+    };
+
+    let debug = debug.iter().map(|debug| {
+        quote! {
+            if let ::core::option::Option::Some(v) = self.#debug() {
+                push_string(&mut json, &::std::format!("{:?}", v));
+            } else
+        }
+    });
+    let display = display.iter().map(|display| {
+        quote! {
+            if let ::core::option::Option::Some(v) = self.#display() {
+                push_string(&mut json, &::std::string::ToString::to_string(v));
+            } else
+        }
+    });
+
+    quote! {
+        pub fn to_debug_json(&self) -> ::std::string::String {
+            fn push_string(json: &mut ::std::string::String, s: &str) {
+                json.push('"');
+                for c in s.chars() {
+                    match c {
+                        '"' => json.push_str("\\\""),
+                        '\\' => json.push_str("\\\\"),
+                        c if (c as u32) < 0x20 => json.push_str(&::std::format!("\\u{:04x}", c as u32)),
+                        c => json.push(c),
+                    }
+                }
+                json.push('"');
+            }
+
+            let mut json = ::std::string::String::from("{\"variant\":");
+            push_string(&mut json, self.variant_name());
+            let value_start = json.len();
+            json.push_str(",\"value\":");
+            #(#debug)* #(#display)* {
+                json.truncate(value_start);
+            }
+            json.push('}');
+            json
+        }
+    }
+}
+
+/// Generates the accessor methods shared by the enum, the owned enum and the shared view, as they
+/// have the same variants, holding either references or boxes.
+///
+/// # Arguments
+///
+/// * `ir` - The intermediate representation containing the enum implementation
+/// * `enum_name` - The name of the enum the accessors are generated for
+/// * `wrapped` - Whether the payloads are stored in the wrapper type, needing an extra deref
+/// * `mutable` - Whether the payloads of the mutable traits can be borrowed mutably, generating
+///   their `try_as_*_mut` accessors
+///
+/// # Returns
+///
+/// A TokenStream for the accessor methods
+fn generate_accessors(ir: &Ir, enum_name: &Ident, wrapped: bool, mutable: bool) -> TokenStream {
+    let mut fns = TokenStream::new();
+    let mut_functions: &[Function] = if mutable {
+        &ir.enum_impl.mut_functions
+    } else {
+        &[]
+    };
+    let derefs = derefs(wrapped);
+
+    let variants: Vec<_> = ir.r#enum.variants.iter().map(|v| v.ident).collect();
+    let variant_names = ir.r#enum.variants.iter().map(|v| v.ident.to_string());
+    let discriminants: Vec<_> = (0..ir.r#enum.variants.len())
+        .map(|d| Literal::u16_unsuffixed(d as u16))
+        .collect();
+    let variant_names: Vec<_> = variant_names.collect();
+    let variant_count = Literal::usize_unsuffixed(ir.r#enum.variants.len());
+    let masks = ir
+        .r#enum
+        .variants
+        .iter()
+        .map(|v| Literal::u32_unsuffixed(v.mask));
+    let trait_names: Vec<_> = ir
+        .r#enum
+        .variants
+        .iter()
+        .map(|v| {
+            let names = v.traits.iter().map(|t| t.unraw().to_string());
+            quote! { &[#(#names),*] }
+        })
+        .collect();
+    // The bits follow the sorted trait order, as in the masks.
+    let bits = &ir.enum_impl.bits;
+    let bit_indices = (0..bits.len()).map(Literal::usize_unsuffixed);
+    fns.extend(quote! {
+        pub const VARIANT_COUNT: usize = #variant_count;
+
+        pub fn variant_name(&self) -> &'static str {
+            match self {
+                #(#enum_name::#variants { .. } => #variant_names,)*
+            }
+        }
+
+        pub fn trait_names(&self) -> &'static [&'static str] {
+            match self {
+                #(#enum_name::#variants { .. } => #trait_names,)*
+            }
+        }
+
+        pub fn discriminant(&self) -> u16 {
+            match self {
+                #(#enum_name::#variants { .. } => #discriminants,)*
+            }
+        }
+
+        pub const DISCRIMINANT_MASKS: [u32; #variant_count] = [#(#masks),*];
+
+        pub fn traits_mask(&self) -> u32 {
+            Self::DISCRIMINANT_MASKS[self.discriminant() as usize]
+        }
+
+        #(pub const #bits: u32 = 1 << #bit_indices;)*
+
+        pub fn traits_bitmask(&self) -> u32 {
+            self.traits_mask()
+        }
+
+        pub fn variant_name_of(discriminant: u16) -> ::core::option::Option<&'static str> {
+            match discriminant {
+                #(#discriminants => ::core::option::Option::Some(#variant_names),)*
+                _ => ::core::option::Option::None,
+            }
+        }
+
+        pub const fn variant_traits(discriminant: u16) -> &'static [&'static str] {
+            match discriminant {
+                #(#discriminants => #trait_names,)*
+                _ => &[],
+            }
+        }
+    });
+
+    if let Some(none) = none_variant(ir) {
+        fns.extend(quote! {
+            pub fn is_none(&self) -> bool {
+                ::core::matches!(self, #enum_name::#none { .. })
+            }
+        });
+    }
+
+    for Function {
+        count_name,
+        matching_variants,
+        ..
+    } in &ir.enum_impl.functions
+    {
+        let count = Literal::usize_unsuffixed(matching_variants.len());
+        fns.extend(quote! {
+            pub const #count_name: usize = #count;
+        });
+    }
+
+    for Function {
+        name,
+        panicking_name,
+        trait_name,
+        result_path,
+        result_bounds,
+        docs,
+        matching_variants,
+        ..
+    } in ir.enum_impl.functions.iter().chain(&ir.enum_impl.common)
+    {
+        let object = object(result_path, result_bounds);
+        let message = panic_message(trait_name);
+        let body = accessor_match(ir, enum_name, matching_variants, wrapped, false, |_, v| v);
+
+        fns.extend(quote! {
+            #(#docs)*
+            pub fn #name(&self) -> ::core::option::Option<&#object> {
+                #body
+            }
+
+            #(#docs)*
+            #[track_caller]
+            pub fn #panicking_name(&self) -> &#object {
+                let variant = self.variant_name();
+                match self.#name() {
+                    ::core::option::Option::Some(v) => v,
+                    ::core::option::Option::None => ::core::panic!(#message, variant),
+                }
+            }
+        });
+    }
+
+    // Every listed trait extends the marker, so the trait object of any variant with traits is
+    // upcast to it.
+    if let Some(marker) = ir.enum_impl.marker {
+        let matching_variants: Vec<_> = ir
+            .r#enum
+            .variants
+            .iter()
+            .filter(|v| !matches!(v.constraint, Constraint::None))
+            .map(|v| v.ident)
+            .collect();
+        let body = accessor_match(ir, enum_name, &matching_variants, wrapped, false, |_, v| v);
+
+        fns.extend(quote! {
+            pub fn as_marker(&self) -> ::core::option::Option<&dyn #marker> {
+                #body
+            }
+        });
+    }
+
+    if ir.enum_impl.unchecked_accessors {
+        for function in ir.enum_impl.functions.iter().chain(&ir.enum_impl.common) {
+            fns.extend(generate_unchecked_accessor(
+                ir, function, enum_name, &derefs, false,
+            ));
+        }
+    }
+
+    for Function {
+        name,
+        panicking_name,
+        modify_name,
+        trait_name,
+        result_path,
+        result_bounds,
+        docs,
+        matching_variants,
+        ..
+    } in mut_functions
+    {
+        let object = object(result_path, result_bounds);
+        let message = panic_message(trait_name);
+        let body = accessor_match(ir, enum_name, matching_variants, wrapped, true, |_, v| v);
+
+        fns.extend(quote! {
+            #(#docs)*
+            pub fn #name(&mut self) -> ::core::option::Option<&mut #object> {
+                #body
+            }
+
+            #(#docs)*
+            #[track_caller]
+            pub fn #panicking_name(&mut self) -> &mut #object {
+                let variant = self.variant_name();
+                match self.#name() {
+                    ::core::option::Option::Some(v) => v,
+                    ::core::option::Option::None => ::core::panic!(#message, variant),
+                }
+            }
+
+            #(#docs)*
+            pub fn #modify_name<U>(&mut self, f: impl FnOnce(&mut #object) -> U) -> ::core::option::Option<U> {
+                self.#name().map(f)
+            }
+        });
+    }
+
+    if ir.enum_impl.unchecked_accessors {
+        for function in mut_functions {
+            fns.extend(generate_unchecked_accessor(
+                ir, function, enum_name, &derefs, true,
+            ));
+        }
+    }
+
+    fns
+}
+
+/// Generates an unsafe accessor skipping the check that the variant implements the trait, e.g.
+/// `as_trait_name_unchecked`.
+/// Debug builds still check the variant, panicking like the panicking accessor on misuse.
+///
+/// # Arguments
+///
+/// * `ir` - The intermediate representation containing the enum variants
+/// * `function` - The function to generate the unchecked variant of
+/// * `enum_name` - The name of the enum the accessor is generated for
+/// * `derefs` - The derefs from the matched payload to the trait object
+/// * `mutable` - Whether the accessor returns a mutable reference
+///
+/// # Returns
+///
+/// A TokenStream for the unchecked accessor
+fn generate_unchecked_accessor(
+    ir: &Ir,
+    function: &Function,
+    enum_name: &Ident,
+    derefs: &TokenStream,
+    mutable: bool,
+) -> TokenStream {
+    let Function {
+        name,
+        unchecked_name,
+        trait_name,
+        result_path,
+        result_bounds,
+        docs,
+        matching_variants,
+        ..
+    } = function;
+    let object = object(result_path, result_bounds);
+    let message = panic_message(trait_name);
+    let safety = format!(
+        " The variant must implement `{}`, e.g. as checked with `{}()`.",
+        trait_name, name
+    );
+    let (receiver, borrow) = if mutable {
+        (quote! {&mut self}, quote! {&mut})
+    } else {
+        (quote! {&self}, quote! {&})
+    };
+    let fallback = fallback_arm(
+        ir,
+        matching_variants,
+        quote! {
+            {
+                ::core::debug_assert!(false, #message, self.variant_name());
+                // SAFETY: the caller guarantees that the variant implements the trait.
+                unsafe { ::core::hint::unreachable_unchecked() }
+            }
+        },
+    );
+
+    quote! {
+        #(#docs)*
+        ///
+        /// # Safety
+        ///
+        #[doc = #safety]
+        #[track_caller]
+        pub unsafe fn #unchecked_name(#receiver) -> #borrow #object {
+            match self {
+                #(#enum_name::#matching_variants (v) => #borrow #derefs v,)*
+                #fallback
+            }
+        }
+    }
+}
+
+/// Creates the fallback arm of a match over the variants implementing a trait, e.g. `_ => None,`.
+/// No arm is needed when the variants cover the whole enum, e.g. for the universal trait, whose
+/// accessors then have no dead arm.
+///
+/// # Arguments
+///
+/// * `ir` - The intermediate representation containing the enum variants
+/// * `matching_variants` - The variants matched by the preceding arms
+/// * `body` - The body of the fallback arm
+///
+/// # Returns
+///
+/// The fallback arm, or None if the matching variants cover every variant
+fn fallback_arm(ir: &Ir, matching_variants: &[&Ident], body: TokenStream) -> Option<TokenStream> {
+    (matching_variants.len() < ir.r#enum.variants.len()).then(|| quote! { _ => #body, })
+}
+
+/// Creates the derefs from the payload matched out of a borrowed enum to its trait object.
+///
+/// # Arguments
+///
+/// * `wrapped` - Whether the payloads are stored in the wrapper type, needing an extra deref
+///
+/// # Returns
+///
+/// A TokenStream containing either `***` or `**`
+fn derefs(wrapped: bool) -> TokenStream {
+    if wrapped {
+        quote! {***}
+    } else {
+        quote! {**}
+    }
+}
+
+/// Creates the body of an accessor, matching the variants implementing a trait into an optional
+/// value holding their trait object, e.g. `match self { Type::Debug(v) => Some(&**v), _ => None }`.
+///
+/// # Arguments
+///
+/// * `ir` - The intermediate representation containing the enum variants
+/// * `enum_name` - The name of the enum the accessor is generated for
+/// * `matching_variants` - The variants implementing the trait
+/// * `wrapped` - Whether the payloads are stored in the wrapper type, needing an extra deref
+/// * `mutable` - Whether the trait object is borrowed mutably
+/// * `value` - Creates the value returned for a variant out of its name and its borrowed trait
+///   object
+///
+/// # Returns
+///
+/// A TokenStream for the match expression
+fn accessor_match(
+    ir: &Ir,
+    enum_name: &Ident,
+    matching_variants: &[&Ident],
+    wrapped: bool,
+    mutable: bool,
+    value: impl Fn(&Ident, TokenStream) -> TokenStream,
+) -> TokenStream {
+    let borrow = borrow(mutable);
+    let derefs = derefs(wrapped);
+    let values = matching_variants
+        .iter()
+        .map(|variant| value(variant, quote! {#borrow #derefs v}));
+    let fallback = fallback_arm(ir, matching_variants, quote! {::core::option::Option::None});
+
+    quote! {
+        match self {
+            #(#enum_name::#matching_variants (v) => ::core::option::Option::Some(#values),)*
+            #fallback
+        }
+    }
+}
+
+/// Creates the panic message of a panicking accessor, formatted with the variant name.
+///
+/// # Arguments
+///
+/// * `trait_name` - The name of the trait the accessor gives access to
+///
+/// # Returns
+///
+/// The format string, e.g. ``"`{}` does not implement `Debug`"``
+fn panic_message(trait_name: &str) -> String {
+    format!("`{{}}` does not implement `{}`", trait_name)
+}
+
+/// Creates a TokenStream constructing the wrapper type from the value passed to a tag's `into`
+/// function.
+/// The trait object type is spelled out, so that the value is coerced into it before being passed
+/// to `From::from`.
+///
+/// # Arguments
+///
+/// * `wrapper` - The wrapper type
+/// * `object` - The trait object type, e.g. `dyn Debug`
+/// * `mutable` - Whether the wrapper holds a mutable reference
+///
+/// # Returns
+///
+/// A TokenStream containing the parenthesized construction, e.g.
+/// `(<Guard<&'_ dyn Debug> as From<&'_ dyn Debug>>::from(v))`
+fn wrap_value(wrapper: &Path, object: TokenStream, mutable: bool) -> TokenStream {
+    let reference = if mutable {
+        quote! {&'_ mut #object}
+    } else {
+        quote! {&'_ #object}
+    };
+
+    quote! {
+        (<#wrapper<#reference> as ::core::convert::From<#reference>>::from(v))
+    }
+}
+
+/// Generates the trait reference enum, holding a single trait object, and the `map` function
+/// passing the enum's trait object to a closure as a trait reference, e.g.
+/// `dispatcher.map(|r| match r { TypeTraitRef::Debug(v) => ..., TypeTraitRef::Display(v) => ... })`.
+/// An enum reused through `trait_ref = use Name` isn't defined again.
+///
+/// # Arguments
+///
+/// * `ir` - The intermediate representation containing the trait reference enum
+///
+/// # Returns
+///
+/// A TokenStream for the trait reference enum definition and the `map` function
+fn generate_trait_ref(ir: &Ir) -> TokenStream {
+    let enum_name = ir.r#enum.name;
+    let TraitRef {
+        name,
+        define,
+        traits,
+        map_arms,
+        iter_name,
+        iter_items,
+    } = &ir.trait_ref;
+
+    let mut fields = TokenStream::new();
+    for t in traits {
+        let ident = t.ident;
+        let object = object(t.path, t.bounds);
+        let reference = if t.mutable {
+            quote! {&'t mut}
+        } else {
+            quote! {&'t}
+        };
+
+        fields.extend(quote! {
+            #ident(#reference #object),
+        });
+    }
+
+    // Wrapped payloads are dropped at the end of `map`, so the trait references can only borrow
+    // them for the duration of the call.
+    let lifetime = match ir.wrapper {
+        Some(_) => quote! {'_},
+        None => quote! {'t},
+    };
+
+    // A reused enum is already defined by the enum it's taken from.
+    let definition = define.then(|| {
+        let allow = allow_non_camel_case(traits.iter().map(|t| t.ident));
+        quote! {
+            #allow
+            pub enum #name<'t> {
+                #fields
+            }
+        }
+    });
+
+    let iter = generate_trait_ref_iter(ir, iter_name, iter_items);
+
+    let mut arms = TokenStream::new();
+    for MapArm { variant, r#trait } in map_arms {
+        arms.extend(match r#trait {
+            None => quote! {
+                #enum_name::#variant => ::core::option::Option::None,
+            },
+            Some(t) => {
+                let ident = t.ident;
+                // Shared traits are reborrowed, as the variant may hold a mutable reference.
+                let (binding, value) = match (t.mutable, ir.wrapper) {
+                    (true, None) => (quote! {v}, quote! {v}),
+                    (false, None) => (quote! {v}, quote! {&*v}),
+                    (true, Some(_)) => (quote! {mut v}, quote! {&mut **v}),
+                    (false, Some(_)) => (quote! {v}, quote! {&**v}),
+                };
+
+                quote! {
+                    #enum_name::#variant(#binding) => ::core::option::Option::Some(f(#name::#ident(#value))),
+                }
+            }
+        });
+    }
+
+    quote! {
+        #definition
+
+        impl<'t> #enum_name<'t> {
+            pub fn map<U>(self, f: impl FnOnce(#name<#lifetime>) -> U) -> ::core::option::Option<U> {
+                match self {
+                    #arms
+                }
+            }
+        }
+
+        #iter
+    }
+}
+
+/// Generates an iterator over the shared trait references of the enum, returned by
+/// `iter_traits()` and by the `IntoIterator` implementation for `&Enum`.
+/// The iterator's state is the index of the next trait to query, and traits the enum's variant
+/// doesn't implement are skipped.
+/// Also generates `as_any_of(&["Display", "Debug"])`, returning the trait reference of the first
+/// trait in the given order the variant implements. Unknown names are skipped.
+///
+/// # Arguments
+///
+/// * `ir` - The intermediate representation containing the trait reference enum
+/// * `iter_name` - The name of the iterator
+/// * `iter_items` - The traits to iterate over
+///
+/// # Returns
+///
+/// A TokenStream for the iterator definition and implementations
+fn generate_trait_ref_iter(ir: &Ir, iter_name: &Ident, iter_items: &[IterItem]) -> TokenStream {
+    let enum_name = ir.r#enum.name;
+    let trait_ref = &ir.trait_ref.name;
+
+    let indices = 0..iter_items.len();
+    let accessors: Vec<_> = iter_items.iter().map(|i| &i.accessor).collect();
+    let variants: Vec<_> = iter_items.iter().map(|i| i.variant).collect();
+    let names = variants.iter().map(|v| v.unraw().to_string());
+
+    quote! {
+        pub struct #iter_name<'a, 't> {
+            r#enum: &'a #enum_name<'t>,
+            index: usize,
+        }
+
+        #[allow(deprecated)]
+        impl<'a, 't> ::core::iter::Iterator for #iter_name<'a, 't> {
+            type Item = #trait_ref<'a>;
+
+            fn next(&mut self) -> ::core::option::Option<Self::Item> {
+                loop {
+                    let item = match self.index {
+                        #(#indices => self.r#enum.#accessors().map(#trait_ref::#variants),)*
+                        _ => return ::core::option::Option::None,
+                    };
+                    self.index += 1;
+
+                    if item.is_some() {
+                        return item;
+                    }
+                }
+            }
+        }
+
+        #[allow(deprecated)]
+        impl<'t> #enum_name<'t> {
+            pub fn iter_traits(&self) -> #iter_name<'_, 't> {
+                #iter_name {
+                    r#enum: self,
+                    index: 0,
+                }
+            }
+
+            pub fn as_any_of(&self, order: &[&str]) -> ::core::option::Option<#trait_ref<'_>> {
+                order.iter().find_map(|name| match *name {
+                    #(#names => self.#accessors().map(#trait_ref::#variants),)*
+                    _ => ::core::option::Option::None,
+                })
+            }
+        }
+
+        impl<'a, 't> ::core::iter::IntoIterator for &'a #enum_name<'t> {
+            type Item = #trait_ref<'a>;
+            type IntoIter = #iter_name<'a, 't>;
+
+            fn into_iter(self) -> Self::IntoIter {
+                self.iter_traits()
+            }
+        }
+    }
+}
+
+/// Generates the owned enum, which has the same variants as the enum but owns the values in
+/// boxes, so it isn't bound to the lifetime of the original value.
+/// Also generates the accessors and a macro moving values into the owned enum, e.g.
+/// `into_my_mux_owned!(value)`, and a conversion of the owned enum into the box of each trait,
+/// e.g. `impl TryFrom<TypeOwned> for Box<dyn Debug>`, so `try_into()` extracts the trait object
+/// by value, returning the enum back as the error if its variant doesn't implement the trait.
+///
+/// # Arguments
+///
+/// * `ir` - The intermediate representation containing the enum definition
+/// * `owned` - The identifiers used in owned mode
+///
+/// # Returns
+///
+/// A TokenStream for the owned enum definition, its implementation and its macro
+fn generate_owned(ir: &Ir, owned: &Owned) -> TokenStream {
+    let Owned {
+        name,
+        into,
+        inner_into,
+        set: MacroIdents {
+            inner: inner_set,
+            public: set,
+        },
+    } = owned;
+    let tag = select_tag(ir, quote! {value});
+
+    let mut enum_fields = TokenStream::new();
+
+    for EnumVariant {
+        ident, constraint, ..
+    } in &ir.r#enum.variants
+    {
+        let constraint = match constraint {
+            Constraint::None => quote! {},
+            Constraint::Path(path, bounds) => {
+                quote! {(::std::boxed::Box<dyn #path #(+ #bounds)*>)}
+            }
+            Constraint::Ident(ident) => quote! {(::std::boxed::Box<dyn #ident>)},
+        };
+
+        enum_fields.extend(quote! {
+            #ident #constraint,
+        });
+    }
+
+    let fns = generate_accessors(ir, name, false, true);
+
+    // The boxes of the variants implementing several traits are upcast to the requested trait.
+    let conversions = ir.enum_impl.functions.iter().map(
+        |Function {
+             result_path,
+             result_bounds,
+             matching_variants,
+             ..
+         }| {
+            // Like `fallback_arm`, but binding the enum to return it.
+            let fallback = (matching_variants.len() < ir.r#enum.variants.len())
+                .then(|| quote! { other => ::core::result::Result::Err(other), });
+
+            quote! {
+                impl ::core::convert::TryFrom<#name> for ::std::boxed::Box<dyn #result_path #(+ #result_bounds)*> {
+                    type Error = #name;
+
+                    fn try_from(value: #name) -> ::core::result::Result<Self, #name> {
+                        match value {
+                            #(#name::#matching_variants(v) => ::core::result::Result::Ok(v),)*
+                            #fallback
+                        }
+                    }
+                }
+            }
+        },
+    );
+
+    let owned_macro = generate_macro(
+        ir,
+        quote! {},
+        inner_into,
+        into,
+        quote! {
+            ($value:expr) => {
+                {
+                    let value = $value;
+                    #tag.#into(value)
+                }
+            }
+        },
+    );
+
+    // Selecting the variant needs the concrete type, so replacing the contents is a macro as well.
+    let set_macro = generate_macro(
+        ir,
+        quote! {},
+        inner_set,
+        set,
+        quote! {
+            ($slot:expr, $value:expr) => {
+                *($slot) = #inner_into!($value)
+            }
+        },
+    );
+
+    quote! {
+        pub enum #name {
+            #enum_fields
+        }
+
+        #[allow(deprecated)]
+        impl #name {
+            #fns
+        }
+
+        #(#conversions)*
+
+        #owned_macro
+        #set_macro
+    }
+}
+
+/// Generates the steps selecting the variant, each detecting a single trait with a two-level
+/// autoref specialization, like in bitset mode.
+/// The match trait of the implemented trait is implemented for a reference to the wrap struct, so
+/// it's found first when the method is called on a double reference, and only applies when the
+/// value implements the trait. Otherwise the method of the other match trait is found after one
+/// autoderef. Either way, the state of the previous steps is wrapped in the selected step.
+///
+/// # Arguments
+///
+/// * `ir` - The intermediate representation containing the selection steps
+///
+/// # Returns
+///
+/// A TokenStream for the states and match traits of every step
+fn generate_selection_steps(ir: &Ir) -> TokenStream {
+    let generic = &ir.value_generic;
+    let wrap = ir.wrap_ident;
+    let derive = ir.show_internals.then(|| quote! {#[derive(Debug)]});
+    let mut result = TokenStream::new();
+
+    for SelectionStep {
+        path,
+        bounds,
+        step,
+        none_step,
+        r#match,
+        none_match,
+        into_step,
+    } in &ir.steps
+    {
+        result.extend(quote! {
+            #derive
+            #[doc(hidden)]
+            pub struct #step<S>(pub S);
+
+            #derive
+            #[doc(hidden)]
+            pub struct #none_step<S>(pub S);
+
+            #[doc(hidden)]
+
+            pub trait #r#match<#generic> {
+                fn #into_step<S>(&self, state: S) -> #step<S>;
+            }
+            impl<'t, #generic: #path #(+ #bounds)*> #r#match<#generic> for &#wrap<'t, #generic> {
+                fn #into_step<S>(&self, state: S) -> #step<S> {
+                    #step(state)
+                }
+            }
+
+            #[doc(hidden)]
+
+            pub trait #none_match<#generic> {
+                fn #into_step<S>(&self, state: S) -> #none_step<S>;
+            }
+            impl<'t, #generic> #none_match<#generic> for #wrap<'t, #generic> {
+                fn #into_step<S>(&self, state: S) -> #none_step<S> {
+                    #none_step(state)
+                }
+            }
+        });
+    }
+
+    result
+}
+
+/// Generates the autoref specializers, which are responsible for automatically
+/// referencing values and converting them into the appropriate enum variants.
+/// Every tag is returned by the state the selection steps leave for its variant, and by the
+/// states of the trait combinations falling back to it.
+///
+/// # Arguments
+///
+/// * `ir` - The intermediate representation containing the autoref specializers
+///
+/// # Returns
+///
+/// A TokenStream for all autoref specializer definitions and their implementations
+fn generate_autoref_specializers(ir: &Ir) -> TokenStream {
+    let generic = &ir.value_generic;
+    let mut autoref_specializers = TokenStream::new();
+
+    let enum_name = ir.r#enum.name;
+    let public = public_name(ir);
+    let into = &ir.into;
+    let into_tag = &ir.into_tag;
+    let derive = ir.show_internals.then(|| quote! {#[derive(Debug)]});
+
+    // Traits bound by the enum lifetime need it to be named, instead of being elided.
+    let (lifetime, reference, output) = match (ir.enum_lifetime, ir.borrow_mut) {
+        (false, _) => (None, borrow(ir.borrow_mut), quote! {#public}),
+        (true, false) => (Some(quote! {'t,}), quote! {&'t}, quote! {#public<'t>}),
+        (true, true) => (Some(quote! {'t,}), quote! {&'t mut}, quote! {#public<'t>}),
+    };
+
+    ir.autoref_specializers
+        .iter()
+        .map(
+            |AutorefSpecializer {
+                 tag,
+                 implemented,
+                 fallbacks,
+                 variant,
+                 constraint,
+                 mutable,
+             }| {
+                let state = selection_state(ir, implemented);
+                let state_generics = (implemented.len() < ir.steps.len()).then(|| quote! {<S>});
+                let fallbacks = fallbacks.iter().map(|fallback| selection_state(ir, fallback));
+
+                let bound = match constraint {
+                    Constraint::None => None,
+                    Constraint::Path(path, bounds) => Some(quote! {#path #(+ #bounds)*}),
+                    Constraint::Ident(ident) => Some(aggregate(ir, ident)),
+                };
+
+                let t_constraint = match &bound {
+                    None => quote! {},
+                    Some(bound) => quote! {: #bound},
+                };
+
+                let t_bounds = match &bound {
+                    None => quote! {},
+                    Some(bound) => quote! {+ #bound},
+                };
+
+                // Every tag takes the value, as the into macro can't know which one is selected,
+                // but the tag without traits discards it. It isn't bound then, so the code stays
+                // free of unused variables outside of the expansion, e.g. dumped with `dump_to`.
+                let v = match constraint {
+                    Constraint::None => quote! {_},
+                    Constraint::Path(..) | Constraint::Ident(_) => quote! {v},
+                };
+
+                let param = match (constraint, ir.wrapper) {
+                    (Constraint::None, _) => quote! {},
+                    (_, None) => quote! {(v)},
+                    (Constraint::Path(path, bounds), Some(wrapper)) => {
+                        wrap_value(wrapper, object(path, bounds), *mutable)
+                    }
+                    (Constraint::Ident(ident), Some(wrapper)) => {
+                        let aggregate = aggregate(ir, ident);
+                        wrap_value(wrapper, quote! {dyn #aggregate}, *mutable)
+                    }
+                };
+
+                let value = match ir.opaque {
+                    Some(opaque) => quote! {#opaque(#enum_name::#variant #param)},
+                    None => quote! {#enum_name::#variant #param},
                 };
 
-                autoref_specializers.extend(quote! {
-                    pub struct #tag;
-                    impl #tag {
-                        pub fn #into<T #t_constraint>(self, v: &T) -> #enum_name {
-                            #enum_name::#variant #param
-                        }
-                    }
+                let into_owned = ir.owned.as_ref().map(|Owned { name, into, .. }| {
+                    let param = match constraint {
+                        Constraint::None => quote! {},
+                        Constraint::Path(..) | Constraint::Ident(_) => {
+                            quote! {(::std::boxed::Box::new(v))}
+                        }
+                    };
+
+                    quote! {
+                        pub fn #into<#generic: 'static #t_bounds>(self, #v: #generic) -> #name {
+                            #name::#variant #param
+                        }
+                    }
+                });
+
+                // The tag without traits requires the never implemented marker trait instead.
+                let into_strict = ir.strict.as_ref().map(|Strict { into, r#trait, .. }| {
+                    let t_constraint = match constraint {
+                        Constraint::None => quote! {: #r#trait},
+                        Constraint::Path(..) | Constraint::Ident(_) => t_constraint.clone(),
+                    };
+
+                    quote! {
+                        pub fn #into<#lifetime #generic #t_constraint>(self, #v: #reference #generic) -> #output {
+                            #value
+                        }
+                    }
+                });
+
+                autoref_specializers.extend(quote! {
+                    #derive
+                    #[doc(hidden)]
+                    pub struct #tag;
+                    impl #tag {
+                        pub fn #into<#lifetime #generic #t_constraint>(self, #v: #reference #generic) -> #output {
+                            #value
+                        }
+                        #into_strict
+                        #into_owned
+                    }
+
+                    impl #state_generics #state {
+                        pub fn #into_tag(self) -> #tag {
+                            #tag
+                        }
+                    }
+
+                    #(
+                        impl #fallbacks {
+                            pub fn #into_tag(self) -> #tag {
+                                #tag
+                            }
+                        }
+                    )*
+                });
+            },
+        )
+        .count();
+
+    autoref_specializers
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::trait_mux::analyze::{Trait, analyze};
+    use crate::trait_mux::lower::lower;
+    use crate::trait_mux::parse::{parse, parse_trait_set_splice};
+
+    use super::*;
+    use proc_macro2::Span;
+    use syn::parse_quote;
+
+    fn create_idents() -> HashMap<&'static str, Ident> {
+        let mut res = HashMap::new();
+
+        res.insert("Wrap", Ident::new("Wrap", Span::call_site()));
+        res.insert("into", Ident::new("into", Span::call_site()));
+        res.insert("Combined", Ident::new("Combined", Span::call_site()));
+        res.insert("Dispatcher", Ident::new("Dispatcher", Span::call_site()));
+        res.insert("Debug", Ident::new("Debug", Span::call_site()));
+        res.insert("Display", Ident::new("Display", Span::call_site()));
+        res.insert(
+            "DebugDisplay",
+            Ident::new("DebugDisplay", Span::call_site()),
+        );
+
+        res
+    }
+
+    fn create_paths() -> HashMap<&'static str, Path> {
+        let mut res = HashMap::new();
+
+        res.insert("std::fmt::Debug", parse_quote!(std::fmt::Debug));
+        res.insert("std::fmt::Display", parse_quote!(std::fmt::Display));
+
+        res
+    }
+
+    fn create_traits<'t>(
+        idents: &'t HashMap<&'static str, Path>,
+    ) -> HashMap<&'static str, Trait<'t>> {
+        idents
+            .iter()
+            .map(|(&k, v)| {
+                (
+                    k,
+                    Trait {
+                        ident: &v.segments.last().unwrap().ident,
+                        path: v,
+                        bounds: &[],
+                        mutable: false,
+                        docs: &[],
+                    },
+                )
+            })
+            .collect()
+    }
+
+    // Helper function to create a simple IR for testing
+    fn create_test_ir<'t>(
+        idents: &'t HashMap<&str, Ident>,
+        paths: &'t HashMap<&str, Path>,
+        traits: &'t HashMap<&str, Trait<'t>>,
+    ) -> Ir<'t> {
+        Ir {
+            wrap_ident: &idents["Wrap"],
+            value_generic: Ident::new("T", Span::call_site()),
+            show_internals: false,
+            priority_chain: false,
+            borrow_mut: false,
+            into: Ident::new("into", Span::call_site()),
+            inner_into: Ident::new("__into", Span::call_site()),
+            into_tag: Ident::new("into_tag", Span::call_site()),
+            from_any_as: MacroIdents {
+                inner: Ident::new("__from_any_as", Span::call_site()),
+                public: Ident::new("from_any_as", Span::call_site()),
+            },
+            dispatch_array: None,
+            dispatch_eq: None,
+            dispatch_tuple: None,
+            into_opt: None,
+            into_result: None,
+            matches_variant: None,
+            variant_traits: None,
+            scoped_macros: false,
+            assert_dispatch: None,
+            trait_aggregates: vec![TraitAggregate {
+                name: &idents["Combined"],
+                traits: vec![&traits["std::fmt::Debug"], &traits["std::fmt::Display"]],
+            }],
+            r#enum: crate::lower::Enum {
+                name: &idents["Dispatcher"],
+                repr: None,
+                attrs: &[],
+                traits: vec![&traits["std::fmt::Debug"], &traits["std::fmt::Display"]],
+                variants: vec![
+                    EnumVariant {
+                        ident: &idents["Debug"],
+                        constraint: Constraint::Path(&paths["std::fmt::Debug"], &[]),
+                        mutable: false,
+                        traits: vec![traits["std::fmt::Debug"].ident],
+                        mask: 0b01,
+                    },
+                    EnumVariant {
+                        ident: &idents["Display"],
+                        constraint: Constraint::Path(&paths["std::fmt::Display"], &[]),
+                        mutable: false,
+                        traits: vec![traits["std::fmt::Display"].ident],
+                        mask: 0b10,
+                    },
+                    EnumVariant {
+                        ident: &idents["DebugDisplay"],
+                        constraint: Constraint::Ident(&idents["DebugDisplay"]),
+                        mutable: false,
+                        traits: vec![
+                            traits["std::fmt::Debug"].ident,
+                            traits["std::fmt::Display"].ident,
+                        ],
+                        mask: 0b11,
+                    },
+                ],
+            },
+            enum_impl: crate::lower::EnumImpl {
+                functions: vec![
+                    Function {
+                        name: Ident::new("as_debug", Span::call_site()),
+                        panicking_name: Ident::new("expect_debug", Span::call_site()),
+                        lifetime_name: Ident::new("as_debug_for", Span::call_site()),
+                        static_name: Ident::new("as_debug_static", Span::call_site()),
+                        named_name: Ident::new("as_debug_named", Span::call_site()),
+                        modify_name: Ident::new("modify_debug", Span::call_site()),
+                        unchecked_name: Ident::new("expect_debug_unchecked", Span::call_site()),
+                        objects_name: Ident::new("debug_objects", Span::call_site()),
+                        count_name: Ident::new("DEBUG_VARIANT_COUNT", Span::call_site()),
+                        trait_name: "Debug".to_string(),
+                        docs: &[],
+                        result_path: &paths["std::fmt::Debug"],
+                        result_bounds: &[],
+                        matching_variants: vec![&idents["Debug"], &idents["DebugDisplay"]],
+                    },
+                    Function {
+                        name: Ident::new("as_display", Span::call_site()),
+                        panicking_name: Ident::new("expect_display", Span::call_site()),
+                        lifetime_name: Ident::new("as_display_for", Span::call_site()),
+                        static_name: Ident::new("as_display_static", Span::call_site()),
+                        named_name: Ident::new("as_display_named", Span::call_site()),
+                        modify_name: Ident::new("modify_display", Span::call_site()),
+                        unchecked_name: Ident::new("expect_display_unchecked", Span::call_site()),
+                        objects_name: Ident::new("display_objects", Span::call_site()),
+                        count_name: Ident::new("DISPLAY_VARIANT_COUNT", Span::call_site()),
+                        trait_name: "Display".to_string(),
+                        docs: &[],
+                        result_path: &paths["std::fmt::Display"],
+                        result_bounds: &[],
+                        matching_variants: vec![&idents["Display"], &idents["DebugDisplay"]],
+                    },
+                ],
+                mut_functions: vec![],
+                common: None,
+                marker: None,
+                bits: vec![
+                    Ident::new("DEBUG_BIT", Span::call_site()),
+                    Ident::new("DISPLAY_BIT", Span::call_site()),
+                ],
+                variants_table: None,
+                lifetime_accessors: false,
+                static_accessors: false,
+                named_accessors: false,
+                compat: None,
+                unchecked_accessors: false,
+                debug_json: None,
+            },
+            bitset: None,
+            types: None,
+            monomorphic: None,
+            wrapper: None,
+            strict: None,
+            owned: None,
+            erased: None,
+            shared: None,
+            collection: None,
+            counter: None,
+            extension: None,
+            primary: None,
+            error_impl: None,
+            eq_by_variant: false,
+            trait_names: vec!["Debug".to_string(), "Display".to_string()],
+            write_to: None,
+            access: None,
+            with_accessors: vec![],
+            opaque: None,
+            self_test: None,
+            reexport: None,
+            enum_lifetime: false,
+            trait_ref: TraitRef {
+                name: parse_quote!(DispatcherTraitRef),
+                define: true,
+                traits: vec![&traits["std::fmt::Debug"], &traits["std::fmt::Display"]],
+                map_arms: vec![
+                    MapArm {
+                        variant: &idents["Debug"],
+                        r#trait: Some(&traits["std::fmt::Debug"]),
+                    },
+                    MapArm {
+                        variant: &idents["Display"],
+                        r#trait: Some(&traits["std::fmt::Display"]),
+                    },
+                    MapArm {
+                        variant: &idents["DebugDisplay"],
+                        r#trait: Some(&traits["std::fmt::Debug"]),
+                    },
+                ],
+                iter_name: Ident::new("DispatcherIter", Span::call_site()),
+                iter_items: vec![
+                    IterItem {
+                        accessor: Ident::new("as_debug", Span::call_site()),
+                        variant: &idents["Debug"],
+                    },
+                    IterItem {
+                        accessor: Ident::new("as_display", Span::call_site()),
+                        variant: &idents["Display"],
+                    },
+                ],
+            },
+            autoref_specializers: vec![
+                AutorefSpecializer {
+                    tag: Ident::new("DebugDisplayTag", Span::call_site()),
+                    implemented: vec![true, true],
+                    fallbacks: vec![],
+                    variant: &idents["DebugDisplay"],
+                    constraint: Constraint::Ident(&idents["DebugDisplay"]),
+                    mutable: false,
+                },
+                AutorefSpecializer {
+                    tag: Ident::new("DebugTag", Span::call_site()),
+                    implemented: vec![true, false],
+                    fallbacks: vec![],
+                    variant: &idents["Debug"],
+                    constraint: Constraint::Path(&paths["std::fmt::Debug"], &[]),
+                    mutable: false,
+                },
+                AutorefSpecializer {
+                    tag: Ident::new("DisplayTag", Span::call_site()),
+                    implemented: vec![false, true],
+                    fallbacks: vec![],
+                    variant: &idents["Display"],
+                    constraint: Constraint::Path(&paths["std::fmt::Display"], &[]),
+                    mutable: false,
+                },
+            ],
+            steps: vec![
+                SelectionStep {
+                    path: &paths["std::fmt::Debug"],
+                    bounds: &[],
+                    step: Ident::new("DebugStep", Span::call_site()),
+                    none_step: Ident::new("DebugNoneStep", Span::call_site()),
+                    r#match: Ident::new("DebugMatch", Span::call_site()),
+                    none_match: Ident::new("DebugNoneMatch", Span::call_site()),
+                    into_step: Ident::new("into_debug_step", Span::call_site()),
+                },
+                SelectionStep {
+                    path: &paths["std::fmt::Display"],
+                    bounds: &[],
+                    step: Ident::new("DisplayStep", Span::call_site()),
+                    none_step: Ident::new("DisplayNoneStep", Span::call_site()),
+                    r#match: Ident::new("DisplayMatch", Span::call_site()),
+                    none_match: Ident::new("DisplayNoneMatch", Span::call_site()),
+                    into_step: Ident::new("into_display_step", Span::call_site()),
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_generate_wrap() {
+        let idents = create_idents();
+        let paths = create_paths();
+        let traits = create_traits(&paths);
+        let ir = create_test_ir(&idents, &paths, &traits);
+
+        let result = generate_wrap(&ir);
+        let expected = quote! {
+            #[repr(transparent)]
+            #[doc(hidden)]
+            pub struct Wrap<'t, T>(pub &'t T);
+        };
+        assert_eq!(result.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_generate_helpers() {
+        let idents = create_idents();
+        let paths = create_paths();
+        let traits = create_traits(&paths);
+        let ir = create_test_ir(&idents, &paths, &traits);
+
+        let result = generate_helpers(&ir).to_string();
+        let expected = quote! {
+            #[repr(transparent)]
+            #[doc(hidden)]
+            pub struct Wrap<'t, T>(pub &'t T);
+        };
+        assert!(result.starts_with(&expected.to_string()));
+        assert!(result.contains(&quote! {pub struct DebugTag;}.to_string()));
+        assert!(result.contains(&quote! {pub struct DebugStep<S>(pub S);}.to_string()));
+        assert!(!result.contains("mod"));
+    }
+
+    #[test]
+    fn test_generate_trait_aggregates() {
+        let idents = create_idents();
+        let paths = create_paths();
+        let traits = create_traits(&paths);
+        let ir = create_test_ir(&idents, &paths, &traits);
+
+        let result = generate_trait_aggregates(&ir);
+        let expected = quote! {
+            pub trait Combined: std::fmt::Debug + std::fmt::Display {}
+            impl<T: std::fmt::Debug + std::fmt::Display> Combined for T {}
+        };
+        assert_eq!(result.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_generate_trait_aggregates_enum_lifetime() {
+        let idents = create_idents();
+        let paths = create_paths();
+        let traits = create_traits(&paths);
+        let mut ir = create_test_ir(&idents, &paths, &traits);
+        ir.enum_lifetime = true;
+
+        let result = generate_trait_aggregates(&ir);
+        let expected = quote! {
+            pub trait Combined<'t>: std::fmt::Debug + std::fmt::Display {}
+            impl<'t, T: std::fmt::Debug + std::fmt::Display> Combined<'t> for T {}
+        };
+        assert_eq!(result.to_string(), expected.to_string());
+
+        let result = generate_enum(&ir).to_string();
+        let expected = quote! {DebugDisplay (&'t dyn DebugDisplay<'t>)};
+        assert!(result.contains(&expected.to_string()));
+
+        let result = generate_autoref_specializers(&ir).to_string();
+        let expected = quote! {
+            pub fn into<'t, T: DebugDisplay<'t> >(self, v: &'t T) -> Dispatcher<'t> {
+                Dispatcher::DebugDisplay(v)
+            }
+        };
+        assert!(result.contains(&expected.to_string()));
+    }
+
+    #[test]
+    fn test_generate_enum() {
+        let idents = create_idents();
+        let paths = create_paths();
+        let traits = create_traits(&paths);
+        let ir = create_test_ir(&idents, &paths, &traits);
+
+        let result = generate_enum(&ir);
+        let expected = quote! {
+            pub enum Dispatcher<'t> {
+                Debug (&'t dyn std::fmt::Debug),
+                Display (&'t dyn std::fmt::Display),
+                DebugDisplay (&'t dyn DebugDisplay),
+            }
+        };
+        assert_eq!(result.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_generate_enum_repr() {
+        let idents = create_idents();
+        let paths = create_paths();
+        let traits = create_traits(&paths);
+        let mut ir = create_test_ir(&idents, &paths, &traits);
+
+        let repr = Ident::new("u8", Span::call_site());
+        ir.r#enum.repr = Some(&repr);
+
+        let result = generate_enum(&ir);
+        let expected = quote! {
+            #[repr(u8)]
+            pub enum Dispatcher<'t> {
+                Debug (&'t dyn std::fmt::Debug),
+                Display (&'t dyn std::fmt::Display),
+                DebugDisplay (&'t dyn DebugDisplay),
+            }
+        };
+        assert_eq!(result.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_generate_enum_cfg_attrs() {
+        let idents = create_idents();
+        let paths = create_paths();
+        let traits = create_traits(&paths);
+        let mut ir = create_test_ir(&idents, &paths, &traits);
+
+        let attrs: Vec<syn::Attribute> =
+            vec![syn::parse_quote!(#[cfg_attr(feature = "serde", derive(Serialize))])];
+        ir.r#enum.attrs = &attrs;
+
+        let result = generate_enum(&ir);
+        let expected = quote! {
+            #[cfg_attr(feature = "serde", derive(Serialize))]
+            pub enum Dispatcher<'t> {
+                Debug (&'t dyn std::fmt::Debug),
+                Display (&'t dyn std::fmt::Display),
+                DebugDisplay (&'t dyn DebugDisplay),
+            }
+        };
+        assert_eq!(result.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_generate_enum_lowercase_name() {
+        let idents = create_idents();
+        let paths = create_paths();
+        let traits = create_traits(&paths);
+        let mut ir = create_test_ir(&idents, &paths, &traits);
+
+        let name: Ident = syn::parse_quote!(r#match);
+        ir.r#enum.name = &name;
+
+        let result = generate_enum(&ir);
+        let expected = quote! {
+            #[allow(non_camel_case_types)]
+            pub enum r#match<'t> {
+                Debug (&'t dyn std::fmt::Debug),
+                Display (&'t dyn std::fmt::Display),
+                DebugDisplay (&'t dyn DebugDisplay),
+            }
+        };
+        assert_eq!(result.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_generate_extension() {
+        let idents = create_idents();
+        let paths = create_paths();
+        let traits = create_traits(&paths);
+        let mut ir = create_test_ir(&idents, &paths, &traits);
+        assert!(generate_extension(&ir).is_empty());
+
+        let types: Vec<syn::Type> = vec![parse_quote!(String), parse_quote!(Vec<u8>)];
+        ir.extension = Some(Extension {
+            name: Ident::new("IntoDispatcher", Span::call_site()),
+            method: Ident::new("as_dispatcher", Span::call_site()),
+            types: types.iter().collect(),
+        });
+
+        let result = generate_extension(&ir);
+        let expected = quote! {
+            pub trait IntoDispatcher {
+                fn as_dispatcher(&self) -> Dispatcher<'_>;
+            }
+
+            impl IntoDispatcher for String {
+                fn as_dispatcher(&self) -> Dispatcher<'_> {
+                    __into!((*self))
+                }
+            }
+
+            impl<'t> ::core::convert::From<&'t String> for Dispatcher<'t> {
+                fn from(value: &'t String) -> Self {
+                    __into!((*value))
+                }
+            }
+
+            impl IntoDispatcher for Vec<u8> {
+                fn as_dispatcher(&self) -> Dispatcher<'_> {
+                    __into!((*self))
+                }
+            }
+
+            impl<'t> ::core::convert::From<&'t Vec<u8> > for Dispatcher<'t> {
+                fn from(value: &'t Vec<u8>) -> Self {
+                    __into!((*value))
+                }
+            }
+        };
+        assert_eq!(result.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_generate_enum_impl() {
+        let idents = create_idents();
+        let paths = create_paths();
+        let traits = create_traits(&paths);
+        let ir = create_test_ir(&idents, &paths, &traits);
+
+        let result = generate_enum_impl(&ir);
+        let expected = quote! {
+            #[allow(deprecated)]
+            impl<'t> Dispatcher<'t> {
+                pub const VARIANT_COUNT: usize = 3;
+                pub fn variant_name(&self) -> &'static str {
+                    match self {
+                        Dispatcher::Debug { .. } => "Debug",
+                        Dispatcher::Display { .. } => "Display",
+                        Dispatcher::DebugDisplay { .. } => "DebugDisplay",
+                    }
+                }
+                pub fn trait_names(&self) -> &'static [&'static str] {
+                    match self {
+                        Dispatcher::Debug { .. } => &["Debug"],
+                        Dispatcher::Display { .. } => &["Display"],
+                        Dispatcher::DebugDisplay { .. } => &["Debug", "Display"],
+                    }
+                }
+                pub fn discriminant(&self) -> u16 {
+                    match self {
+                        Dispatcher::Debug { .. } => 0,
+                        Dispatcher::Display { .. } => 1,
+                        Dispatcher::DebugDisplay { .. } => 2,
+                    }
+                }
+                pub const DISCRIMINANT_MASKS: [u32; 3] = [1, 2, 3];
+                pub fn traits_mask(&self) -> u32 {
+                    Self::DISCRIMINANT_MASKS[self.discriminant() as usize]
+                }
+                pub const DEBUG_BIT: u32 = 1 << 0;
+                pub const DISPLAY_BIT: u32 = 1 << 1;
+                pub fn traits_bitmask(&self) -> u32 {
+                    self.traits_mask()
+                }
+                pub fn variant_name_of(discriminant: u16) -> ::core::option::Option<&'static str> {
+                    match discriminant {
+                        0 => ::core::option::Option::Some("Debug"),
+                        1 => ::core::option::Option::Some("Display"),
+                        2 => ::core::option::Option::Some("DebugDisplay"),
+                        _ => ::core::option::Option::None,
+                    }
+                }
+                pub const fn variant_traits(discriminant: u16) -> &'static [&'static str] {
+                    match discriminant {
+                        0 => &["Debug"],
+                        1 => &["Display"],
+                        2 => &["Debug", "Display"],
+                        _ => &[],
+                    }
+                }
+                pub const DEBUG_VARIANT_COUNT: usize = 2;
+                pub const DISPLAY_VARIANT_COUNT: usize = 2;
+                pub fn as_debug(&self) -> ::core::option::Option<&dyn std::fmt::Debug> {
+                    match self {
+                        Dispatcher::Debug(v) => ::core::option::Option::Some(&**v),
+                        Dispatcher::DebugDisplay(v) => ::core::option::Option::Some(&**v),
+                        _ => ::core::option::Option::None,
+                    }
+                }
+                #[track_caller]
+                pub fn expect_debug(&self) -> &dyn std::fmt::Debug {
+                    let variant = self.variant_name();
+                    match self.as_debug() {
+                        ::core::option::Option::Some(v) => v,
+                        ::core::option::Option::None => ::core::panic!("`{}` does not implement `Debug`", variant),
+                    }
+                }
+                pub fn as_display(&self) -> ::core::option::Option<&dyn std::fmt::Display> {
+                    match self {
+                        Dispatcher::Display(v) => ::core::option::Option::Some(&**v),
+                        Dispatcher::DebugDisplay(v) => ::core::option::Option::Some(&**v),
+                        _ => ::core::option::Option::None,
+                    }
+                }
+                #[track_caller]
+                pub fn expect_display(&self) -> &dyn std::fmt::Display {
+                    let variant = self.variant_name();
+                    match self.as_display() {
+                        ::core::option::Option::Some(v) => v,
+                        ::core::option::Option::None => ::core::panic!("`{}` does not implement `Display`", variant),
+                    }
+                }
+                pub fn for_each_variant<F: ::core::ops::FnMut(&'static str, usize)>(mut f: F) {
+                    f("Debug", 1);
+                    f("Display", 1);
+                    f("DebugDisplay", 2);
+                }
+                pub fn debug_objects<'a, I>(iter: I) -> impl ::core::iter::Iterator<Item = &'a dyn std::fmt::Debug> + use<'a, 't, I>
+                where
+                    I: ::core::iter::IntoIterator<Item = &'a Self>,
+                    't: 'a,
+                {
+                    iter.into_iter().filter_map(Self::as_debug)
+                }
+                pub fn display_objects<'a, I>(iter: I) -> impl ::core::iter::Iterator<Item = &'a dyn std::fmt::Display> + use<'a, 't, I>
+                where
+                    I: ::core::iter::IntoIterator<Item = &'a Self>,
+                    't: 'a,
+                {
+                    iter.into_iter().filter_map(Self::as_display)
+                }
+            }
+        };
+        assert_eq!(result.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_generate_enum_impl_variants_table() {
+        let idents = create_idents();
+        let paths = create_paths();
+        let traits = create_traits(&paths);
+        let mut ir = create_test_ir(&idents, &paths, &traits);
+
+        ir.enum_impl.variants_table = Some(vec![
+            VariantsTableRow {
+                variant: &idents["DebugDisplay"],
+                traits: vec!["Debug".to_string(), "Display".to_string()],
+            },
+            VariantsTableRow {
+                variant: &idents["Debug"],
+                traits: vec!["Debug".to_string()],
+            },
+        ]);
+
+        let result = generate_enum_impl(&ir).to_string();
+        let expected = quote! {
+            pub const VARIANTS_TABLE: &'static [(&'static str, &'static [&'static str])] = &[
+                ("DebugDisplay", &["Debug", "Display"]),
+                ("Debug", &["Debug"])
+            ];
+        };
+        assert!(result.contains(&expected.to_string()));
+    }
+
+    #[test]
+    fn test_generate_enum_impl_unchecked_accessors() {
+        let idents = create_idents();
+        let paths = create_paths();
+        let traits = create_traits(&paths);
+        let mut ir = create_test_ir(&idents, &paths, &traits);
+
+        let result = generate_enum_impl(&ir).to_string();
+        assert!(!result.contains("unchecked"));
+
+        ir.enum_impl.unchecked_accessors = true;
+
+        let result = generate_enum_impl(&ir).to_string();
+        let expected = quote! {
+            #[track_caller]
+            pub unsafe fn expect_debug_unchecked(&self) -> &dyn std::fmt::Debug {
+                match self {
+                    Dispatcher::Debug (v) => & **v,
+                    Dispatcher::DebugDisplay (v) => & **v,
+                    _ => {
+                        ::core::debug_assert!(false, "`{}` does not implement `Debug`", self.variant_name());
+                        unsafe { ::core::hint::unreachable_unchecked() }
+                    },
+                }
+            }
+        };
+        assert!(result.contains(&expected.to_string()));
+    }
+
+    #[test]
+    fn test_generate_enum_impl_debug_json() {
+        let idents = create_idents();
+        let paths = create_paths();
+        let traits = create_traits(&paths);
+        let mut ir = create_test_ir(&idents, &paths, &traits);
+
+        assert!(
+            !generate_enum_impl(&ir)
+                .to_string()
+                .contains("to_debug_json")
+        );
+
+        ir.enum_impl.debug_json = Some(Formatting {
+            debug: Some(Ident::new("as_debug", Span::call_site())),
+            display: None,
+        });
+
+        let result = generate_enum_impl(&ir).to_string();
+        let expected = quote! {
+            json.push_str(",\"value\":");
+            if let ::core::option::Option::Some(v) = self.as_debug() {
+                push_string(&mut json, &::std::format!("{:?}", v));
+            } else {
+                json.truncate(value_start);
+            }
+        };
+        assert!(result.contains("pub fn to_debug_json"));
+        assert!(result.contains(&expected.to_string()));
+    }
+
+    #[test]
+    fn test_generate_enum_impl_exhaustive_accessor() {
+        let idents = create_idents();
+        let paths = create_paths();
+        let traits = create_traits(&paths);
+        let mut ir = create_test_ir(&idents, &paths, &traits);
+
+        // An accessor matching every variant, like the one of the universal trait, has no fallback.
+        ir.enum_impl.functions[0].matching_variants = vec![
+            &idents["Debug"],
+            &idents["Display"],
+            &idents["DebugDisplay"],
+        ];
+
+        let result = generate_enum_impl(&ir).to_string();
+        let expected = quote! {
+            pub fn as_debug(&self) -> ::core::option::Option<&dyn std::fmt::Debug> {
+                match self {
+                    Dispatcher::Debug (v) => ::core::option::Option::Some(&**v),
+                    Dispatcher::Display (v) => ::core::option::Option::Some(&**v),
+                    Dispatcher::DebugDisplay (v) => ::core::option::Option::Some(&**v),
+                }
+            }
+        };
+        assert!(result.contains(&expected.to_string()));
+    }
+
+    #[test]
+    fn test_generate_as_ref() {
+        let idents = create_idents();
+        let paths = create_paths();
+        let traits = create_traits(&paths);
+        let mut ir = create_test_ir(&idents, &paths, &traits);
+
+        // Neither trait is implemented by every variant.
+        assert!(generate_as_ref(&ir).is_empty());
+
+        ir.r#enum.variants.retain(|v| v.ident != "Display");
+        ir.enum_impl.functions[1]
+            .matching_variants
+            .retain(|v| *v != "Display");
+
+        let result = generate_as_ref(&ir).to_string();
+        let expected = quote! {
+            impl<'t> ::core::convert::AsRef<dyn std::fmt::Debug + 't> for Dispatcher<'t> {
+                fn as_ref(&self) -> &(dyn std::fmt::Debug + 't) {
+                    match self {
+                        Dispatcher::Debug (v) => &**v,
+                        Dispatcher::DebugDisplay (v) => &**v,
+                    }
+                }
+            }
+        };
+        assert!(result.contains(&expected.to_string()));
+        assert!(!result.contains("Display + 't"));
+    }
+
+    #[test]
+    fn test_generate_enum_impl_compat() {
+        let idents = create_idents();
+        let paths = create_paths();
+        let traits = create_traits(&paths);
+        let mut ir = create_test_ir(&idents, &paths, &traits);
+
+        let result = generate_enum_impl(&ir).to_string();
+        assert!(!result.contains("same_capabilities"));
+
+        let other: Path = parse_quote!(other::Other);
+        ir.enum_impl.compat = Some(&other);
+
+        let result = generate_enum_impl(&ir).to_string();
+        let expected = quote! {
+            pub fn same_capabilities(&self, other: &other::Other) -> bool {
+                self.trait_names() == other.trait_names()
+            }
+        };
+        assert!(result.contains(&expected.to_string()));
+    }
+
+    #[test]
+    fn test_generate_enum_impl_named_accessors() {
+        let idents = create_idents();
+        let paths = create_paths();
+        let traits = create_traits(&paths);
+        let mut ir = create_test_ir(&idents, &paths, &traits);
+
+        let result = generate_enum_impl(&ir).to_string();
+        assert!(!result.contains("as_debug_named"));
+
+        ir.enum_impl.named_accessors = true;
+
+        let result = generate_enum_impl(&ir).to_string();
+        let expected = quote! {
+            pub fn as_debug_named(&self) -> ::core::option::Option<(&'static str, &dyn std::fmt::Debug)> {
+                match self {
+                    Dispatcher::Debug (v) => ::core::option::Option::Some(("Debug", &**v)),
+                    Dispatcher::DebugDisplay (v) => ::core::option::Option::Some(("DebugDisplay", &**v)),
+                    _ => ::core::option::Option::None,
+                }
+            }
+        };
+        assert!(result.contains(&expected.to_string()));
+    }
+
+    #[test]
+    fn test_generate_enum_impl_static_accessors() {
+        let idents = create_idents();
+        let paths = create_paths();
+        let traits = create_traits(&paths);
+        let mut ir = create_test_ir(&idents, &paths, &traits);
+
+        let result = generate_enum_impl(&ir).to_string();
+        assert!(!result.contains("as_debug_static"));
+
+        ir.enum_impl.static_accessors = true;
+
+        let result = generate_enum_impl(&ir).to_string();
+        assert!(result.contains(&quote! { impl Dispatcher<'static> }.to_string()));
+        let expected = quote! {
+            pub fn as_debug_static(&self) -> ::core::option::Option<&(dyn std::fmt::Debug + 'static)> {
+                match self {
+                    Dispatcher::Debug (v) => ::core::option::Option::Some(&**v),
+                    Dispatcher::DebugDisplay (v) => ::core::option::Option::Some(&**v),
+                    _ => ::core::option::Option::None,
+                }
+            }
+        };
+        assert!(result.contains(&expected.to_string()));
+    }
+
+    #[test]
+    fn test_generate_enum_impl_lifetime_accessors() {
+        let idents = create_idents();
+        let paths = create_paths();
+        let traits = create_traits(&paths);
+        let mut ir = create_test_ir(&idents, &paths, &traits);
+
+        let result = generate_enum_impl(&ir).to_string();
+        assert!(!result.contains("as_debug_for"));
+
+        ir.enum_impl.lifetime_accessors = true;
+
+        let result = generate_enum_impl(&ir).to_string();
+        let expected = quote! {
+            pub fn as_debug_for<'a>(&'a self) -> ::core::option::Option<&'a (dyn std::fmt::Debug + 't)> {
+                match self {
+                    Dispatcher::Debug (v) => ::core::option::Option::Some(&**v),
+                    Dispatcher::DebugDisplay (v) => ::core::option::Option::Some(&**v),
+                    _ => ::core::option::Option::None,
+                }
+            }
+        };
+        assert!(result.contains(&expected.to_string()));
+    }
+
+    #[test]
+    fn test_generate_wrapper() {
+        let idents = create_idents();
+        let paths = create_paths();
+        let traits = create_traits(&paths);
+        let mut ir = create_test_ir(&idents, &paths, &traits);
+
+        let wrapper: Path = parse_quote!(Guard);
+        ir.wrapper = Some(&wrapper);
+
+        let result = generate_enum(&ir).to_string();
+        let expected = quote! {
+            Debug(Guard<&'t dyn std::fmt::Debug>),
+        };
+        assert!(result.contains(&expected.to_string()));
+
+        let result = generate_enum_impl(&ir).to_string();
+        let expected = quote! {
+            Dispatcher::Debug(v) => ::core::option::Option::Some(&***v),
+        };
+        assert!(result.contains(&expected.to_string()));
+
+        let result = generate_autoref_specializers(&ir).to_string();
+        let expected = quote! {
+            pub fn into<T: std::fmt::Debug>(self, v: &T) -> Dispatcher {
+                Dispatcher::Debug(
+                    <Guard<&'_ dyn std::fmt::Debug> as ::core::convert::From<&'_ dyn std::fmt::Debug>>::from(v)
+                )
+            }
+        };
+        assert!(result.contains(&expected.to_string()));
+
+        let result = generate_trait_ref(&ir).to_string();
+        let expected = quote! {
+            pub fn map<U>(self, f: impl FnOnce(DispatcherTraitRef<'_>) -> U) -> ::core::option::Option<U>
+        };
+        assert!(result.contains(&expected.to_string()));
+        let expected = quote! {
+            Dispatcher::Debug(v) => ::core::option::Option::Some(f(DispatcherTraitRef::Debug(&**v))),
+        };
+        assert!(result.contains(&expected.to_string()));
+    }
+
+    #[test]
+    fn test_generate_common() {
+        let idents = create_idents();
+        let paths = create_paths();
+        let traits = create_traits(&paths);
+        let mut ir = create_test_ir(&idents, &paths, &traits);
+
+        let common: Path = parse_quote!(Base);
+        ir.enum_impl.common = Some(Function {
+            name: Ident::new("as_base", Span::call_site()),
+            panicking_name: Ident::new("expect_base", Span::call_site()),
+            lifetime_name: Ident::new("as_base_for", Span::call_site()),
+            static_name: Ident::new("as_base_static", Span::call_site()),
+            named_name: Ident::new("as_base_named", Span::call_site()),
+            modify_name: Ident::new("modify_base", Span::call_site()),
+            unchecked_name: Ident::new("expect_base_unchecked", Span::call_site()),
+            objects_name: Ident::new("base_objects", Span::call_site()),
+            count_name: Ident::new("BASE_VARIANT_COUNT", Span::call_site()),
+            trait_name: "Base".to_string(),
+            result_path: &common,
+            result_bounds: &[],
+            docs: &[],
+            matching_variants: vec![&idents["Debug"], &idents["DebugDisplay"]],
+        });
+
+        let result = generate_enum_impl(&ir).to_string();
+
+        let expected = quote! {
+            pub fn as_base(&self) -> ::core::option::Option<&dyn Base> {
+                match self {
+                    Dispatcher::Debug(v) => ::core::option::Option::Some(&**v),
+                    Dispatcher::DebugDisplay(v) => ::core::option::Option::Some(&**v),
+                    _ => ::core::option::Option::None,
+                }
+            }
+        };
+        assert!(result.contains(&expected.to_string()));
+
+        let expected = quote! {
+            impl<'t> ::core::convert::From<Dispatcher<'t>> for ::core::option::Option<&'t dyn Base> {
+                fn from(value: Dispatcher<'t>) -> Self {
+                    match value {
+                        Dispatcher::Debug(v) => ::core::option::Option::Some(&*v),
+                        Dispatcher::Display(v) => ::core::option::Option::Some(&*v),
+                        Dispatcher::DebugDisplay(v) => ::core::option::Option::Some(&*v),
+                    }
+                }
+            }
+        };
+        assert!(result.contains(&expected.to_string()));
+
+        let expected = quote! {
+            pub fn as_common_vec<'a>(items: &'a [Self]) -> ::std::vec::Vec<&'a dyn Base>
+            where
+                't: 'a,
+            {
+                items.iter().filter_map(Self::as_base).collect()
+            }
+        };
+        assert!(result.contains(&expected.to_string()));
+
+        // The wrapper is dropped along with the enum, so the conversion can't be generated.
+        let wrapper: Path = parse_quote!(Guard);
+        ir.wrapper = Some(&wrapper);
+        let result = generate_enum_impl(&ir).to_string();
+        assert!(result.contains("as_base"));
+        assert!(!result.contains("From"));
+    }
+
+    #[test]
+    fn test_generate_marker() {
+        let idents = create_idents();
+        let paths = create_paths();
+        let traits = create_traits(&paths);
+        let mut ir = create_test_ir(&idents, &paths, &traits);
+
+        let marker: Path = parse_quote!(Marker);
+        ir.enum_impl.marker = Some(&marker);
+
+        let result = generate_enum_impl(&ir).to_string();
+
+        // Every variant has traits, so no fallback arm is needed.
+        let expected = quote! {
+            pub fn as_marker(&self) -> ::core::option::Option<&dyn Marker> {
+                match self {
+                    Dispatcher::Debug(v) => ::core::option::Option::Some(&**v),
+                    Dispatcher::Display(v) => ::core::option::Option::Some(&**v),
+                    Dispatcher::DebugDisplay(v) => ::core::option::Option::Some(&**v),
+                }
+            }
+        };
+        assert!(result.contains(&expected.to_string()));
+    }
+
+    #[test]
+    fn test_generate_strict() {
+        let idents = create_idents();
+        let paths = create_paths();
+        let traits = create_traits(&paths);
+        let mut ir = create_test_ir(&idents, &paths, &traits);
+
+        let none = Ident::new("None", Span::call_site());
+        ir.r#enum.variants.push(EnumVariant {
+            ident: &none,
+            constraint: Constraint::None,
+            mutable: false,
+            traits: vec![],
+            mask: 0,
+        });
+        ir.autoref_specializers.push(AutorefSpecializer {
+            tag: Ident::new("NoneTag", Span::call_site()),
+            implemented: vec![false, false],
+            fallbacks: vec![],
+            variant: &none,
+            constraint: Constraint::None,
+            mutable: false,
+        });
+        ir.strict = Some(Strict {
+            r#macro: MacroIdents {
+                inner: Ident::new("__into_strict", Span::call_site()),
+                public: Ident::new("into_strict", Span::call_site()),
+            },
+            into: Ident::new("into_strict", Span::call_site()),
+            r#trait: Ident::new("AnyTrait", Span::call_site()),
+        });
+
+        let result = generate_strict(&ir).to_string();
+        let expected = quote! {
+            #[doc(hidden)]
+            #[diagnostic::on_unimplemented(message = "`{Self}` implements none of the traits of `Dispatcher`")]
+            pub trait AnyTrait {}
+        };
+        assert!(result.contains(&expected.to_string()));
+        let expected = quote! {
+            (&&Wrap(&$var))
+                .into_display_step((&&Wrap(&$var)).into_debug_step(()))
+                .into_tag().into_strict(&$var)
+        };
+        assert!(result.contains(&expected.to_string()));
+
+        let result = generate_autoref_specializers(&ir).to_string();
+        let expected = quote! {
+            pub fn into_strict<T: std::fmt::Debug>(self, v: &T) -> Dispatcher {
+                Dispatcher::Debug(v)
+            }
+        };
+        assert!(result.contains(&expected.to_string()));
+        let expected = quote! {
+            pub fn into_strict<T: AnyTrait>(self, _: &T) -> Dispatcher {
+                Dispatcher::None
+            }
+        };
+        assert!(result.contains(&expected.to_string()));
+        // The tag without traits discards the value without binding it.
+        let expected = quote! {
+            pub fn into<T>(self, _: &T) -> Dispatcher {
+                Dispatcher::None
+            }
+        };
+        assert!(result.contains(&expected.to_string()));
+    }
+
+    #[test]
+    fn test_generate_mutable() {
+        let idents = create_idents();
+        let paths = create_paths();
+        let traits = create_traits(&paths);
+        let mut ir = create_test_ir(&idents, &paths, &traits);
+
+        // Make Display mutable
+        ir.borrow_mut = true;
+        ir.r#enum.variants[1].mutable = true;
+        ir.r#enum.variants[2].mutable = true;
+        ir.enum_impl.mut_functions.push(Function {
+            name: Ident::new("as_display_mut", Span::call_site()),
+            panicking_name: Ident::new("expect_display_mut", Span::call_site()),
+            lifetime_name: Ident::new("as_display_mut_for", Span::call_site()),
+            static_name: Ident::new("as_display_mut_static", Span::call_site()),
+            named_name: Ident::new("as_display_mut_named", Span::call_site()),
+            modify_name: Ident::new("modify_display", Span::call_site()),
+            unchecked_name: Ident::new("expect_display_mut_unchecked", Span::call_site()),
+            objects_name: Ident::new("display_mut_objects", Span::call_site()),
+            count_name: Ident::new("DISPLAY_MUT_VARIANT_COUNT", Span::call_site()),
+            trait_name: "Display".to_string(),
+            result_path: &paths["std::fmt::Display"],
+            result_bounds: &[],
+            docs: &[],
+            matching_variants: vec![&idents["Display"], &idents["DebugDisplay"]],
+        });
+
+        let result = generate_enum(&ir);
+        let expected = quote! {
+            pub enum Dispatcher<'t> {
+                Debug (&'t dyn std::fmt::Debug),
+                Display (&'t mut dyn std::fmt::Display),
+                DebugDisplay (&'t mut dyn DebugDisplay),
+            }
+        };
+        assert_eq!(result.to_string(), expected.to_string());
+
+        let result = generate_enum_impl(&ir).to_string();
+        let expected = quote! {
+            pub fn as_display_mut(&mut self) -> ::core::option::Option<&mut dyn std::fmt::Display> {
+                match self {
+                    Dispatcher::Display(v) => ::core::option::Option::Some(&mut **v),
+                    Dispatcher::DebugDisplay(v) => ::core::option::Option::Some(&mut **v),
+                    _ => ::core::option::Option::None,
+                }
+            }
+        };
+        assert!(result.contains(&expected.to_string()));
+
+        let expected = quote! {
+            pub fn modify_display<U>(&mut self, f: impl FnOnce(&mut dyn std::fmt::Display) -> U) -> ::core::option::Option<U> {
+                self.as_display_mut().map(f)
+            }
+        };
+        assert!(result.contains(&expected.to_string()));
+
+        let result = generate_autoref_specializers(&ir).to_string();
+        let expected = quote! {
+            pub fn into<T: std::fmt::Debug>(self, v: &mut T) -> Dispatcher {
+                Dispatcher::Debug(v)
+            }
+        };
+        assert!(result.contains(&expected.to_string()));
+
+        let result = codegen(ir).to_string();
+        assert!(result.contains(&quote! {.into_tag().into(&mut $var)}.to_string()));
+        assert!(result.contains(&quote! {downcast_mut::<$ty>()}.to_string()));
+    }
+
+    #[test]
+    fn test_generate_enum_impl_none_variant() {
+        let idents = create_idents();
+        let paths = create_paths();
+        let traits = create_traits(&paths);
+        let mut ir = create_test_ir(&idents, &paths, &traits);
+
+        let is_none = quote! { pub fn is_none };
+        assert!(
+            !generate_enum_impl(&ir)
+                .to_string()
+                .contains(&is_none.to_string())
+        );
+
+        let none = Ident::new("Nothing", Span::call_site());
+        ir.r#enum.variants.push(EnumVariant {
+            ident: &none,
+            constraint: Constraint::None,
+            mutable: false,
+            traits: vec![],
+            mask: 0,
+        });
+
+        let result = generate_enum_impl(&ir).to_string();
+        let expected = quote! {
+            pub fn is_none(&self) -> bool {
+                ::core::matches!(self, Dispatcher::Nothing { .. })
+            }
+        };
+        assert!(result.contains(&expected.to_string()));
+        let expected = quote! {
+            pub const fn none() -> Self {
+                Dispatcher::Nothing
+            }
+        };
+        assert!(result.contains(&expected.to_string()));
+    }
+
+    #[test]
+    fn test_generate_enum_impl_docs() {
+        let idents = create_idents();
+        let paths = create_paths();
+        let traits = create_traits(&paths);
+        let mut ir = create_test_ir(&idents, &paths, &traits);
+
+        let docs: Vec<syn::Attribute> = vec![parse_quote!(#[doc = " The debug one."])];
+        ir.enum_impl.functions[0].docs = &docs;
+
+        let result = generate_enum_impl(&ir).to_string();
+        let expected = quote! {
+            #[doc = " The debug one."]
+            pub fn as_debug(&self)
+        };
+        assert!(result.contains(&expected.to_string()));
+        let expected = quote! {
+            #[doc = " The debug one."]
+            #[track_caller]
+            pub fn expect_debug(&self)
+        };
+        assert!(result.contains(&expected.to_string()));
+        let expected = quote! {
+            #[doc = " The debug one."]
+            pub fn as_display(&self)
+        };
+        assert!(!result.contains(&expected.to_string()));
+    }
+
+    #[test]
+    fn test_generate_selection_steps() {
+        let idents = create_idents();
+        let paths = create_paths();
+        let traits = create_traits(&paths);
+        let ir = create_test_ir(&idents, &paths, &traits);
+
+        let result = generate_selection_steps(&ir).to_string();
+        let expected = quote! {
+            #[doc(hidden)]
+            pub struct DebugStep<S>(pub S);
+
+            #[doc(hidden)]
+            pub struct DebugNoneStep<S>(pub S);
+
+            #[doc(hidden)]
+            pub trait DebugMatch<T> {
+                fn into_debug_step<S>(&self, state: S) -> DebugStep<S>;
+            }
+            impl<'t, T: std::fmt::Debug> DebugMatch<T> for &Wrap<'t, T> {
+                fn into_debug_step<S>(&self, state: S) -> DebugStep<S> {
+                    DebugStep(state)
+                }
+            }
+
+            #[doc(hidden)]
+            pub trait DebugNoneMatch<T> {
+                fn into_debug_step<S>(&self, state: S) -> DebugNoneStep<S>;
+            }
+            impl<'t, T> DebugNoneMatch<T> for Wrap<'t, T> {
+                fn into_debug_step<S>(&self, state: S) -> DebugNoneStep<S> {
+                    DebugNoneStep(state)
+                }
+            }
+        };
+        assert!(result.starts_with(&expected.to_string()));
+        assert!(result.contains("DisplayNoneMatch"));
+    }
+
+    #[test]
+    fn test_value_generic() {
+        let idents = create_idents();
+        let paths = create_paths();
+        let traits = create_traits(&paths);
+        let mut ir = create_test_ir(&idents, &paths, &traits);
+        ir.value_generic = Ident::new("V", Span::call_site());
+
+        // Every implementation bounded by the listed traits names the value after the option.
+        let result = generate_selection_steps(&ir).to_string();
+        let expected = quote! {
+            impl<'t, V: std::fmt::Debug> DebugMatch<V> for &Wrap<'t, V>
+        };
+        assert!(result.contains(&expected.to_string()));
+
+        let result = generate_autoref_specializers(&ir).to_string();
+        let expected = quote! {
+            pub fn into<V: std::fmt::Debug>(self, v: &V) -> Dispatcher
+        };
+        assert!(result.contains(&expected.to_string()));
+
+        let result = generate_trait_aggregates(&ir).to_string();
+        let expected = quote! {
+            impl<V: std::fmt::Debug + std::fmt::Display> Combined for V {}
+        };
+        assert!(result.contains(&expected.to_string()));
+
+        let result = generate_wrap(&ir).to_string();
+        let expected = quote! {pub struct Wrap<'t, V>(pub &'t V);};
+        assert!(result.contains(&expected.to_string()));
+    }
+
+    #[test]
+    fn test_select_tag() {
+        let idents = create_idents();
+        let paths = create_paths();
+        let traits = create_traits(&paths);
+        let mut ir = create_test_ir(&idents, &paths, &traits);
+
+        // Every step takes two autoref levels, however many traits are listed.
+        let result = select_tag(&ir, quote! {$var});
+        let expected = quote! {
+            (&&Wrap(&$var))
+                .into_display_step((&&Wrap(&$var)).into_debug_step(()))
+                .into_tag()
+        };
+        assert_eq!(result.to_string(), expected.to_string());
+
+        assert_eq!(
+            selection_state(&ir, &[true, false]).to_string(),
+            quote! {DisplayNoneStep<DebugStep<()> >}.to_string()
+        );
+        // The state of the first steps is left generic if only the last ones are given.
+        assert_eq!(
+            selection_state(&ir, &[true]).to_string(),
+            quote! {DisplayStep<S>}.to_string()
+        );
+
+        ir.steps.clear();
+        let result = select_tag(&ir, quote! {value});
+        assert_eq!(result.to_string(), quote! {().into_tag()}.to_string());
+    }
+
+    #[test]
+    fn test_generate_autoref_specializers() {
+        let idents = create_idents();
+        let paths = create_paths();
+        let traits = create_traits(&paths);
+        let mut ir = create_test_ir(&idents, &paths, &traits);
+
+        let result = generate_autoref_specializers(&ir);
+
+        let expected_structs = vec![
+            quote! {
+                #[doc(hidden)]
+                pub struct DebugDisplayTag;
+            },
+            quote! {
+                #[doc(hidden)]
+                pub struct DebugTag;
+            },
+            quote! {
+                #[doc(hidden)]
+                pub struct DisplayTag;
+            },
+        ];
+
+        let expected_struct_impls = vec![
+            quote! {
+                impl DebugDisplayTag {
+                    pub fn into<T: DebugDisplay>(self, v: &T) -> Dispatcher {
+                        Dispatcher::DebugDisplay(v)
+                    }
+                }
+            },
+            quote! {
+                impl DebugTag {
+                    pub fn into<T: std::fmt::Debug>(self, v: &T) -> Dispatcher {
+                        Dispatcher::Debug(v)
+                    }
+                }
+            },
+            quote! {
+                impl DisplayTag {
+                    pub fn into<T: std::fmt::Display>(self, v: &T) -> Dispatcher {
+                        Dispatcher::Display(v)
+                    }
+                }
+            },
+        ];
+
+        // The tag of each variant is returned by the state the selection steps leave for it.
+        let expected_states = vec![
+            quote! {
+                impl DisplayStep<DebugStep<()> > {
+                    pub fn into_tag(self) -> DebugDisplayTag {
+                        DebugDisplayTag
+                    }
+                }
+            },
+            quote! {
+                impl DisplayNoneStep<DebugStep<()> > {
+                    pub fn into_tag(self) -> DebugTag {
+                        DebugTag
+                    }
+                }
+            },
+            quote! {
+                impl DisplayStep<DebugNoneStep<()> > {
+                    pub fn into_tag(self) -> DisplayTag {
+                        DisplayTag
+                    }
+                }
+            },
+        ];
+
+        // Check for expected substrings to make test less brittle
+        let result_str = result.to_string();
+
+        for expected in expected_structs {
+            assert!(result_str.contains(&expected.to_string()));
+        }
+
+        for expected in expected_struct_impls {
+            assert!(result_str.contains(&expected.to_string()));
+        }
+
+        for expected in expected_states {
+            assert!(result_str.contains(&expected.to_string()));
+        }
+        assert!(!result_str.contains("derive"));
+
+        ir.show_internals = true;
+        let result_str = generate_autoref_specializers(&ir).to_string();
+        let expected = quote! {
+            #[derive(Debug)]
+            #[doc(hidden)]
+            pub struct DebugTag;
+        };
+        assert!(result_str.contains(&expected.to_string()));
+    }
+
+    #[test]
+    fn test_generate_autoref_specializers_fallbacks() {
+        let idents = create_idents();
+        let paths = create_paths();
+        let traits = create_traits(&paths);
+        let mut ir = create_test_ir(&idents, &paths, &traits);
+
+        // Without a `Display` variant, e.g. with `implies(Display => Debug)`, a value implementing
+        // only `Display` falls back to the variant without traits.
+        let none = Ident::new("None", Span::call_site());
+        ir.autoref_specializers.pop();
+        ir.autoref_specializers.push(AutorefSpecializer {
+            tag: Ident::new("NoneTag", Span::call_site()),
+            implemented: vec![false, false],
+            fallbacks: vec![vec![false, true]],
+            variant: &none,
+            constraint: Constraint::None,
+            mutable: false,
+        });
+
+        let result = generate_autoref_specializers(&ir).to_string();
+        let expected = quote! {
+            impl DisplayNoneStep<DebugNoneStep<()> > {
+                pub fn into_tag(self) -> NoneTag {
+                    NoneTag
+                }
+            }
+
+            impl DisplayStep<DebugNoneStep<()> > {
+                pub fn into_tag(self) -> NoneTag {
+                    NoneTag
+                }
+            }
+        };
+        assert!(result.contains(&expected.to_string()));
+    }
+
+    #[test]
+    fn test_generate_from_any_as() {
+        let idents = create_idents();
+        let paths = create_paths();
+        let traits = create_traits(&paths);
+        let ir = create_test_ir(&idents, &paths, &traits);
+
+        let result = generate_from_any_as(&ir);
+        let expected = quote! {
+            #[macro_export]
+            #[allow(non_local_definitions)]
+            #[allow(unused_macros)]
+            macro_rules! __from_any_as {
+                ($any:expr, $ty:ty) => {
+                    match ($any).downcast_ref::<$ty>() {
+                        ::core::option::Option::Some(v) => ::core::option::Option::Some(__into!((*v))),
+                        ::core::option::Option::None => ::core::option::Option::None,
+                    }
+                }
+            }
+            #[allow(unused_imports)]
+            pub use __from_any_as as from_any_as;
+        };
+        assert_eq!(result.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_generate_dispatch_array() {
+        let idents = create_idents();
+        let paths = create_paths();
+        let traits = create_traits(&paths);
+        let mut ir = create_test_ir(&idents, &paths, &traits);
+
+        assert!(generate_dispatch_array(&ir).is_empty());
+
+        ir.dispatch_array = Some(MacroIdents {
+            inner: Ident::new("__dispatch_array", Span::call_site()),
+            public: Ident::new("dispatch_array", Span::call_site()),
+        });
+        let result = generate_dispatch_array(&ir);
+        let expected = quote! {
+            #[macro_export]
+            #[allow(non_local_definitions)]
+            #[allow(unused_macros)]
+            macro_rules! __dispatch_array {
+                ($array:tt) => {
+                    ($array).each_ref().map(|v| __into!((*v)))
+                }
+            }
+            #[allow(unused_imports)]
+            pub use __dispatch_array as dispatch_array;
+        };
+        assert_eq!(result.to_string(), expected.to_string());
+
+        ir.borrow_mut = true;
+        let result = generate_dispatch_array(&ir).to_string();
+        assert!(result.contains(&quote! {($array).each_mut()}.to_string()));
+    }
+
+    #[test]
+    fn test_generate_dispatch_tuple() {
+        let idents = create_idents();
+        let paths = create_paths();
+        let traits = create_traits(&paths);
+        let mut ir = create_test_ir(&idents, &paths, &traits);
+
+        assert!(generate_dispatch_tuple(&ir).is_empty());
+
+        ir.dispatch_tuple = Some(MacroIdents {
+            inner: Ident::new("__dispatch_tuple", Span::call_site()),
+            public: Ident::new("dispatch_tuple", Span::call_site()),
+        });
+        let result = generate_dispatch_tuple(&ir).to_string();
+        let expected = quote! {
+            (($e0:tt, $e1:tt $(,)?)) => {
+                (__into!($e0), __into!($e1),)
+            };
+        };
+        assert!(result.contains(&expected.to_string()));
+
+        // Every arity from 2 to 8 gets an arm.
+        assert_eq!(result.matches("=>").count(), 7);
+        assert!(result.contains(&quote! {__into!($e7)}.to_string()));
+        assert!(!result.contains("$e8"));
+    }
+
+    #[test]
+    fn test_generate_into_opt_result() {
+        let idents = create_idents();
+        let paths = create_paths();
+        let traits = create_traits(&paths);
+        let mut ir = create_test_ir(&idents, &paths, &traits);
+
+        assert!(generate_into_opt_result(&ir).is_empty());
+
+        ir.into_opt = Some(MacroIdents {
+            inner: Ident::new("__into_opt", Span::call_site()),
+            public: Ident::new("into_opt", Span::call_site()),
+        });
+        ir.into_result = Some(MacroIdents {
+            inner: Ident::new("__into_result", Span::call_site()),
+            public: Ident::new("into_result", Span::call_site()),
+        });
+        let result = generate_into_opt_result(&ir);
+        let expected = quote! {
+            #[macro_export]
+            #[allow(non_local_definitions)]
+            #[allow(unused_macros)]
+            macro_rules! __into_opt {
+                ($value:expr) => {
+                    ::core::option::Option::as_ref($value).map(|v| __into!((*v)))
+                }
+            }
+            #[allow(unused_imports)]
+            pub use __into_opt as into_opt;
+            #[macro_export]
+            #[allow(non_local_definitions)]
+            #[allow(unused_macros)]
+            macro_rules! __into_result {
+                ($value:expr) => {
+                    ::core::result::Result::as_ref($value).map(|v| __into!((*v)))
+                }
+            }
+            #[allow(unused_imports)]
+            pub use __into_result as into_result;
+        };
+        assert_eq!(result.to_string(), expected.to_string());
+
+        ir.borrow_mut = true;
+        let result = generate_into_opt_result(&ir).to_string();
+        assert!(result.contains(&quote! {::core::option::Option::as_mut($value)}.to_string()));
+        assert!(result.contains(&quote! {::core::result::Result::as_mut($value)}.to_string()));
+    }
+
+    #[test]
+    fn test_generate_dispatch_eq() {
+        let idents = create_idents();
+        let paths = create_paths();
+        let traits = create_traits(&paths);
+        let mut ir = create_test_ir(&idents, &paths, &traits);
+
+        assert!(generate_dispatch_eq(&ir).is_empty());
+
+        ir.dispatch_eq = Some(MacroIdents {
+            inner: Ident::new("__dispatch_eq", Span::call_site()),
+            public: Ident::new("dispatch_eq", Span::call_site()),
+        });
+        let result = generate_dispatch_eq(&ir);
+        let expected = quote! {
+            #[macro_export]
+            #[allow(non_local_definitions)]
+            #[allow(unused_macros)]
+            macro_rules! __dispatch_eq {
+                ($a:tt, $b:tt) => {{
+                    let a = __into!($a).discriminant();
+                    a == __into!($b).discriminant()
+                }}
+            }
+            #[allow(unused_imports)]
+            pub use __dispatch_eq as dispatch_eq;
+        };
+        assert_eq!(result.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_generate_matches_variant() {
+        let idents = create_idents();
+        let paths = create_paths();
+        let traits = create_traits(&paths);
+        let mut ir = create_test_ir(&idents, &paths, &traits);
+
+        assert!(generate_matches_variant(&ir).is_empty());
+
+        ir.matches_variant = Some(MacroIdents {
+            inner: Ident::new("__matches_variant", Span::call_site()),
+            public: Ident::new("matches_variant", Span::call_site()),
+        });
+
+        let result = generate_matches_variant(&ir).to_string();
+        let expected = quote! {
+            #[allow(dead_code, non_upper_case_globals)]
+            const Debug: u32 = 1;
+            #[allow(dead_code, non_upper_case_globals)]
+            const Display: u32 = 2;
+        };
+        assert!(result.contains(&expected.to_string()));
+        let expected = quote! {
+            ::core::matches!(mask, 1 | 2 | 3)
+        };
+        assert!(result.contains(&expected.to_string()));
+        let expected = quote! {
+            match $value {
+                Dispatcher::Debug { .. } => __MASK == 1,
+                Dispatcher::Display { .. } => __MASK == 2,
+                Dispatcher::DebugDisplay { .. } => __MASK == 3,
+            }
+        };
+        assert!(result.contains(&expected.to_string()));
+        assert!(result.contains("compile_error"));
+    }
+
+    #[test]
+    fn test_codegen_deterministic() {
+        // Test that the whole pipeline produces identical output for identical input
+        let generate = || {
+            let ast = parse(quote! {
+                #[trait_mux(implies(Ord => Eq), common = Base, owned, expose_table)]
+                Dispatcher{Debug, Display, Ord, Eq, Hash, mut Write}
+            });
+            let model = analyze(&ast);
+            codegen(lower(&model)).to_string()
+        };
+
+        assert_eq!(generate(), generate());
+    }
+
+    /// Splits generated code into its items, describing each by the tokens preceding its body,
+    /// e.g. `pub enum D < 't >`, without attributes. The items are split on tokens, as syn can't
+    /// parse every generated item.
+    fn describe_items(ts: TokenStream) -> Vec<(String, Option<TokenStream>)> {
+        let mut items = vec![];
+        let mut head = TokenStream::new();
+        let mut tokens = ts.into_iter().peekable();
+
+        while let Some(token) = tokens.next() {
+            match &token {
+                // Attributes are skipped along with their bracketed contents.
+                TokenTree::Punct(p) if p.as_char() == '#' && head.is_empty() => {
+                    tokens.next();
+                }
+                TokenTree::Punct(p) if p.as_char() == ';' => {
+                    items.push((head.to_string(), None));
+                    head = TokenStream::new();
+                }
+                TokenTree::Group(group)
+                    if group.delimiter() == proc_macro2::Delimiter::Brace
+                        && !head.to_string().starts_with("pub use") =>
+                {
+                    items.push((head.to_string(), Some(group.stream())));
+                    head = TokenStream::new();
+                }
+                _ => head.extend([token]),
+            }
+        }
+
+        assert!(head.is_empty(), "unterminated item: {}", head);
+        items
+    }
+
+    #[test]
+    fn test_codegen_auto_trait_bounds() {
+        // Only the trait objects of the bounded trait get its bounds, while the aggregate of a
+        // combined variant is bound by all of them, and detects them along with the traits.
+        let ast = parse(quote!(D { Debug + Send, Display, Error + Send + Sync }));
+        let model = analyze(&ast);
+        let result = codegen(lower(&model)).to_string();
+
+        let expected = quote! {
+            pub trait DDebugDisplayError: Debug + Display + Error + Send + Sync {}
+        };
+        assert!(result.contains(&expected.to_string()));
+
+        let expected = quote! {
+            DDebug(&'t (dyn Debug + Send)), DDisplay(&'t dyn Display), DError(&'t (dyn Error + Send + Sync)),
+        };
+        assert!(result.contains(&expected.to_string()));
+
+        let expected = quote! {
+            pub fn try_as_debug(&self) -> ::core::option::Option<&(dyn Debug + Send)>
+        };
+        assert!(result.contains(&expected.to_string()));
+        let expected = quote! {
+            pub fn try_as_display(&self) -> ::core::option::Option<&dyn Display>
+        };
+        assert!(result.contains(&expected.to_string()));
+
+        let expected = quote! {
+            impl<'t, __T: Error + Send + Sync> DErrorMatch<__T> for &WrapD<'t, __T>
+        };
+        assert!(result.contains(&expected.to_string()));
+    }
+
+    #[test]
+    fn test_codegen_priority_chain() {
+        // The first listed trait is detected last, so its step is the outermost, and its tag
+        // doesn't depend on the steps of the traits listed after it.
+        let ast = parse(quote!(
+            #[trait_mux(mode = priority_chain)]
+            D { Display, Debug }
+        ));
+        let model = analyze(&ast);
+        let result = codegen(lower(&model)).to_string();
+
+        let expected = quote! {
+            pub enum D<'t> {
+                DDisplay(&'t dyn Display),
+                DDebug(&'t dyn Debug),
+                DNone,
+            }
+        };
+        assert!(result.contains(&expected.to_string()));
+
+        let expected = quote! {
+            impl<S> DDisplayStep<S> {
+                pub fn into_d_tag(self) -> DDisplayTag {
+                    DDisplayTag
+                }
+            }
+        };
+        assert!(result.contains(&expected.to_string()));
+
+        let expected = quote! {
+            impl DDisplayNoneStep<DDebugStep<()> > {
+                pub fn into_d_tag(self) -> DDebugTag {
+                    DDebugTag
+                }
+            }
+        };
+        assert!(result.contains(&expected.to_string()));
+
+        let expected = quote! {
+            impl DDisplayNoneStep<DDebugNoneStep<()> > {
+                pub fn into_d_tag(self) -> DNoneTag {
+                    DNoneTag
+                }
+            }
+        };
+        assert!(result.contains(&expected.to_string()));
+
+        assert!(!result.contains("pub trait DDebugDisplay"));
+    }
+
+    #[test]
+    fn test_codegen_single_trait() {
+        // With a single trait there is no combination of traits, so no trait aggregate is
+        // generated, only a variant with the trait and the variant without traits.
+        let ast = parse(quote!(D { Debug }));
+        let model = analyze(&ast);
+        let items = describe_items(codegen(lower(&model)));
+
+        let names: Vec<_> = items.iter().map(|(name, _)| name.as_str()).collect();
+        let expected = [
+            // The wrap struct, the selection step and the two autoref specializers.
+            "pub struct WrapD < 't , __T > (pub & 't __T)",
+            "pub struct DDebugStep < S > (pub S)",
+            "pub struct DDebugNoneStep < S > (pub S)",
+            "pub trait DDebugMatch < __T >",
+            "impl < 't , __T : Debug > DDebugMatch < __T > for & WrapD < 't , __T >",
+            "pub trait DDebugNoneMatch < __T >",
+            "impl < 't , __T > DDebugNoneMatch < __T > for WrapD < 't , __T >",
+            "pub struct DDebugTag",
+            "impl DDebugTag",
+            "impl DDebugStep < () >",
+            "pub struct DNoneTag",
+            "impl DNoneTag",
+            "impl DDebugNoneStep < () >",
+            "impl < 't > D < 't >", // traits
+            "impl < 't > D < 't >", // write_to
+            "pub enum D < 't >",
+            "impl < 't > D < 't >", // accessors
+            "pub enum DTraitRef < 't >",
+            "impl < 't > D < 't >", // map
+            "pub struct DIter < 'a , 't >",
+            "impl < 'a , 't > :: core :: iter :: Iterator for DIter < 'a , 't >",
+            "impl < 't > D < 't >", // iter_traits
+            "impl < 'a , 't > :: core :: iter :: IntoIterator for & 'a D < 't >",
+            "macro_rules ! __into_d",
+            "pub use __into_d as into_d",
+            "macro_rules ! __from_any_as_d",
+            "pub use __from_any_as_d as from_any_as_d",
+            "macro_rules ! __matches_variant_d",
+            "pub use __matches_variant_d as matches_variant_d",
+        ];
+        assert_eq!(names, expected);
+
+        let variants = items[15].1.as_ref().unwrap();
+        assert_eq!(
+            variants.to_string(),
+            quote! {DDebug(&'t dyn Debug), DNone,}.to_string()
+        );
+    }
+
+    #[test]
+    fn test_write_dump() {
+        // Test that the dumped code is written to the file and can be parsed back
+        let ast = parse(quote! {
+            #[trait_mux(owned)]
+            Dispatcher{Debug, Display, mut Write}
+        });
+        let model = analyze(&ast);
+        let ts = codegen(lower(&model));
+
+        let file = std::env::temp_dir()
+            .join(format!("trait_mux_dump_{}", std::process::id()))
+            .join("generated.rs");
+        write_dump(&ts, &file).unwrap();
+
+        let contents = std::fs::read_to_string(&file).unwrap();
+        std::fs::remove_dir_all(file.parent().unwrap()).unwrap();
+        // syn 1 doesn't support the precise capturing syntax (`use<..>`) of the generated code, so
+        // the file is only parsed back into tokens.
+        let parsed: TokenStream = contents.parse().unwrap();
+        assert_eq!(parsed.to_string(), ts.to_string());
+    }
+
+    #[test]
+    fn test_generate_collection() {
+        let idents = create_idents();
+        let paths = create_paths();
+        let traits = create_traits(&paths);
+        let mut ir = create_test_ir(&idents, &paths, &traits);
+
+        assert!(generate_collection(&ir).is_empty());
+
+        ir.collection = Some(Collection {
+            name: Ident::new("DispatcherVec", Span::call_site()),
+            push: MacroIdents {
+                inner: Ident::new("__push_dispatcher", Span::call_site()),
+                public: Ident::new("push_dispatcher", Span::call_site()),
+            },
+        });
+
+        let result = generate_collection(&ir).to_string();
+        let expected = quote! {
+            pub struct DispatcherVec<'t>(pub ::std::vec::Vec<Dispatcher<'t>>);
+        };
+        assert!(result.contains(&expected.to_string()));
+
+        let expected = quote! {
+            macro_rules! __push_dispatcher {
+                ($collection:expr, $($var:tt),+ $(,)?) => {
+                    {
+                    $(($collection).push(__into!($var));)+
+                }
+                }
+            }
+        };
+        assert!(result.contains(&expected.to_string()));
+    }
+
+    #[test]
+    fn test_generate_counter() {
+        let idents = create_idents();
+        let paths = create_paths();
+        let traits = create_traits(&paths);
+        let mut ir = create_test_ir(&idents, &paths, &traits);
+
+        assert!(generate_counter(&ir).is_empty());
+
+        ir.counter = Some(Ident::new("DispatcherCounter", Span::call_site()));
+
+        let result = generate_counter(&ir).to_string();
+        let expected = quote! {
+            pub struct DispatcherCounter {
+                pub counts: [usize; 3],
+            }
+        };
+        assert!(result.contains(&expected.to_string()));
+
+        let expected = quote! {
+            pub fn record(&mut self, value: &Dispatcher<'_>) {
+                self.counts[value.discriminant() as usize] += 1;
+            }
+        };
+        assert!(result.contains(&expected.to_string()));
+
+        let expected = quote! {
+            ["Debug", "Display", "DebugDisplay"].into_iter().zip(self.counts).collect()
+        };
+        assert!(result.contains(&expected.to_string()));
+    }
+
+    #[test]
+    fn test_generate_self_test() {
+        let idents = create_idents();
+        let paths = create_paths();
+        let traits = create_traits(&paths);
+        let mut ir = create_test_ir(&idents, &paths, &traits);
+        assert!(generate_self_test(&ir).is_empty());
+
+        ir.self_test = Some(Ident::new("__dispatcher_self_test", Span::call_site()));
+        let result = generate_self_test(&ir).to_string();
+
+        let expected = quote! {
+            #[cfg(test)]
+            #[allow(dead_code, clippy::enum_variant_names)]
+            mod __dispatcher_self_test
+        };
+        assert!(result.starts_with(&expected.to_string()));
+
+        let expected = quote! {
+            pub trait DebugMarker {}
+            pub trait DisplayMarker {}
+        };
+        assert!(result.contains(&expected.to_string()));
+
+        // The shadow enum has the same name, and its traits are named after the listed ones
+        assert!(result.contains(&quote! {pub enum Dispatcher<'t>}.to_string()));
+        assert!(result.contains(&quote! {pub fn as_debug(&self)}.to_string()));
+
+        let expected = quote! {
+            struct DebugDisplayValue;
+            impl DebugMarker for DebugDisplayValue {}
+            impl DisplayMarker for DebugDisplayValue {}
+        };
+        assert!(result.contains(&expected.to_string()));
+
+        let expected = quote! {
+            let value = DebugDisplayValue;
+            ::core::assert_eq!(into!(value).variant_name(), "DebugDisplay");
+        };
+        assert!(result.contains(&expected.to_string()));
+    }
+
+    #[test]
+    fn test_generate_opaque() {
+        let idents = create_idents();
+        let paths = create_paths();
+        let traits = create_traits(&paths);
+        let inner = Ident::new("DispatcherInner", Span::call_site());
+        let mut ir = create_test_ir(&idents, &paths, &traits);
+        ir.r#enum.name = &inner;
+        ir.opaque = Some(&idents["Dispatcher"]);
+
+        let result = generate_opaque(&ir, &idents["Dispatcher"]).to_string();
+        let expected = quote! {
+            #[non_exhaustive]
+            pub struct Dispatcher<'t>(DispatcherInner<'t>);
+        };
+        assert!(result.contains(&expected.to_string()));
+
+        // Interpolated, as `self.0.as_debug` would be tokenized differently when written out
+        let name = Ident::new("as_debug", Span::call_site());
+        let expected = quote! {
+            pub fn #name(&self) -> ::core::option::Option<&dyn std::fmt::Debug> {
+                self.0.#name()
+            }
+        };
+        assert!(result.contains(&expected.to_string()));
+
+        // The enum itself is private
+        let result = generate_enum(&ir).to_string();
+        assert!(result.starts_with(&quote! {enum DispatcherInner<'t>}.to_string()));
+
+        let result = generate_autoref_specializers(&ir).to_string();
+        let expected = quote! {
+            pub fn into<T: std::fmt::Debug>(self, v: &T) -> Dispatcher {
+                Dispatcher(DispatcherInner::Debug(v))
+            }
+        };
+        assert!(result.contains(&expected.to_string()));
+    }
+
+    #[test]
+    fn test_generate_variant_traits() {
+        let idents = create_idents();
+        let paths = create_paths();
+        let traits = create_traits(&paths);
+        let mut ir = create_test_ir(&idents, &paths, &traits);
+
+        assert!(generate_variant_traits(&ir).is_empty());
+
+        ir.variant_traits = Some(MacroIdents {
+            inner: Ident::new("__variant_traits", Span::call_site()),
+            public: Ident::new("variant_traits", Span::call_site()),
+        });
+
+        let result = generate_variant_traits(&ir).to_string();
+        let expected = quote! {
+            (DebugDisplay) => {
+                std::fmt::Debug, std::fmt::Display
+            };
+            (DebugDisplay => $callback:ident) => {
+                $callback! { std::fmt::Debug, std::fmt::Display }
+            };
+        };
+        assert!(result.contains(&expected.to_string()));
+
+        let expected = quote! {
+            "is not a variant of `Dispatcher`"
+        };
+        assert!(result.contains(&expected.to_string()));
+    }
+
+    #[test]
+    fn test_generate_erased() {
+        let idents = create_idents();
+        let paths = create_paths();
+        let traits = create_traits(&paths);
+        let mut ir = create_test_ir(&idents, &paths, &traits);
+
+        assert!(generate_erased(&ir).is_empty());
+
+        ir.erased = Some(Ident::new("DispatcherDynRef", Span::call_site()));
+
+        let result = generate_erased(&ir).to_string();
+        let expected_enum = quote! {
+            #[derive(Clone, Copy)]
+            pub enum DispatcherDynRef {
+                Debug(*const (dyn std::fmt::Debug + 'static)),
+                Display(*const (dyn std::fmt::Display + 'static)),
+                DebugDisplay(*const (dyn DebugDisplay + 'static)),
+            }
+        };
+        assert!(result.contains(&expected_enum.to_string()));
+
+        let expected_erase = quote! {
+            Dispatcher::Debug(v) => DispatcherDynRef::Debug(unsafe {
+                ::core::mem::transmute::<*const (dyn std::fmt::Debug + 't), *const (dyn std::fmt::Debug + 'static)>(&**v)
+            }),
+        };
+        assert!(result.contains(&expected_erase.to_string()));
+
+        let expected_accessor = quote! {
+            pub unsafe fn as_debug<'a>(self) -> ::core::option::Option<&'a dyn std::fmt::Debug> {
+                match self {
+                    DispatcherDynRef::Debug (p) => ::core::option::Option::Some(unsafe { &*p }),
+                    DispatcherDynRef::DebugDisplay (p) => ::core::option::Option::Some(unsafe { &*p }),
+                    _ => ::core::option::Option::None,
+                }
+            }
+        };
+        assert!(result.contains(&expected_accessor.to_string()));
+    }
+
+    #[test]
+    fn test_generate_shared() {
+        let idents = create_idents();
+        let paths = create_paths();
+        let traits = create_traits(&paths);
+        let mut ir = create_test_ir(&idents, &paths, &traits);
 
-                    pub trait #r#match<T> {
-                        fn #into_tag(&self) -> #tag;
-                    }
-                    impl<'t, T #t_constraint> #r#match<T> for #refs #wrap<'t,T> {
-                        fn #into_tag(&self) -> #tag {
-                            #tag
-                        }
+        assert!(generate_shared(&ir).is_empty());
+
+        ir.shared = Some(Ident::new("SharedDispatcher", Span::call_site()));
+        ir.borrow_mut = true;
+        ir.r#enum.variants[1].mutable = true;
+        ir.r#enum.variants[2].mutable = true;
+        ir.enum_impl.mut_functions.push(Function {
+            name: Ident::new("try_as_display_mut", Span::call_site()),
+            panicking_name: Ident::new("as_display_mut", Span::call_site()),
+            lifetime_name: Ident::new("try_as_display_mut_for", Span::call_site()),
+            static_name: Ident::new("try_as_display_mut_static", Span::call_site()),
+            named_name: Ident::new("try_as_display_mut_named", Span::call_site()),
+            modify_name: Ident::new("modify_display", Span::call_site()),
+            unchecked_name: Ident::new("as_display_mut_unchecked", Span::call_site()),
+            objects_name: Ident::new("display_mut_objects", Span::call_site()),
+            count_name: Ident::new("DISPLAY_MUT_VARIANT_COUNT", Span::call_site()),
+            trait_name: "Display".to_string(),
+            result_path: &paths["std::fmt::Display"],
+            result_bounds: &[],
+            docs: &[],
+            matching_variants: vec![&idents["Display"], &idents["DebugDisplay"]],
+        });
+
+        let result = generate_shared(&ir).to_string();
+        let expected_enum = quote! {
+            #[derive(Clone, Copy)]
+            pub enum SharedDispatcher<'t> {
+                Debug(&'t dyn std::fmt::Debug),
+                Display(&'t dyn std::fmt::Display),
+                DebugDisplay(&'t dyn DebugDisplay),
+            }
+        };
+        assert!(result.contains(&expected_enum.to_string()));
+
+        // The mutable references are reborrowed as shared ones for the borrow of the enum.
+        let expected_conversion = quote! {
+            impl<'t> Dispatcher<'t> {
+                pub fn as_shared(&self) -> SharedDispatcher<'_> {
+                    match self {
+                        Dispatcher::Debug(v) => SharedDispatcher::Debug(&**v),
+                        Dispatcher::Display(v) => SharedDispatcher::Display(&**v),
+                        Dispatcher::DebugDisplay(v) => SharedDispatcher::DebugDisplay(&**v),
                     }
-                });
+                }
+            }
+        };
+        assert!(result.contains(&expected_conversion.to_string()));
+
+        // The view only has the shared accessors.
+        assert!(result.contains(&quote! {pub fn as_debug(&self)}.to_string()));
+        assert!(!result.contains("try_as_display_mut"));
+        assert!(!result.contains("modify_display"));
+    }
+
+    #[test]
+    fn test_generate_assert_dispatch() {
+        let idents = create_idents();
+        let paths = create_paths();
+        let traits = create_traits(&paths);
+        let mut ir = create_test_ir(&idents, &paths, &traits);
+
+        assert!(generate_assert_dispatch(&ir).is_empty());
+
+        ir.assert_dispatch = Some(MacroIdents {
+            inner: Ident::new("__assert_dispatch", Span::call_site()),
+            public: Ident::new("assert_dispatch", Span::call_site()),
+        });
+
+        let result = generate_assert_dispatch(&ir);
+        let expected = quote! {
+            #[cfg(test)]
+            #[macro_export]
+            #[allow(non_local_definitions)]
+            #[allow(unused_macros)]
+            macro_rules! __assert_dispatch {
+                ($var:tt => $variant:ident) => {
+                    ::core::assert_eq!(__into!($var).variant_name(), ::core::stringify!($variant))
+                }
+            }
+            #[cfg(test)]
+            #[allow(unused_imports)]
+            pub use __assert_dispatch as assert_dispatch;
+        };
+        assert_eq!(result.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_generate_monomorphic() {
+        let idents = create_idents();
+        let paths = create_paths();
+        let traits = create_traits(&paths);
+        let mut ir = create_test_ir(&idents, &paths, &traits);
+        ir.monomorphic = Some(Monomorphic {
+            r#trait: BitsetTrait {
+                path: &paths["std::fmt::Debug"],
+                accessor: Ident::new("as_debug", Span::call_site()),
+                docs: &[],
+                tag: Ident::new("DebugTag", Span::call_site()),
+                none_tag: Ident::new("DebugNoneTag", Span::call_site()),
+                r#match: Ident::new("DebugMatch", Span::call_site()),
+                none_match: Ident::new("DebugNoneMatch", Span::call_site()),
+                into_tag: Ident::new("into_debug_tag", Span::call_site()),
+                erase: Ident::new("DebugErase", Span::call_site()),
             },
-        )
-        .count();
+            cast: Ident::new("DebugCast", Span::call_site()),
+        });
+        let monomorphic = ir.monomorphic.as_ref().unwrap();
 
-    autoref_specializers
-}
+        let result = generate_monomorphic(&ir, monomorphic).to_string();
 
-#[cfg(test)]
-mod tests {
-    use std::collections::HashMap;
+        let expected_struct = quote! {
+            pub struct Dispatcher<'t, T, S> {
+                value: &'t T,
+                tag: ::core::marker::PhantomData<S>,
+            }
+        };
+        assert!(result.contains(&expected_struct.to_string()));
 
-    use crate::trait_mux::analyze::Trait;
+        let expected_casts = quote! {
+            impl<T: std::fmt::Debug> DebugCast<T> for DebugTag {
+                const IMPLEMENTED: bool = true;
 
-    use super::*;
-    use proc_macro2::Span;
-    use syn::{Ident, Path, parse_quote};
+                fn cast(value: &T) -> ::core::option::Option<&dyn std::fmt::Debug> {
+                    ::core::option::Option::Some(value)
+                }
+            }
 
-    fn create_idents() -> HashMap<&'static str, Ident> {
-        let mut res = HashMap::new();
+            impl<T> DebugCast<T> for DebugNoneTag {
+                const IMPLEMENTED: bool = false;
 
-        res.insert("Wrap", Ident::new("Wrap", Span::call_site()));
-        res.insert("into", Ident::new("into", Span::call_site()));
-        res.insert("Combined", Ident::new("Combined", Span::call_site()));
-        res.insert("Dispatcher", Ident::new("Dispatcher", Span::call_site()));
-        res.insert("Debug", Ident::new("Debug", Span::call_site()));
-        res.insert("Display", Ident::new("Display", Span::call_site()));
-        res.insert(
-            "DebugDisplay",
-            Ident::new("DebugDisplay", Span::call_site()),
-        );
+                fn cast(_: &T) -> ::core::option::Option<&dyn std::fmt::Debug> {
+                    ::core::option::Option::None
+                }
+            }
+        };
+        assert!(result.contains(&expected_casts.to_string()));
 
-        res
+        let expected_impl = quote! {
+            impl<'t, T, S: DebugCast<T>> Dispatcher<'t, T, S>
+        };
+        assert!(result.contains(&expected_impl.to_string()));
+
+        let expected_accessor = quote! {
+            pub fn as_debug(&self) -> ::core::option::Option<&dyn std::fmt::Debug> {
+                S::cast(self.value)
+            }
+        };
+        assert!(result.contains(&expected_accessor.to_string()));
+
+        let result = generate_monomorphic_into_expr(&ir, monomorphic);
+        let expected = quote! {
+            match &$var {
+                value => Dispatcher::__new(value, (&&Wrap(value)).into_debug_tag()),
+            }
+        };
+        assert_eq!(result.to_string(), expected.to_string());
     }
 
-    fn create_paths() -> HashMap<&'static str, Path> {
-        let mut res = HashMap::new();
+    #[test]
+    fn test_generate_bitset() {
+        let idents = create_idents();
+        let paths = create_paths();
+        let traits = create_traits(&paths);
+        let ir = create_test_ir(&idents, &paths, &traits);
 
-        res.insert("std::fmt::Debug", parse_quote!(std::fmt::Debug));
-        res.insert("std::fmt::Display", parse_quote!(std::fmt::Display));
+        let bitset = vec![
+            BitsetTrait {
+                path: &paths["std::fmt::Debug"],
+                accessor: Ident::new("as_debug", Span::call_site()),
+                docs: &[],
+                tag: Ident::new("DebugTag", Span::call_site()),
+                none_tag: Ident::new("DebugNoneTag", Span::call_site()),
+                r#match: Ident::new("DebugMatch", Span::call_site()),
+                none_match: Ident::new("DebugNoneMatch", Span::call_site()),
+                into_tag: Ident::new("into_debug_tag", Span::call_site()),
+                erase: Ident::new("DebugErase", Span::call_site()),
+            },
+            BitsetTrait {
+                path: &paths["std::fmt::Display"],
+                accessor: Ident::new("as_display", Span::call_site()),
+                docs: &[],
+                tag: Ident::new("DisplayTag", Span::call_site()),
+                none_tag: Ident::new("DisplayNoneTag", Span::call_site()),
+                r#match: Ident::new("DisplayMatch", Span::call_site()),
+                none_match: Ident::new("DisplayNoneMatch", Span::call_site()),
+                into_tag: Ident::new("into_display_tag", Span::call_site()),
+                erase: Ident::new("DisplayErase", Span::call_site()),
+            },
+        ];
 
-        res
-    }
+        let bitset = Bitset {
+            traits: bitset,
+            vtables: Ident::new("DispatcherVtables", Span::call_site()),
+        };
+        let result = generate_bitset(&ir, &bitset).to_string();
 
-    fn create_traits<'t>(
-        idents: &'t HashMap<&'static str, Path>,
-    ) -> HashMap<&'static str, Trait<'t>> {
-        idents
-            .iter()
-            .map(|(&k, v)| {
-                (
-                    k,
-                    Trait {
-                        ident: &v.segments.last().unwrap().ident,
-                        path: v,
-                    },
-                )
-            })
-            .collect()
+        let expected_struct = quote! {
+            pub struct Dispatcher<'t> {
+                value: *const (),
+                casts: &'static (
+                    unsafe fn(*const ()) -> *const (dyn std::fmt::Debug + 'static),
+                    unsafe fn(*const ()) -> *const (dyn std::fmt::Display + 'static),
+                ),
+                mask: u32,
+                lifetime: ::core::marker::PhantomData<&'t ()>,
+            }
+        };
+        assert!(result.contains(&expected_struct.to_string()));
+
+        let expected_vtables = quote! {
+            impl<T, __E0: DebugErase<T>, __E1: DisplayErase<T> > DispatcherVtables<T, __E0, __E1> {
+                const CASTS: &'static (
+                    unsafe fn(*const ()) -> *const (dyn std::fmt::Debug + 'static),
+                    unsafe fn(*const ()) -> *const (dyn std::fmt::Display + 'static),
+                ) = &(<__E0 as DebugErase<T>>::cast, <__E1 as DisplayErase<T>>::cast,);
+                const MASK: u32 = 0
+                    | (<__E0 as DebugErase<T>>::IMPLEMENTED as u32) << 0u32
+                    | (<__E1 as DisplayErase<T>>::IMPLEMENTED as u32) << 1u32;
+            }
+        };
+        assert!(result.contains(&expected_vtables.to_string()));
+
+        let expected_accessor = quote! {
+            pub fn as_display(&self) -> ::core::option::Option<&dyn std::fmt::Display> {
+                if self.mask & (1 << 1u32) == 0 {
+                    return ::core::option::Option::None;
+                }
+                ::core::option::Option::Some(unsafe { &*(self.casts.1)(self.value) })
+            }
+        };
+        assert!(result.contains(&expected_accessor.to_string()));
+
+        let result = generate_bitset_specializers(&ir, &bitset.traits).to_string();
+        let expected_matches = quote! {
+            impl<'t, T: std::fmt::Debug> DebugMatch<T> for &Wrap<'t, T> {
+                fn into_debug_tag(&self) -> DebugTag {
+                    DebugTag
+                }
+            }
+            #[doc(hidden)]
+            pub trait DebugNoneMatch<T> {
+                fn into_debug_tag(&self) -> DebugNoneTag;
+            }
+            impl<'t, T> DebugNoneMatch<T> for Wrap<'t, T> {
+                fn into_debug_tag(&self) -> DebugNoneTag {
+                    DebugNoneTag
+                }
+            }
+        };
+        assert!(result.contains(&expected_matches.to_string()));
+
+        let result = generate_bitset_into_expr(&ir, &bitset);
+        let expected = quote! {
+            match &$var {
+                value => unsafe {
+                    Dispatcher::__new(
+                        value,
+                        (&&Wrap(value)).into_debug_tag(),
+                        (&&Wrap(value)).into_display_tag(),
+                    )
+                },
+            }
+        };
+        assert_eq!(result.to_string(), expected.to_string());
     }
 
-    // Helper function to create a simple IR for testing
-    fn create_test_ir<'t>(
-        idents: &'t HashMap<&str, Ident>,
-        paths: &'t HashMap<&str, Path>,
-        traits: &'t HashMap<&str, Trait<'t>>,
-    ) -> Ir<'t> {
-        Ir {
-            wrap_ident: &idents["Wrap"],
-            wrap_derefs: 1,
-            into: Ident::new("into", Span::call_site()),
-            inner_into: Ident::new("__into", Span::call_site()),
-            into_tag: Ident::new("into_tag", Span::call_site()),
-            trait_aggregates: vec![TraitAggregate {
-                name: &idents["Combined"],
-                traits: vec![&traits["std::fmt::Debug"], &traits["std::fmt::Display"]],
-            }],
-            r#enum: crate::lower::Enum {
-                name: &idents["Dispatcher"],
-                variants: vec![
-                    EnumVariant {
-                        ident: &idents["Debug"],
-                        constraint: Constraint::Path(&paths["std::fmt::Debug"]),
-                    },
-                    EnumVariant {
-                        ident: &idents["Display"],
-                        constraint: Constraint::Path(&paths["std::fmt::Display"]),
-                    },
-                    EnumVariant {
-                        ident: &idents["DebugDisplay"],
-                        constraint: Constraint::Ident(&idents["DebugDisplay"]),
-                    },
-                ],
+    #[test]
+    fn test_generate_types() {
+        let idents = create_idents();
+        let paths = create_paths();
+        let traits = create_traits(&paths);
+        let mut ir = create_test_ir(&idents, &paths, &traits);
+
+        let string: syn::Type = parse_quote!(String);
+        let int: syn::Type = parse_quote!(i32);
+        let variants = [
+            ConcreteType {
+                ident: Ident::new("String", Span::call_site()),
+                ty: &string,
             },
-            enum_impl: crate::lower::EnumImpl {
-                functions: vec![
-                    Function {
-                        name: Ident::new("as_debug", Span::call_site()),
-                        result_path: &paths["std::fmt::Debug"],
-                        matching_variants: vec![&idents["Debug"], &idents["DebugDisplay"]],
-                    },
-                    Function {
-                        name: Ident::new("as_display", Span::call_site()),
-                        result_path: &paths["std::fmt::Display"],
-                        matching_variants: vec![&idents["Display"], &idents["DebugDisplay"]],
-                    },
-                ],
+            ConcreteType {
+                ident: Ident::new("I32", Span::call_site()),
+                ty: &int,
             },
-            autoref_specializers: vec![
-                AutorefSpecializer {
-                    tag: Ident::new("DebugDisplayTag", Span::call_site()),
-                    r#match: Ident::new("DebugDisplayMatch", Span::call_site()),
-                    deref_count: 2,
-                    variant: &idents["DebugDisplay"],
-                    constraint: Constraint::Ident(&idents["DebugDisplay"]),
-                },
-                AutorefSpecializer {
-                    tag: Ident::new("DebugTag", Span::call_site()),
-                    r#match: Ident::new("DebugMatch", Span::call_site()),
-                    deref_count: 1,
-                    variant: &idents["Debug"],
-                    constraint: Constraint::Path(&paths["std::fmt::Debug"]),
-                },
-                AutorefSpecializer {
-                    tag: Ident::new("DisplayTag", Span::call_site()),
-                    r#match: Ident::new("DisplayMatch", Span::call_site()),
-                    deref_count: 1,
-                    variant: &idents["Display"],
-                    constraint: Constraint::Path(&paths["std::fmt::Display"]),
-                },
-            ],
-        }
+        ];
+        let types = Types {
+            variants: &variants,
+            traits: vec![BitsetTrait {
+                path: &paths["std::fmt::Debug"],
+                accessor: Ident::new("try_as_debug", Span::call_site()),
+                docs: &[],
+                tag: Ident::new("DebugTag", Span::call_site()),
+                none_tag: Ident::new("DebugNoneTag", Span::call_site()),
+                r#match: Ident::new("DebugMatch", Span::call_site()),
+                none_match: Ident::new("DebugNoneMatch", Span::call_site()),
+                into_tag: Ident::new("into_debug_tag", Span::call_site()),
+                erase: Ident::new("DebugErase", Span::call_site()),
+            }],
+            copy: None,
+        };
+
+        let result = generate_types(&ir, &types).to_string();
+
+        let expected_enum = quote! {
+            pub enum Dispatcher {
+                String(String),
+                I32(i32),
+            }
+        };
+        assert!(result.starts_with(&expected_enum.to_string()));
+
+        let expected_accessor = quote! {
+            pub fn try_as_debug(&self) -> ::core::option::Option<&dyn std::fmt::Debug> {
+                match self {
+                    Dispatcher::String(v) => (&&Wrap(v)).into_debug_tag().into(v),
+                    Dispatcher::I32(v) => (&&Wrap(v)).into_debug_tag().into(v),
+                }
+            }
+        };
+        assert!(result.contains(&expected_accessor.to_string()));
+
+        let expected_from = quote! {
+            impl ::core::convert::From<i32> for Dispatcher {
+                fn from(v: i32) -> Self {
+                    Dispatcher::I32(v)
+                }
+            }
+        };
+        assert!(result.contains(&expected_from.to_string()));
+        assert!(!result.contains("try_as_debug_copy"));
+
+        // The per-trait specializers are generated along with the other helpers.
+        ir.types = Some(types);
+        let result = generate_helpers(&ir).to_string();
+        assert!(result.contains(&quote! {pub struct DebugNoneTag;}.to_string()));
+        assert!(result.contains(&quote! {pub trait DebugNoneMatch<T>}.to_string()));
+    }
+
+    #[test]
+    fn test_generate_types_copy_accessors() {
+        let idents = create_idents();
+        let paths = create_paths();
+        let traits = create_traits(&paths);
+        let ir = create_test_ir(&idents, &paths, &traits);
+
+        let int: syn::Type = parse_quote!(u8);
+        let variants = [ConcreteType {
+            ident: Ident::new("U8", Span::call_site()),
+            ty: &int,
+        }];
+        let types = Types {
+            variants: &variants,
+            traits: vec![BitsetTrait {
+                path: &paths["std::fmt::Debug"],
+                accessor: Ident::new("try_as_debug", Span::call_site()),
+                docs: &[],
+                tag: Ident::new("DebugTag", Span::call_site()),
+                none_tag: Ident::new("DebugNoneTag", Span::call_site()),
+                r#match: Ident::new("DebugMatch", Span::call_site()),
+                none_match: Ident::new("DebugNoneMatch", Span::call_site()),
+                into_tag: Ident::new("into_debug_tag", Span::call_site()),
+                erase: Ident::new("DebugErase", Span::call_site()),
+            }],
+            copy: Some(&variants[0]),
+        };
+
+        let result = generate_types(&ir, &types).to_string();
+        let expected = quote! {
+            pub fn try_as_debug_copy(&self) -> ::core::option::Option<u8> where u8: ::core::marker::Copy {
+                let Dispatcher::U8(v) = self;
+                self.try_as_debug().map(|_| *v)
+            }
+        };
+        assert!(result.contains(&expected.to_string()));
+    }
+
+    #[test]
+    fn test_generate_trait_ref() {
+        let idents = create_idents();
+        let paths = create_paths();
+        let traits = create_traits(&paths);
+        let mut ir = create_test_ir(&idents, &paths, &traits);
+
+        let result = generate_trait_ref(&ir);
+
+        let expected = quote! {
+            pub enum DispatcherTraitRef<'t> {
+                Debug(&'t dyn std::fmt::Debug),
+                Display(&'t dyn std::fmt::Display),
+            }
+
+            impl<'t> Dispatcher<'t> {
+                pub fn map<U>(self, f: impl FnOnce(DispatcherTraitRef<'t>) -> U) -> ::core::option::Option<U> {
+                    match self {
+                        Dispatcher::Debug(v) => ::core::option::Option::Some(f(DispatcherTraitRef::Debug(&*v))),
+                        Dispatcher::Display(v) => ::core::option::Option::Some(f(DispatcherTraitRef::Display(&*v))),
+                        Dispatcher::DebugDisplay(v) => ::core::option::Option::Some(f(DispatcherTraitRef::Debug(&*v))),
+                    }
+                }
+            }
+        };
+
+        assert!(result.to_string().starts_with(&expected.to_string()));
+
+        // A reused enum is only referred to.
+        ir.trait_ref.name = parse_quote!(refs::SharedTraitRef);
+        ir.trait_ref.define = false;
+        let result = generate_trait_ref(&ir).to_string();
+        assert!(!result.contains("pub enum"));
+        let expected = quote! {
+            Dispatcher::Debug(v) => ::core::option::Option::Some(f(refs::SharedTraitRef::Debug(&*v))),
+        };
+        assert!(result.contains(&expected.to_string()));
+    }
+
+    #[test]
+    fn test_generate_trait_ref_iter() {
+        let idents = create_idents();
+        let paths = create_paths();
+        let traits = create_traits(&paths);
+        let ir = create_test_ir(&idents, &paths, &traits);
+
+        let result =
+            generate_trait_ref_iter(&ir, &ir.trait_ref.iter_name, &ir.trait_ref.iter_items)
+                .to_string();
+
+        let expected = quote! {
+            let item = match self.index {
+                0usize => self.r#enum.as_debug().map(DispatcherTraitRef::Debug),
+                1usize => self.r#enum.as_display().map(DispatcherTraitRef::Display),
+                _ => return ::core::option::Option::None,
+            };
+        };
+        assert!(result.contains(&expected.to_string()));
+
+        let expected = quote! {
+            impl<'a, 't> ::core::iter::IntoIterator for &'a Dispatcher<'t> {
+                type Item = DispatcherTraitRef<'a>;
+                type IntoIter = DispatcherIter<'a, 't>;
+
+                fn into_iter(self) -> Self::IntoIter {
+                    self.iter_traits()
+                }
+            }
+        };
+        assert!(result.contains(&expected.to_string()));
+
+        let expected = quote! {
+            pub fn as_any_of(&self, order: &[&str]) -> ::core::option::Option<DispatcherTraitRef<'_>> {
+                order.iter().find_map(|name| match *name {
+                    "Debug" => self.as_debug().map(DispatcherTraitRef::Debug),
+                    "Display" => self.as_display().map(DispatcherTraitRef::Display),
+                    _ => ::core::option::Option::None,
+                })
+            }
+        };
+        assert!(result.contains(&expected.to_string()));
     }
 
     #[test]
-    fn test_refs() {
-        let result = refs(3);
-        let expected = quote!(& & &);
-        assert_eq!(result.to_string(), expected.to_string());
+    fn test_generate_owned() {
+        let idents = create_idents();
+        let paths = create_paths();
+        let traits = create_traits(&paths);
+        let mut ir = create_test_ir(&idents, &paths, &traits);
+
+        let owned = Owned {
+            name: Ident::new("DispatcherOwned", Span::call_site()),
+            into: Ident::new("into_owned", Span::call_site()),
+            inner_into: Ident::new("__into_owned", Span::call_site()),
+            set: MacroIdents {
+                inner: Ident::new("__set_owned", Span::call_site()),
+                public: Ident::new("set_owned", Span::call_site()),
+            },
+        };
+
+        let result = generate_owned(&ir, &owned).to_string();
+
+        let expected_enum = quote! {
+            pub enum DispatcherOwned {
+                Debug (::std::boxed::Box<dyn std::fmt::Debug>),
+                Display (::std::boxed::Box<dyn std::fmt::Display>),
+                DebugDisplay (::std::boxed::Box<dyn DebugDisplay>),
+            }
+        };
+        assert!(result.contains(&expected_enum.to_string()));
+
+        let expected_accessor = quote! {
+            pub fn as_debug(&self) -> ::core::option::Option<&dyn std::fmt::Debug> {
+                match self {
+                    DispatcherOwned::Debug(v) => ::core::option::Option::Some(&**v),
+                    DispatcherOwned::DebugDisplay(v) => ::core::option::Option::Some(&**v),
+                    _ => ::core::option::Option::None,
+                }
+            }
+        };
+        assert!(result.contains("impl DispatcherOwned"));
+        assert!(result.contains(&expected_accessor.to_string()));
+
+        let expected_conversion = quote! {
+            impl ::core::convert::TryFrom<DispatcherOwned> for ::std::boxed::Box<dyn std::fmt::Debug> {
+                type Error = DispatcherOwned;
+
+                fn try_from(value: DispatcherOwned) -> ::core::result::Result<Self, DispatcherOwned> {
+                    match value {
+                        DispatcherOwned::Debug(v) => ::core::result::Result::Ok(v),
+                        DispatcherOwned::DebugDisplay(v) => ::core::result::Result::Ok(v),
+                        other => ::core::result::Result::Err(other),
+                    }
+                }
+            }
+        };
+        assert!(result.contains(&expected_conversion.to_string()));
+
+        let expected_macro = quote! {
+            macro_rules! __into_owned {
+                ($value:expr) => {
+                    {
+                        let value = $value;
+                        (&&Wrap(&value))
+                .into_display_step((&&Wrap(&value)).into_debug_step(()))
+                .into_tag().into_owned(value)
+                    }
+                }
+            }
+            #[allow(unused_imports)]
+            pub use __into_owned as into_owned;
+        };
+        assert!(result.contains(&expected_macro.to_string()));
+
+        let expected_set = quote! {
+            macro_rules! __set_owned {
+                ($slot:expr, $value:expr) => {
+                    *($slot) = __into_owned!($value)
+                }
+            }
+            #[allow(unused_imports)]
+            pub use __set_owned as set_owned;
+        };
+        assert!(result.contains(&expected_set.to_string()));
+
+        ir.owned = Some(owned);
+        let result = generate_autoref_specializers(&ir).to_string();
+        let expected_into = quote! {
+            impl DebugTag {
+                pub fn into<T: std::fmt::Debug>(self, v: &T) -> Dispatcher {
+                    Dispatcher::Debug(v)
+                }
+                pub fn into_owned<T: 'static + std::fmt::Debug>(self, v: T) -> DispatcherOwned {
+                    DispatcherOwned::Debug(::std::boxed::Box::new(v))
+                }
+            }
+        };
+        assert!(result.contains(&expected_into.to_string()));
     }
 
     #[test]
-    fn test_generate_wrap() {
+    fn test_codegen() {
         let idents = create_idents();
         let paths = create_paths();
         let traits = create_traits(&paths);
         let ir = create_test_ir(&idents, &paths, &traits);
 
-        let result = generate_wrap(&ir);
+        let result = codegen(ir);
+
+        // Just verify that the output contains expected important elements
+        let result_str = result.to_string();
+
+        assert!(result_str.contains(&quote! {pub struct Wrap}.to_string()));
+        assert!(result_str.contains(&quote! {pub trait Combined}.to_string()));
+        assert!(result_str.contains(&quote! {pub enum Dispatcher}.to_string()));
+        assert!(result_str.contains(&quote! {impl<'t> Dispatcher<'t>}.to_string()));
+        assert!(result_str.contains(&quote! {macro_rules! __into}.to_string()));
+        assert!(result_str.contains(&quote! {pub use __into as into;}.to_string()));
+        assert!(result_str.contains(&quote! {pub use __from_any_as as from_any_as;}.to_string()));
+    }
+
+    #[test]
+    fn test_generate_primary() {
+        let idents = create_idents();
+        let paths = create_paths();
+        let traits = create_traits(&paths);
+        let mut ir = create_test_ir(&idents, &paths, &traits);
+
+        assert!(generate_primary(&ir).is_empty());
+
+        ir.primary = Some(Primary {
+            accessor: Ident::new("try_as_debug", Span::call_site()),
+            path: &paths["std::fmt::Debug"],
+            bounds: &[],
+        });
+        let result = generate_primary(&ir);
         let expected = quote! {
-            pub struct Wrap<'t, T>(pub &'t T);
+            #[allow(deprecated)]
+            impl<'t> Dispatcher<'t> {
+                pub fn as_primary(&self) -> ::core::option::Option<&dyn std::fmt::Debug> {
+                    self.try_as_debug()
+                }
+            }
         };
         assert_eq!(result.to_string(), expected.to_string());
     }
 
     #[test]
-    fn test_generate_trait_aggregates() {
+    fn test_generate_error_impl() {
         let idents = create_idents();
         let paths = create_paths();
         let traits = create_traits(&paths);
-        let ir = create_test_ir(&idents, &paths, &traits);
+        let mut ir = create_test_ir(&idents, &paths, &traits);
 
-        let result = generate_trait_aggregates(&ir);
+        assert!(generate_error_impl(&ir).is_empty());
+
+        ir.error_impl = Some(Ident::new("try_as_error", Span::call_site()));
+        let result = generate_error_impl(&ir).to_string();
         let expected = quote! {
-            pub trait Combined: std::fmt::Debug + std::fmt::Display {}
-            impl<T: std::fmt::Debug + std::fmt::Display> Combined for T {}
+            fn source(&self) -> ::core::option::Option<&(dyn ::std::error::Error + 'static)> {
+                self.try_as_error().and_then(|error| error.source())
+            }
         };
-        assert_eq!(result.to_string(), expected.to_string());
+        assert!(result.contains(&expected.to_string()));
+        assert!(result.contains("impl < 't > :: std :: error :: Error for Dispatcher < 't >"));
+        assert!(result.contains("impl < 't > :: core :: fmt :: Display for Dispatcher < 't >"));
+        assert!(result.contains("impl < 't > :: core :: fmt :: Debug for Dispatcher < 't >"));
     }
 
     #[test]
-    fn test_generate_enum() {
+    fn test_generate_eq_by_variant() {
+        let idents = create_idents();
+        let paths = create_paths();
+        let traits = create_traits(&paths);
+        let mut ir = create_test_ir(&idents, &paths, &traits);
+
+        assert!(generate_eq_by_variant(&ir).is_empty());
+
+        ir.eq_by_variant = true;
+        let result = generate_eq_by_variant(&ir).to_string();
+        let expected = quote! {
+            impl<'t> ::core::cmp::PartialEq for Dispatcher<'t> {
+                fn eq(&self, other: &Self) -> bool {
+                    self.discriminant() == other.discriminant()
+                }
+            }
+        };
+        assert!(result.contains(&expected.to_string()));
+        assert!(result.contains("Hash for Dispatcher"));
+    }
+
+    #[test]
+    fn test_generate_trait_list() {
         let idents = create_idents();
         let paths = create_paths();
         let traits = create_traits(&paths);
         let ir = create_test_ir(&idents, &paths, &traits);
 
-        let result = generate_enum(&ir);
+        let result = generate_trait_list(&ir);
         let expected = quote! {
-            pub enum Dispatcher<'t> {
-                Debug (&'t dyn std::fmt::Debug),
-                Display (&'t dyn std::fmt::Display),
-                DebugDisplay (&'t dyn DebugDisplay),
+            impl<'t> Dispatcher<'t> {
+                pub fn traits() -> &'static [&'static str] {
+                    &["Debug", "Display"]
+                }
             }
         };
         assert_eq!(result.to_string(), expected.to_string());
     }
 
     #[test]
-    fn test_generate_enum_impl() {
+    fn test_generate_write_to() {
         let idents = create_idents();
         let paths = create_paths();
         let traits = create_traits(&paths);
-        let ir = create_test_ir(&idents, &paths, &traits);
+        let mut ir = create_test_ir(&idents, &paths, &traits);
 
-        let result = generate_enum_impl(&ir);
+        assert!(generate_write_to(&ir).is_empty());
+
+        ir.write_to = Some(Formatting {
+            debug: Some(Ident::new("try_as_debug", Span::call_site())),
+            display: Some(Ident::new("try_as_display", Span::call_site())),
+        });
+        let result = generate_write_to(&ir);
         let expected = quote! {
+            #[allow(deprecated)]
             impl<'t> Dispatcher<'t> {
-                pub fn as_debug(&self) -> ::core::option::Option<&dyn std::fmt::Debug> {
-                    match self {
-                        Dispatcher::Debug(v) => Some(*v),
-                        Dispatcher::DebugDisplay(v) => Some(*v),
-                        _ => None,
+                pub fn write_to<W: ::core::fmt::Write + ?::core::marker::Sized>(&self, w: &mut W) -> ::core::fmt::Result {
+                    if let ::core::option::Option::Some(v) = self.try_as_display() {
+                        return ::core::fmt::Write::write_fmt(w, ::core::format_args!("{}", v));
                     }
-                }
-                pub fn as_display(&self) -> ::core::option::Option<&dyn std::fmt::Display> {
-                    match self {
-                        Dispatcher::Display(v) => Some(*v),
-                        Dispatcher::DebugDisplay(v) => Some(*v),
-                        _ => None,
+                    if let ::core::option::Option::Some(v) = self.try_as_debug() {
+                        return ::core::fmt::Write::write_fmt(w, ::core::format_args!("{:?}", v));
                     }
+                    ::core::result::Result::Ok(())
                 }
             }
         };
@@ -456,129 +6675,173 @@ mod tests {
     }
 
     #[test]
-    fn test_generate_autoref_specializers() {
+    fn test_generate_access() {
         let idents = create_idents();
         let paths = create_paths();
         let traits = create_traits(&paths);
-        let ir = create_test_ir(&idents, &paths, &traits);
+        let mut ir = create_test_ir(&idents, &paths, &traits);
 
-        let result = generate_autoref_specializers(&ir);
+        assert!(generate_access(&ir).is_empty());
 
-        let expected_structs = vec![
-            quote! {
-                pub struct DebugDisplayTag;
-            },
-            quote! {
-                pub struct DebugTag;
-            },
-            quote! {
-                pub struct DisplayTag;
-            },
-        ];
+        ir.access = Some(Access {
+            name: Ident::new("DispatcherAccess", Span::call_site()),
+            sealed: Ident::new("__dispatcher_sealed", Span::call_site()),
+            methods: vec![
+                AccessMethod {
+                    name: Ident::new("try_as_debug", Span::call_site()),
+                    path: &paths["std::fmt::Debug"],
+                    bounds: &[],
+                    docs: &[],
+                    mutable: false,
+                },
+                AccessMethod {
+                    name: Ident::new("try_as_debug_mut", Span::call_site()),
+                    path: &paths["std::fmt::Debug"],
+                    bounds: &[],
+                    docs: &[],
+                    mutable: true,
+                },
+            ],
+        });
+        let result = generate_access(&ir);
+        let expected = quote! {
+            pub trait DispatcherAccess: __dispatcher_sealed::Sealed {
+                fn try_as_debug(&self) -> ::core::option::Option<&dyn std::fmt::Debug>;
+                fn try_as_debug_mut(&mut self) -> ::core::option::Option<&mut dyn std::fmt::Debug>;
+            }
 
-        let expected_struct_impls = vec![
-            quote! {
-                impl DebugDisplayTag {
-                    pub fn into<T: DebugDisplay>(self, v: &T) -> Dispatcher {
-                        Dispatcher::DebugDisplay(v)
-                    }
-                }
-            },
-            quote! {
-                impl DebugTag {
-                    pub fn into<T: std::fmt::Debug>(self, v: &T) -> Dispatcher {
-                        Dispatcher::Debug(v)
-                    }
+            #[allow(deprecated)]
+            impl<'t> DispatcherAccess for Dispatcher<'t> {
+                fn try_as_debug(&self) -> ::core::option::Option<&dyn std::fmt::Debug> {
+                    Self::try_as_debug(self)
                 }
-            },
-            quote! {
-                impl DisplayTag {
-                    pub fn into<T: std::fmt::Display>(self, v: &T) -> Dispatcher {
-                        Dispatcher::Display(v)
-                    }
+                fn try_as_debug_mut(&mut self) -> ::core::option::Option<&mut dyn std::fmt::Debug> {
+                    Self::try_as_debug_mut(self)
                 }
-            },
-        ];
+            }
+        };
+        assert_eq!(result.to_string(), expected.to_string());
 
-        let expected_traits = vec![
-            quote! {
-                pub trait DebugDisplayMatch<T> {
-                    fn into_tag(&self) -> DebugDisplayTag;
-                }
-            },
-            quote! {
-                pub trait DebugMatch<T> {
-                    fn into_tag(&self) -> DebugTag;
-                }
-            },
-            quote! {
-                pub trait DisplayMatch<T> {
-                    fn into_tag(&self) -> DisplayTag;
-                }
-            },
-        ];
+        let result = generate_helpers(&ir).to_string();
+        let expected = quote! {
+            mod __dispatcher_sealed {
+                pub trait Sealed {}
+            }
+            impl<'t> __dispatcher_sealed::Sealed for Dispatcher<'t> {}
+        };
+        assert!(result.contains(&expected.to_string()));
+    }
 
-        let expected_trait_impls: Vec<TokenStream> = vec![
-            quote! {
-                impl<'t, T: DebugDisplay> DebugDisplayMatch<T> for & & Wrap<'t,T> {
-                    fn into_tag(&self) -> DebugDisplayTag {
-                        DebugDisplayTag
-                    }
-                }
-            },
-            quote! {
-                impl<'t, T: std::fmt::Debug> DebugMatch<T> for & Wrap<'t,T> {
-                    fn into_tag(&self) -> DebugTag {
-                        DebugTag
-                    }
-                }
-            },
-            quote! {
-                impl<'t, T: std::fmt::Display> DisplayMatch<T> for & Wrap<'t,T> {
-                    fn into_tag(&self) -> DisplayTag {
-                        DisplayTag
-                    }
-                }
-            },
-        ];
+    #[test]
+    fn test_generate_reexport() {
+        let vis: syn::Visibility = syn::parse_quote!(pub(in crate));
+        let reexport = Reexport {
+            module: Ident::new("__dispatcher_module", Span::call_site()),
+            vis: &vis,
+        };
+        let result = generate_reexport(&reexport, quote! {pub struct Dispatcher;});
+        let expected = quote! {
+            #[doc(hidden)]
+            mod __dispatcher_module {
+                #[allow(unused_imports)]
+                use super::*;
 
-        // Check for expected substrings to make test less brittle
-        let result_str = result.to_string();
+                pub struct Dispatcher;
+            }
+            #[allow(unused_imports)]
+            pub(in crate) use __dispatcher_module::*;
+        };
+        assert_eq!(result.to_string(), expected.to_string());
+    }
 
-        for expected in expected_structs {
-            assert!(result_str.contains(&expected.to_string()));
-        }
+    #[test]
+    fn test_generate_with_accessors() {
+        let idents = create_idents();
+        let paths = create_paths();
+        let traits = create_traits(&paths);
+        let mut ir = create_test_ir(&idents, &paths, &traits);
 
-        for expected in expected_struct_impls {
-            assert!(result_str.contains(&expected.to_string()));
-        }
+        assert!(generate_with_accessors(&ir).is_empty());
 
-        for expected in expected_traits {
-            assert!(result_str.contains(&expected.to_string()));
-        }
+        ir.with_accessors = vec![WithAccessor {
+            name: Ident::new("with_debug", Span::call_site()),
+            accessor: Ident::new("try_as_debug", Span::call_site()),
+            path: &paths["std::fmt::Debug"],
+            bounds: &[],
+            docs: &[],
+        }];
+        let result = generate_with_accessors(&ir);
+        let expected = quote! {
+            #[allow(deprecated)]
+            impl<'t> Dispatcher<'t> {
+                pub fn with_debug<R>(&self, f: impl ::core::ops::FnOnce(&dyn std::fmt::Debug) -> R) -> ::core::option::Option<R> {
+                    self.try_as_debug().map(f)
+                }
+            }
+        };
+        assert_eq!(result.to_string(), expected.to_string());
+    }
 
-        for expected in expected_trait_impls {
-            assert!(result_str.contains(&expected.to_string()));
-        }
+    #[test]
+    fn test_generate_trait_set() {
+        let set: TraitSet = parse_quote!(fmt_traits = {Debug, Display});
+        let result = generate_trait_set(&set);
+        let expected = quote! {
+            #[allow(unused_macros)]
+            macro_rules! fmt_traits {
+                (@splice [$($head:tt)*] {$($rest:tt)*}) => {
+                    trait_mux!($($head)* {Debug, Display, $($rest)*});
+                };
+                ($($input:tt)*) => {
+                    trait_mux!($($input)* {Debug, Display});
+                };
+            }
+        };
+        assert_eq!(result.to_string(), expected.to_string());
+
+        // A trailing comma isn't doubled.
+        let set: TraitSet = parse_quote!(fmt_traits = (Debug,));
+        let result = generate_trait_set(&set).to_string();
+        assert!(result.contains(&quote! {{Debug, $($rest)*}}.to_string()));
+
+        let set_use: TraitSetUse = parse_quote!(#[trait_mux(owned)] Dispatcher = @fmt_traits);
+        let result = generate_trait_set_use(&set_use);
+        let expected = quote! {
+            fmt_traits!(#[trait_mux(owned)] Dispatcher);
+        };
+        assert_eq!(result.to_string(), expected.to_string());
+
+        let splice = parse_trait_set_splice(quote!(Dispatcher{Clone, @fmt_traits})).unwrap();
+        let result = generate_trait_set_splice(&splice);
+        let expected = quote! {
+            fmt_traits!(@splice [Dispatcher] {Clone,});
+        };
+        assert_eq!(result.to_string(), expected.to_string());
     }
 
     #[test]
-    fn test_codegen() {
+    fn test_codegen_scoped_macros() {
         let idents = create_idents();
         let paths = create_paths();
         let traits = create_traits(&paths);
-        let ir = create_test_ir(&idents, &paths, &traits);
-
-        let result = codegen(ir);
+        let mut ir = create_test_ir(&idents, &paths, &traits);
+        ir.scoped_macros = true;
 
-        // Just verify that the output contains expected important elements
-        let result_str = result.to_string();
+        let result_str = codegen(ir).to_string();
 
-        assert!(result_str.contains(&quote! {pub struct Wrap}.to_string()));
-        assert!(result_str.contains(&quote! {pub trait Combined}.to_string()));
-        assert!(result_str.contains(&quote! {pub enum Dispatcher}.to_string()));
-        assert!(result_str.contains(&quote! {impl<'t> Dispatcher<'t>}.to_string()));
-        assert!(result_str.contains(&quote! {macro_rules! __into}.to_string()));
-        assert!(result_str.contains(&quote! {pub use __into as into;}.to_string()));
+        assert!(!result_str.contains("macro_export"));
+        let expected = quote! {
+            #[allow(unused_macros)]
+            macro_rules! __into {
+                ($var:tt) => {
+                    (&&Wrap(&$var))
+                .into_display_step((&&Wrap(&$var)).into_debug_step(()))
+                .into_tag().into(&$var)
+                }
+            }
+            #[allow(unused_imports)]
+            pub(crate) use __into as into;
+        };
+        assert!(result_str.contains(&expected.to_string()));
     }
 }
@@ -3,12 +3,15 @@
 //! The lowering process transforms the high-level Model into structured data types that
 //! closely match the output code structure.
 
+use std::collections::HashSet;
+
 use convert_case::{Case, Casing};
 
 use proc_macro2::{Ident, Span};
-use syn::Path;
+use syn::{Attribute, Path, Type, Visibility};
 
-use super::analyze::{self, Model, Trait};
+use super::analyze::{self, ConcreteType, Model, Trait, ident_name, uses_enum_lifetime};
+use crate::options::Mode;
 
 /// Represents a collection of traits that need to be implemented together for a specific variant.
 /// Used when a variant implements multiple traits to create trait aggregates.
@@ -28,14 +31,27 @@ pub struct EnumVariant<'t> {
     /// The trait constraint associated with this variant, which could be None, a single trait Path,
     /// or a reference to a trait aggregate Ident.
     pub constraint: Constraint<'t>,
+    /// Whether the variant holds a mutable reference, set if any of its traits is mutable.
+    pub mutable: bool,
+    /// The names of the traits implemented by the variant, in the sorted trait order.
+    pub traits: Vec<&'t Ident>,
+    /// The implemented traits as a mask, with the bit of every trait set by its index in the
+    /// sorted trait list.
+    pub mask: u32,
 }
 
 /// The main enum structure that will be generated.
 pub struct Enum<'t> {
-    /// The name of the enum type to be generated.
+    /// The name of the enum type to be generated, the private `TypeInner` in opaque mode.
     pub name: &'t Ident,
     /// The collection of variants that will be part of this enum.
     pub variants: Vec<EnumVariant<'t>>,
+    /// The primitive representation of the enum, if set with the `repr` option.
+    pub repr: Option<&'t Ident>,
+    /// The `#[cfg_attr(...)]` and `#[derive(...)]` attributes re-emitted unchanged on the enum.
+    pub attrs: &'t [Attribute],
+    /// The listed traits, in the sorted order of their bits in the variant masks.
+    pub traits: Vec<&'t Trait<'t>>,
 }
 
 /// Represents a function derived from a trait, including its identifier,
@@ -43,8 +59,37 @@ pub struct Enum<'t> {
 pub struct Function<'t> {
     /// The generated function name, typically in the form `try_as_trait_name`.
     pub name: Ident,
+    /// The name of the panicking variant of the function, typically in the form `as_trait_name`.
+    pub panicking_name: Ident,
+    /// The name of the variant of the function returning a trait object bound by the enum
+    /// lifetime, typically in the form `try_as_trait_name_for`.
+    pub lifetime_name: Ident,
+    /// The name of the variant of the function for enums holding `'static` values, typically in
+    /// the form `try_as_trait_name_static`.
+    pub static_name: Ident,
+    /// The name of the variant of the function also returning the variant name, typically in the
+    /// form `try_as_trait_name_named`.
+    pub named_name: Ident,
+    /// The name of the function applying a closure to the mutable trait object, typically in the
+    /// form `modify_trait_name`. Only generated for mutable traits.
+    pub modify_name: Ident,
+    /// The name of the unsafe variant of the function skipping the check, typically in the form
+    /// `as_trait_name_unchecked`.
+    pub unchecked_name: Ident,
+    /// The name of the function collecting the trait objects out of an iterator of enums,
+    /// typically in the form `trait_name_objects`.
+    pub objects_name: Ident,
+    /// The name of the constant counting the variants implementing the trait, typically in the
+    /// form `TRAIT_NAME_VARIANT_COUNT`.
+    pub count_name: Ident,
+    /// The name of the trait, used in the panic message.
+    pub trait_name: String,
     /// The path to the trait this function returns when successful.
     pub result_path: &'t Path,
+    /// The auto-trait bounds of the returned trait object, e.g. `Send` in `dyn Debug + Send`.
+    pub result_bounds: &'t [Path],
+    /// The doc comments attached to the function, taken from the trait entry.
+    pub docs: &'t [Attribute],
     /// List of enum variant identifiers that can be matched by this function.
     pub matching_variants: Vec<&'t Ident>,
 }
@@ -53,33 +98,313 @@ pub struct Function<'t> {
 pub struct EnumImpl<'t> {
     /// Collection of functions to be implemented on the enum.
     pub functions: Vec<Function<'t>>,
+    /// Collection of functions returning mutable references, one per mutable trait, in the form
+    /// `try_as_trait_name_mut`.
+    pub mut_functions: Vec<Function<'t>>,
+    /// The accessor of the trait common to all variants with traits, if one is declared.
+    pub common: Option<Function<'t>>,
+    /// The marker supertrait viewed by the `as_marker` function, if `marker_super` is set.
+    pub marker: Option<&'t Path>,
+    /// The names of the constants holding the bit of every trait in `traits_bitmask`, in the
+    /// sorted trait order, e.g. `DEBUG_BIT`.
+    pub bits: Vec<Ident>,
+    /// The rows of the `VARIANTS_TABLE` constant, if `expose_table` is enabled.
+    pub variants_table: Option<Vec<VariantsTableRow<'t>>>,
+    /// Whether to generate the `try_as_trait_name_for` functions, if `lifetime_accessors` is
+    /// enabled.
+    pub lifetime_accessors: bool,
+    /// Whether to generate the `try_as_trait_name_static` functions, if `static` is enabled.
+    pub static_accessors: bool,
+    /// Whether to generate the `try_as_trait_name_named` functions, if `named_accessors` is
+    /// enabled.
+    pub named_accessors: bool,
+    /// The other enum compared by the `same_capabilities` function, if `compat` is set.
+    pub compat: Option<&'t Path>,
+    /// Whether to generate the `as_trait_name_unchecked` functions, if `unchecked_accessors` is
+    /// enabled.
+    pub unchecked_accessors: bool,
+    /// The accessors rendering the value in the `to_debug_json` function, if `debug_json` is
+    /// enabled.
+    pub debug_json: Option<Formatting>,
+}
+
+/// The accessors of the formatting traits, used by the `to_debug_json` and `write_to` functions.
+pub struct Formatting {
+    /// The accessor of the listed `Debug` trait, if any, e.g. `try_as_debug`.
+    pub debug: Option<Ident>,
+    /// The accessor of the listed `Display` trait, if any, e.g. `try_as_display`.
+    pub display: Option<Ident>,
+}
+
+/// A row of the `VARIANTS_TABLE` constant, mapping a variant to the traits it implements.
+pub struct VariantsTableRow<'t> {
+    /// The enum variant.
+    pub variant: &'t Ident,
+    /// The names of the traits implemented by the variant, in the sorted trait order.
+    pub traits: Vec<String>,
 }
 
 /// Specifies the kind of trait constraint applicable to an enum variant.
 pub enum Constraint<'t> {
     /// No trait constraints.
     None,
-    /// A constraint to a single trait path.
-    Path(&'t Path),
+    /// A constraint to a single trait path, along with its auto-trait bounds.
+    Path(&'t Path, &'t [Path]),
     /// A constraint to a trait aggregate, referenced by its identifier.
     Ident(&'t Ident),
 }
 
 /// Used to generate code that uses autoref specialization to convert the user parameter into a
 /// trait mux object.
+/// The variant is selected by the state the selection steps leave, so its tag is returned by that
+/// state rather than by a match trait.
 pub struct AutorefSpecializer<'t> {
     /// The identifier for the tag structure associated with this specializer.
     /// e.g. `TypeBinaryDebugDisplayTag`.
     pub tag: Ident,
-    /// The identifier for the match trait with this specializer.
-    /// e.g. `TypeBinaryDebugDisplayMatch`.
-    pub r#match: Ident,
-    /// The number of dereference operations needed for this specialization.
-    pub deref_count: usize,
+    /// Whether the variant implements the trait of each selection step, in the step order.
+    /// In priority chain mode, the steps of the traits listed after the trait of the variant are
+    /// left out from the start, as they don't affect the selection.
+    pub implemented: Vec<bool>,
+    /// The states of the trait combinations without a variant that fall back to this one, in the
+    /// same form as `implemented`. A value breaking an `implies` declaration is converted into the
+    /// variant with the most traits among those it implements all the traits of.
+    pub fallbacks: Vec<Vec<bool>>,
     /// The enum variant this specializer is associated with.
     pub variant: &'t Ident,
     /// The trait constraint for this specializer.
     pub constraint: Constraint<'t>,
+    /// Whether the variant holds a mutable reference.
+    pub mutable: bool,
+}
+
+/// A trait detected on its own while selecting the variant, using a two-level autoref
+/// specialization.
+/// Every step wraps the state left by the previous steps in a type recording whether the trait is
+/// implemented, so the final state names the variant. The number of autoref levels then doesn't
+/// grow with the number of traits.
+pub struct SelectionStep<'t> {
+    /// The path to the detected trait.
+    pub path: &'t Path,
+    /// The auto-trait bounds detected along with the trait.
+    pub bounds: &'t [Path],
+    /// The state selected when the trait is implemented, e.g. `TypeDebugStep`.
+    pub step: Ident,
+    /// The state selected when the trait isn't implemented, e.g. `TypeDebugNoneStep`.
+    pub none_step: Ident,
+    /// The match trait selecting `step`, e.g. `TypeDebugMatch`.
+    pub r#match: Ident,
+    /// The match trait selecting `none_step`, e.g. `TypeDebugNoneMatch`.
+    pub none_match: Ident,
+    /// The method wrapping the state, e.g. `into_type_debug_step`.
+    pub into_step: Ident,
+}
+
+/// A trait detected on its own in bitset mode, using a two-level autoref specialization.
+/// The tag selected by the specialization converts the value into an optional trait object.
+pub struct BitsetTrait<'t> {
+    /// The path to the detected trait.
+    pub path: &'t Path,
+    /// The accessor function name, e.g. `try_as_debug`.
+    pub accessor: Ident,
+    /// The doc comments attached to the accessor, taken from the trait entry.
+    pub docs: &'t [Attribute],
+    /// The tag selected when the trait is implemented, e.g. `TypeDebugTag`.
+    pub tag: Ident,
+    /// The tag selected when the trait isn't implemented, e.g. `TypeDebugNoneTag`.
+    pub none_tag: Ident,
+    /// The match trait selecting `tag`, e.g. `TypeDebugMatch`.
+    pub r#match: Ident,
+    /// The match trait selecting `none_tag`, e.g. `TypeDebugNoneMatch`.
+    pub none_match: Ident,
+    /// The method returning the selected tag, e.g. `into_type_debug_tag`.
+    pub into_tag: Ident,
+    /// The trait implemented by both tags, casting the erased value back into the trait object in
+    /// bitset mode, e.g. `TypeDebugErase`.
+    pub erase: Ident,
+}
+
+/// The struct generated in bitset mode instead of the enum, holding an erased pointer to the value
+/// and a mask of the traits it implements.
+pub struct Bitset<'t> {
+    /// The separately detected traits, in the sorted trait order.
+    pub traits: Vec<BitsetTrait<'t>>,
+    /// The struct holding the casts of every concrete type into the trait objects, e.g.
+    /// `TypeVtables`.
+    pub vtables: Ident,
+}
+
+/// The generic struct generated in auto mode for a single trait instead of the enum, holding the
+/// value along with the tag selected for its type, so the trait is detected at compile time.
+pub struct Monomorphic<'t> {
+    /// The single listed trait, detected like in bitset mode.
+    pub r#trait: BitsetTrait<'t>,
+    /// The trait implemented by both tags, converting the value into the optional trait object,
+    /// e.g. `TypeDebugCast`.
+    pub cast: Ident,
+}
+
+/// The enum holding concrete types in types mode, instead of trait objects.
+pub struct Types<'t> {
+    /// The listed concrete types, each held by its own variant.
+    pub variants: &'t [ConcreteType<'t>],
+    /// The traits detected for every type, like in bitset mode.
+    pub traits: Vec<BitsetTrait<'t>>,
+    /// The single listed type, returned by value by the `try_as_trait_name_copy` accessors, if
+    /// `copy_accessors` is enabled.
+    pub copy: Option<&'t ConcreteType<'t>>,
+}
+
+/// The trait reference enum, holding a single trait object taken from the enum, with a variant
+/// per listed trait.
+pub struct TraitRef<'t> {
+    /// The name of the trait reference enum, e.g. `TypeTraitRef`, or the path to the reused one.
+    pub name: Path,
+    /// Whether the trait reference enum is defined, false if it's reused from another enum.
+    pub define: bool,
+    /// The listed traits, each getting a variant named after the trait.
+    pub traits: Vec<&'t Trait<'t>>,
+    /// The arms of the `map` function, one per enum variant.
+    pub map_arms: Vec<MapArm<'t>>,
+    /// The name of the iterator over the trait references, e.g. `TypeIter`.
+    pub iter_name: Ident,
+    /// The items of the iterator, one per shared trait, in the sorted trait order.
+    /// Mutable traits are skipped, as they can't be accessed through a shared reference, as are
+    /// traits bound by the enum lifetime, which can't be shortened to the lifetime of the items.
+    pub iter_items: Vec<IterItem<'t>>,
+}
+
+/// An item of the trait reference iterator, yielded if the enum's accessor returns a value.
+pub struct IterItem<'t> {
+    /// The accessor of the trait, e.g. `try_as_debug`.
+    pub accessor: Ident,
+    /// The trait reference variant holding the accessor's result.
+    pub variant: &'t Ident,
+}
+
+/// An arm of the `map` function, converting an enum variant into a trait reference.
+pub struct MapArm<'t> {
+    /// The enum variant matched by this arm.
+    pub variant: &'t Ident,
+    /// The trait passed to the mapping function, or None for the variant without traits.
+    /// When the variant implements multiple traits, this is the first one in the sorted trait
+    /// order, matching the order of the traits in the variant name.
+    pub r#trait: Option<&'t Trait<'t>>,
+}
+
+/// The identifiers used in owned mode, where values are moved into a boxed variant of the enum.
+pub struct Owned {
+    /// The name of the owned enum, e.g. `TypeOwned`.
+    pub name: Ident,
+    /// The identifier for both the owned into macro and the tag function it calls, e.g.
+    /// `into_type_owned`.
+    pub into: Ident,
+    /// The identifier for the inner owned into macro.
+    pub inner_into: Ident,
+    /// The identifiers for the set macro, replacing the contents of an owned enum in place, e.g.
+    /// `set_type_owned`.
+    pub set: MacroIdents,
+}
+
+/// The collection of enums, e.g. `TypeVec<'t>`, wrapping a `Vec<Type<'t>>`.
+pub struct Collection {
+    /// The name of the collection type, e.g. `TypeVec`.
+    pub name: Ident,
+    /// The identifiers for the push macro, converting a value into the enum and pushing it into
+    /// the collection, e.g. `push_type`.
+    pub push: MacroIdents,
+}
+
+/// The identifiers used by the strict into macro, which fails to compile for values implementing
+/// none of the traits.
+pub struct Strict {
+    /// The identifiers for the strict into macro, e.g. `into_type_strict`.
+    pub r#macro: MacroIdents,
+    /// The tag function called by the strict into macro, e.g. `into_type_strict`.
+    pub into: Ident,
+    /// The marker trait required by the tag of the variant without traits, and implemented by no
+    /// type, e.g. `TypeImplementsAnyTrait`.
+    pub r#trait: Ident,
+}
+
+/// The trait declared with the `primary` option, accessed through `as_primary()`.
+pub struct Primary<'t> {
+    /// The fallible accessor of the trait `as_primary()` delegates to, e.g. `try_as_debug`.
+    pub accessor: Ident,
+    /// The path to the trait.
+    pub path: &'t Path,
+    /// The auto-trait bounds of the trait entry.
+    pub bounds: &'t [Path],
+}
+
+/// The accessor trait generated with the `access_trait` option, mirroring the fallible accessors
+/// of the enum, e.g. `TypeAccess`.
+pub struct Access<'t> {
+    /// The name of the trait, e.g. `TypeAccess`.
+    pub name: Ident,
+    /// The name of the private module defining the `Sealed` supertrait sealing it, e.g.
+    /// `__type_sealed`.
+    pub sealed: Ident,
+    /// The mirrored accessors, one per trait, and one more per mutable trait.
+    pub methods: Vec<AccessMethod<'t>>,
+}
+
+/// A fallible accessor mirrored by the accessor trait.
+pub struct AccessMethod<'t> {
+    /// The name of the accessor, e.g. `try_as_debug` or `try_as_write_mut`.
+    pub name: Ident,
+    /// The path to the trait the accessor returns a trait object of.
+    pub path: &'t Path,
+    /// The auto-trait bounds of the trait entry.
+    pub bounds: &'t [Path],
+    /// The doc comments of the trait entry.
+    pub docs: &'t [Attribute],
+    /// Whether the accessor borrows the enum mutably.
+    pub mutable: bool,
+}
+
+/// An accessor generated with the `with_accessors` option, passing the trait object to a closure,
+/// e.g. `with_debug`.
+pub struct WithAccessor<'t> {
+    /// The name of the accessor, e.g. `with_debug`.
+    pub name: Ident,
+    /// The fallible accessor it delegates to, e.g. `try_as_debug`.
+    pub accessor: Ident,
+    /// The path to the trait passed to the closure.
+    pub path: &'t Path,
+    /// The auto-trait bounds of the trait entry.
+    pub bounds: &'t [Path],
+    /// The doc comments of the trait entry.
+    pub docs: &'t [Attribute],
+}
+
+/// The module wrapping the generated items, re-exported into the invocation scope, set by the
+/// `reexport_at` option.
+pub struct Reexport<'t> {
+    /// The name of the wrapping module, e.g. `__type_module`.
+    pub module: Ident,
+    /// The visibility of the re-export, e.g. `pub(in crate)`.
+    pub vis: &'t Visibility,
+}
+
+/// The extension trait converting values of the listed concrete types into the enum, enabling
+/// method chaining, e.g. `value.as_my_mux().try_as_debug()`.
+pub struct Extension<'t> {
+    /// The name of the extension trait, e.g. `IntoMyMux`.
+    pub name: Ident,
+    /// The conversion method of the extension trait, e.g. `as_my_mux`.
+    pub method: Ident,
+    /// The concrete types the trait is implemented for.
+    pub types: Vec<&'t Type>,
+}
+
+/// The identifiers of a generated helper macro.
+/// The macro is defined under a hidden inner name and re-exported under its public name.
+pub struct MacroIdents {
+    /// The identifier the macro is defined with, e.g. `__from_any_as_my_mux`.
+    pub inner: Ident,
+    /// The identifier the macro is re-exported as, e.g. `from_any_as_my_mux`.
+    pub public: Ident,
 }
 
 /// Intermediate representation (IR) of the parsed AST.
@@ -92,18 +417,105 @@ pub struct Ir<'t> {
     pub r#enum: Enum<'t>,
     /// Contains all the functions that will be implemented for the generated enum.
     pub enum_impl: EnumImpl<'t>,
+    /// The trait reference enum passed to the generated `map` function.
+    pub trait_ref: TraitRef<'t>,
     /// Collection of autoref specializers.
     pub autoref_specializers: Vec<AutorefSpecializer<'t>>,
+    /// The steps selecting the variant, one per trait in the sorted trait order, or in reverse
+    /// declaration order in priority chain mode.
+    pub steps: Vec<SelectionStep<'t>>,
+    /// The separately detected traits if bitset mode is enabled, replacing the enum variants.
+    pub bitset: Option<Bitset<'t>>,
+    /// The concrete types and their detected traits in types mode, replacing the enum variants.
+    pub types: Option<Types<'t>>,
+    /// The single trait detected at compile time in auto mode, replacing the enum variants.
+    pub monomorphic: Option<Monomorphic<'t>>,
+    /// The wrapper type the enum payloads are stored in, if set.
+    /// Payloads are constructed with `From` and accessed through `Deref` (and `DerefMut` for mutable
+    /// traits).
+    pub wrapper: Option<&'t Path>,
+    /// The strict into macro identifiers, if strict mode is enabled.
+    pub strict: Option<Strict>,
+    /// The owned enum identifiers, if owned mode is enabled.
+    pub owned: Option<Owned>,
+    /// The name of the type-erased reference type, e.g. `TypeDynRef`, if `unsafe_erased` is
+    /// enabled.
+    pub erased: Option<Ident>,
+    /// The name of the shared view enum, e.g. `SharedType`, if `shared_view` is enabled.
+    pub shared: Option<Ident>,
+    /// The collection type and its push macro, if `collection` is enabled.
+    pub collection: Option<Collection>,
+    /// The name of the struct counting the recorded values per variant, e.g. `TypeCounter`, if
+    /// `counter` is enabled.
+    pub counter: Option<Ident>,
+    /// The extension trait, if any type is listed in the `extension` option.
+    pub extension: Option<Extension<'t>>,
+    /// The primary trait, if one is declared with the `primary` option.
+    pub primary: Option<Primary<'t>>,
+    /// The fallible accessor of the listed `std::error::Error` trait the `Debug`, `Display` and
+    /// `Error` implementations delegate to, e.g. `try_as_error`, if `error_impl` is enabled.
+    pub error_impl: Option<Ident>,
+    /// Whether to implement `PartialEq`, `Eq` and `Hash` by the variant, if `eq_by_variant` is
+    /// enabled.
+    pub eq_by_variant: bool,
+    /// The accessors used by the `write_to` function, if `Debug` or `Display` is listed.
+    pub write_to: Option<Formatting>,
+    /// The accessor trait, if `access_trait` is enabled.
+    pub access: Option<Access<'t>>,
+    /// The accessors passing the trait object to a closure, one per trait if `with_accessors` is
+    /// enabled.
+    pub with_accessors: Vec<WithAccessor<'t>>,
+    /// The names of all listed traits, in the sorted trait order, returned by `traits()`.
+    pub trait_names: Vec<String>,
+    /// The name of the struct wrapping the private enum, if `opaque` is enabled.
+    pub opaque: Option<&'t Ident>,
+    /// The name of the `#[cfg(test)]` module checking the variant selection, e.g.
+    /// `__type_self_test`, if `self_test` is enabled.
+    pub self_test: Option<Ident>,
+    /// The module wrapping the generated items, if `reexport_at` is set.
+    pub reexport: Option<Reexport<'t>>,
     /// The identifier for the wrap function.
     pub wrap_ident: &'t Ident,
-    /// The number of dereference operations needed for the wrap macro.
-    pub wrap_derefs: usize,
+    /// The generic parameter standing for the converted value in the generated implementations,
+    /// `__T` unless set by `value_generic`, so it doesn't shadow a type named in a trait path.
+    pub value_generic: Ident,
+    /// Whether the tag structs derive `Debug`, set by `show_internals`.
+    pub show_internals: bool,
+    /// Whether the enum has a variant per trait, selected in declaration order, set by
+    /// `mode = priority_chain`.
+    pub priority_chain: bool,
+    /// Whether a trait is bound by the enum lifetime `'t`, e.g. `Named<'_>`, in which case the
+    /// trait aggregates and the conversion functions of the tags declare it.
+    pub enum_lifetime: bool,
+    /// Whether the into macro borrows the value mutably, set if any trait is mutable.
+    /// All variants are then created from that single mutable borrow, including the shared ones.
+    pub borrow_mut: bool,
     /// The identifier for the inner into macro.
     pub inner_into: Ident,
     /// The identifier for the into macro.
     pub into: Ident,
     /// The identifier for the into_tag function.
     pub into_tag: Ident,
+    /// Whether the generated macros are scoped to the module instead of exported at the crate root.
+    pub scoped_macros: bool,
+    /// The identifiers for the from_any_as macro.
+    pub from_any_as: MacroIdents,
+    /// The identifiers for the dispatch_array macro, if `dispatch_array` is enabled.
+    pub dispatch_array: Option<MacroIdents>,
+    /// The identifiers for the dispatch_eq macro, if `dispatch_eq` is enabled.
+    pub dispatch_eq: Option<MacroIdents>,
+    /// The identifiers for the dispatch_tuple macro, if `dispatch_tuple` is enabled.
+    pub dispatch_tuple: Option<MacroIdents>,
+    /// The identifiers for the into macro of an `Option`'s value, if `into_opt_result` is enabled.
+    pub into_opt: Option<MacroIdents>,
+    /// The identifiers for the into macro of a `Result`'s value, if `into_opt_result` is enabled.
+    pub into_result: Option<MacroIdents>,
+    /// The identifiers for the matches_variant macro, unless in bitset, monomorphic or opaque mode.
+    pub matches_variant: Option<MacroIdents>,
+    /// The identifiers for the variant_traits macro, if `variant_macro` is enabled.
+    pub variant_traits: Option<MacroIdents>,
+    /// The identifiers for the assert_dispatch macro, if test helpers are enabled.
+    pub assert_dispatch: Option<MacroIdents>,
 }
 
 /// Converts the given AST Model into its intermediate representation (IR).
@@ -119,41 +531,230 @@ pub fn lower<'t>(model: &'t Model<'t>) -> Ir<'t> {
     let trait_aggregates = generate_trait_aggregates(model);
     let r#enum = generate_enum(model);
     let enum_impl = generate_enum_impl(model);
+    let trait_ref = generate_trait_ref(model);
     let autoref_specializers = generate_autoref_specializers(model);
-
-    let into_tag = Ident::new(
-        &format!(
-            "into_{}_tag",
-            model.enum_ident.to_string().to_case(Case::Snake)
+    let bitset = model.options.bitset.then(|| Bitset {
+        traits: generate_bitset_traits(model),
+        vtables: Ident::new(
+            &format!("{}Vtables", ident_name(model.enum_ident)),
+            Span::call_site(),
+        ),
+    });
+    let monomorphic = model.monomorphic.then(|| {
+        let r#trait = generate_bitset_traits(model).remove(0);
+        let cast = Ident::new(
+            &format!(
+                "{}{}Cast",
+                ident_name(model.enum_ident),
+                ident_name(model.traits[0].ident)
+            ),
+            Span::call_site(),
+        );
+        Monomorphic { r#trait, cast }
+    });
+    let types = model.types.as_ref().map(|variants| Types {
+        variants,
+        traits: generate_bitset_traits(model),
+        copy: match variants.as_slice() {
+            [single] if model.options.copy_accessors => Some(single),
+            _ => None,
+        },
+    });
+    let strict = model.options.strict.then(|| Strict {
+        r#macro: macro_idents(model, "into_{}_strict"),
+        into: snake_ident(model, "into_{}_strict"),
+        r#trait: Ident::new(
+            &format!("{}ImplementsAnyTrait", ident_name(model.enum_ident)),
+            Span::call_site(),
         ),
-        Span::call_site(),
-    );
-    let into = Ident::new(
-        &format!("into_{}", model.enum_ident.to_string().to_case(Case::Snake)),
-        Span::call_site(),
-    );
-    let inner_into = Ident::new(
-        &format!(
-            "__into_{}",
-            model.enum_ident.to_string().to_case(Case::Snake)
+    });
+    let owned = model.options.owned.then(|| Owned {
+        name: Ident::new(
+            &format!("{}Owned", ident_name(model.enum_ident)),
+            Span::call_site(),
         ),
-        Span::call_site(),
-    );
+        into: snake_ident(model, "into_{}_owned"),
+        inner_into: snake_ident(model, "__into_{}_owned"),
+        set: macro_idents(model, "set_{}_owned"),
+    });
+
+    let erased = model.options.unsafe_erased.then(|| {
+        Ident::new(
+            &format!("{}DynRef", ident_name(model.enum_ident)),
+            Span::call_site(),
+        )
+    });
+
+    let shared = model.options.shared_view.then(|| {
+        Ident::new(
+            &format!("Shared{}", ident_name(model.enum_ident)),
+            Span::call_site(),
+        )
+    });
+
+    let collection = model.options.collection.then(|| Collection {
+        name: Ident::new(
+            &format!("{}Vec", ident_name(model.enum_ident)),
+            Span::call_site(),
+        ),
+        push: macro_idents(model, "push_{}"),
+    });
+
+    let counter = model.options.counter.then(|| {
+        Ident::new(
+            &format!("{}Counter", ident_name(model.enum_ident)),
+            Span::call_site(),
+        )
+    });
+
+    let extension = (!model.options.extension.is_empty()).then(|| Extension {
+        name: Ident::new(
+            &format!("Into{}", ident_name(model.enum_ident)),
+            Span::call_site(),
+        ),
+        method: snake_ident(model, "as_{}"),
+        types: model.options.extension.iter().collect(),
+    });
+
+    let into_tag = snake_ident(model, "into_{}_tag");
+    let into = snake_ident(model, "into_{}");
+    let inner_into = snake_ident(model, "__into_{}");
+    let from_any_as = macro_idents(model, "from_any_as_{}");
+    let dispatch_array = model
+        .options
+        .dispatch_array
+        .then(|| macro_idents(model, "dispatch_array_{}"));
+    let dispatch_eq = model
+        .options
+        .dispatch_eq
+        .then(|| macro_idents(model, "dispatch_eq_{}"));
+    let dispatch_tuple = model
+        .options
+        .dispatch_tuple
+        .then(|| macro_idents(model, "dispatch_tuple_{}"));
+    let into_opt = model
+        .options
+        .into_opt_result
+        .then(|| macro_idents(model, "into_{}_opt"));
+    let into_result = model
+        .options
+        .into_opt_result
+        .then(|| macro_idents(model, "into_{}_result"));
+    // The variants of the opaque enum can't be named outside of the defining module.
+    let matches_variant = (!model.options.bitset && !model.monomorphic && !model.options.opaque)
+        .then(|| macro_idents(model, "matches_variant_{}"));
+    let variant_traits = model
+        .options
+        .variant_macro
+        .then(|| macro_idents(model, "{}_variant_traits"));
+    let assert_dispatch = model
+        .options
+        .test_helpers
+        .then(|| macro_idents(model, "assert_dispatch_{}"));
 
     Ir {
         trait_aggregates,
         r#enum,
         enum_impl,
+        trait_ref,
         autoref_specializers,
+        steps: generate_selection_steps(model),
+        bitset,
+        types,
+        monomorphic,
+        wrapper: model.options.wrapper.as_ref(),
+        strict,
+        owned,
+        erased,
+        shared,
+        collection,
+        counter,
+        extension,
+        primary: model.primary.as_ref().map(|t| Primary {
+            accessor: Ident::new(&format!("try_{}", accessor_name(t)), Span::call_site()),
+            path: t.path,
+            bounds: t.bounds,
+        }),
+        error_impl: model
+            .error
+            .as_ref()
+            .map(|t| Ident::new(&format!("try_{}", accessor_name(t)), Span::call_site())),
+        eq_by_variant: model.options.eq_by_variant,
+        trait_names: model.traits.iter().map(|t| ident_name(t.ident)).collect(),
+        write_to: Some(formatting(model))
+            .filter(|formatting| formatting.debug.is_some() || formatting.display.is_some()),
+        access: model.options.access_trait.then(|| generate_access(model)),
+        with_accessors: if model.options.with_accessors {
+            generate_with_accessors(model)
+        } else {
+            vec![]
+        },
+        opaque: model.options.opaque.then_some(model.enum_ident),
+        self_test: model
+            .options
+            .self_test
+            .then(|| snake_ident(model, "__{}_self_test")),
+        reexport: model.options.reexport_at.as_ref().map(|vis| Reexport {
+            module: snake_ident(model, "__{}_module"),
+            vis,
+        }),
         wrap_ident: &model.wrap_ident,
-        wrap_derefs: model.traits.len() + 1,
+        value_generic: model
+            .options
+            .value_generic
+            .clone()
+            .unwrap_or_else(|| Ident::new("__T", Span::call_site())),
+        show_internals: model.options.show_internals,
+        priority_chain: model.options.mode == Mode::PriorityChain,
+        enum_lifetime: model.traits.iter().any(|t| uses_enum_lifetime(t.path)),
+        borrow_mut: model.traits.iter().any(|t| t.mutable),
         inner_into,
         into,
         into_tag,
+        scoped_macros: model.options.scoped_macros,
+        from_any_as,
+        dispatch_array,
+        dispatch_eq,
+        dispatch_tuple,
+        into_opt,
+        into_result,
+        matches_variant,
+        variant_traits,
+        assert_dispatch,
+    }
+}
+
+/// Creates an identifier by substituting the snake case enum name into a template.
+///
+/// # Arguments
+/// * `model` - The analyzed Model containing the enum identifier
+/// * `template` - The identifier template, where `{}` is replaced by the enum name, e.g. `into_{}`
+///
+/// # Returns
+/// The resulting identifier, e.g. `into_my_mux`
+fn snake_ident(model: &Model, template: &str) -> Ident {
+    let snake = ident_name(model.enum_ident).to_case(Case::Snake);
+
+    Ident::new(&template.replace("{}", &snake), Span::call_site())
+}
+
+/// Creates the identifiers of a generated macro from a template.
+///
+/// # Arguments
+/// * `model` - The analyzed Model containing the enum identifier
+/// * `template` - The public macro name template, where `{}` is replaced by the enum name
+///
+/// # Returns
+/// The MacroIdents, where the inner name is the public name prefixed by `__`
+fn macro_idents(model: &Model, template: &str) -> MacroIdents {
+    MacroIdents {
+        inner: snake_ident(model, &format!("__{}", template)),
+        public: snake_ident(model, template),
     }
 }
 
-/// Generates trait aggregates for enum variants that implement multiple traits.
+/// Generates trait aggregates for enum variants that implement multiple traits, preceded by the
+/// traits generated for parenthesized combinations of traits.
 /// These aggregates will be used to create compound trait bounds for the enum variants.
 ///
 /// # Arguments
@@ -162,10 +763,14 @@ pub fn lower<'t>(model: &'t Model<'t>) -> Ir<'t> {
 /// # Returns
 /// A vector of TraitAggregate structures
 fn generate_trait_aggregates<'t>(model: &'t Model<'t>) -> Vec<TraitAggregate<'t>> {
-    model
-        .enum_variants
-        .iter()
-        .filter_map(|v| {
+    // The traits generated for combinations of traits are aggregates of their own.
+    let combined = model.combined.iter().map(|c| TraitAggregate {
+        name: c.name,
+        traits: c.traits.iter().collect(),
+    });
+
+    combined
+        .chain(model.enum_variants.iter().filter_map(|v| {
             if v.implemented_traits.len() <= 1 {
                 return None;
             }
@@ -177,7 +782,7 @@ fn generate_trait_aggregates<'t>(model: &'t Model<'t>) -> Vec<TraitAggregate<'t>
                 name: variant_ident,
                 traits: sub_traits,
             })
-        })
+        }))
         .collect()
 }
 
@@ -192,7 +797,7 @@ fn generate_trait_aggregates<'t>(model: &'t Model<'t>) -> Vec<TraitAggregate<'t>
 fn enum_variant_to_constraint<'t>(v: &'t analyze::EnumVariant<'t>) -> Constraint<'t> {
     match v.implemented_traits.len() {
         0 => Constraint::None,
-        1 => Constraint::Path(v.implemented_traits[0].path),
+        1 => Constraint::Path(v.implemented_traits[0].path, v.implemented_traits[0].bounds),
         _ => Constraint::Ident(&v.ident),
     }
 }
@@ -206,7 +811,7 @@ fn enum_variant_to_constraint<'t>(v: &'t analyze::EnumVariant<'t>) -> Constraint
 /// # Returns
 /// An Enum structure representing the main enum to be generated
 fn generate_enum<'t>(model: &'t Model<'t>) -> Enum<'t> {
-    let name = model.enum_ident;
+    let name = model.inner_ident.as_ref().unwrap_or(model.enum_ident);
     let variants = model
         .enum_variants
         .iter()
@@ -216,11 +821,29 @@ fn generate_enum<'t>(model: &'t Model<'t>) -> Enum<'t> {
             EnumVariant {
                 ident: &v.ident,
                 constraint,
+                mutable: v.implemented_traits.iter().any(|t| t.mutable),
+                traits: v.implemented_traits.iter().map(|t| t.ident).collect(),
+                mask: model
+                    .traits
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, t)| {
+                        v.implemented_traits
+                            .iter()
+                            .any(|implemented| core::ptr::eq(implemented.path, t.path))
+                    })
+                    .fold(0, |mask, (i, _)| mask | 1 << i),
             }
         })
         .collect();
 
-    Enum { name, variants }
+    Enum {
+        name,
+        variants,
+        repr: model.options.repr.as_ref(),
+        attrs: &model.options.enum_attrs,
+        traits: model.traits.iter().collect(),
+    }
 }
 
 /// Generates functions for each trait, mapping them to the enum variants
@@ -236,36 +859,334 @@ fn generate_enum_impl<'t>(model: &'t Model<'t>) -> EnumImpl<'t> {
     let functions = model
         .traits
         .iter()
-        .map(|current_trait| {
-            let fn_name = format!(
-                "try_as_{}",
-                current_trait.ident.to_string().to_case(Case::Snake)
-            );
-
-            // Find all enum variants that implement the current trait.
-            let matching_variants = model
-                .enum_variants
+        .map(|current_trait| generate_function(model, current_trait, ""))
+        .collect();
+
+    let mut_functions = model
+        .traits
+        .iter()
+        .filter(|t| t.mutable)
+        .map(|current_trait| generate_function(model, current_trait, "_mut"))
+        .collect();
+
+    let bits = model
+        .traits
+        .iter()
+        .map(|t| {
+            let name = ident_name(t.ident).to_case(Case::UpperSnake);
+            Ident::new(&format!("{}_BIT", name), Span::call_site())
+        })
+        .collect();
+
+    let variants_table = model.options.expose_table.then(|| {
+        model
+            .enum_variants
+            .iter()
+            .map(|v| VariantsTableRow {
+                variant: &v.ident,
+                traits: v
+                    .implemented_traits
+                    .iter()
+                    .map(|t| ident_name(t.ident))
+                    .collect(),
+            })
+            .collect()
+    });
+
+    let common = model
+        .options
+        .common
+        .as_ref()
+        .map(|common| generate_common_function(model, common));
+
+    let debug_json = model.options.debug_json.then(|| formatting(model));
+
+    EnumImpl {
+        functions,
+        mut_functions,
+        common,
+        marker: model.options.marker_super.as_ref(),
+        bits,
+        variants_table,
+        lifetime_accessors: model.options.lifetime_accessors,
+        static_accessors: model.options.static_accessors,
+        named_accessors: model.options.named_accessors,
+        compat: model.options.compat.as_ref(),
+        unchecked_accessors: model.options.unchecked_accessors,
+        debug_json,
+    }
+}
+
+/// Generates a single accessor function for a trait, matching all enum variants that implement
+/// it.
+///
+/// # Arguments
+/// * `model` - The analyzed Model containing traits and enum variants
+/// * `current_trait` - The trait the function gives access to
+/// * `suffix` - Appended to the function name, e.g. `_mut`
+///
+/// # Returns
+/// A Function named `try_as_trait_name{suffix}`, with a panicking variant named
+/// `as_trait_name{suffix}`
+fn generate_function<'t>(
+    model: &'t Model<'t>,
+    current_trait: &'t Trait<'t>,
+    suffix: &str,
+) -> Function<'t> {
+    let trait_name = ident_name(current_trait.ident);
+    let snake_name = format!("{}{}", trait_name.to_case(Case::Snake), suffix);
+    let panicking_name = format!("{}{}", accessor_name(current_trait), suffix);
+    let fn_name = format!("try_{}", panicking_name);
+
+    // Find all enum variants that implement the current trait.
+    let matching_variants = model
+        .enum_variants
+        .iter()
+        .filter(|v| {
+            v.implemented_traits
                 .iter()
-                .filter(|v| {
-                    v.implemented_traits.iter().any(|implemented_trait| {
-                        core::ptr::eq(implemented_trait.path, current_trait.path)
-                    })
-                })
-                .map(|p| &p.ident)
-                .collect();
+                .any(|implemented_trait| core::ptr::eq(implemented_trait.path, current_trait.path))
+        })
+        .map(|p| &p.ident)
+        .collect();
 
-            Function {
-                name: Ident::new(&fn_name, Span::call_site()),
-                result_path: current_trait.path,
-                matching_variants,
-            }
+    Function {
+        name: Ident::new(&fn_name, Span::call_site()),
+        panicking_name: Ident::new(&panicking_name, Span::call_site()),
+        lifetime_name: Ident::new(&format!("{}_for", fn_name), Span::call_site()),
+        static_name: Ident::new(&format!("{}_static", fn_name), Span::call_site()),
+        named_name: Ident::new(&format!("{}_named", fn_name), Span::call_site()),
+        modify_name: Ident::new(
+            &format!(
+                "modify_{}",
+                accessor_name(current_trait).trim_start_matches("as_")
+            ),
+            Span::call_site(),
+        ),
+        unchecked_name: Ident::new(&format!("{}_unchecked", panicking_name), Span::call_site()),
+        objects_name: Ident::new(&format!("{}_objects", snake_name), Span::call_site()),
+        count_name: Ident::new(
+            &format!("{}_VARIANT_COUNT", snake_name.to_case(Case::UpperSnake)),
+            Span::call_site(),
+        ),
+        trait_name,
+        result_path: current_trait.path,
+        result_bounds: current_trait.bounds,
+        docs: current_trait.docs,
+        matching_variants,
+    }
+}
+
+/// Generates the accessor function for the trait declared with the `common` option, matching all
+/// enum variants with traits.
+///
+/// # Arguments
+/// * `model` - The analyzed Model containing the enum variants
+/// * `common` - The path of the common trait
+///
+/// # Returns
+/// A Function named `try_as_trait_name`, with a panicking variant named `as_trait_name`
+fn generate_common_function<'t>(model: &'t Model<'t>, common: &'t Path) -> Function<'t> {
+    // Unwrap safety: syn doesn't parse paths without segments.
+    let trait_name = ident_name(&common.segments.last().unwrap().ident);
+    let snake_name = trait_name.to_case(Case::Snake);
+    let panicking_name = format!("as_{}", snake_name);
+    let fn_name = format!("try_{}", panicking_name);
+
+    let matching_variants = model
+        .enum_variants
+        .iter()
+        .filter(|v| !v.implemented_traits.is_empty())
+        .map(|v| &v.ident)
+        .collect();
+
+    Function {
+        name: Ident::new(&fn_name, Span::call_site()),
+        panicking_name: Ident::new(&panicking_name, Span::call_site()),
+        lifetime_name: Ident::new(&format!("{}_for", fn_name), Span::call_site()),
+        static_name: Ident::new(&format!("{}_static", fn_name), Span::call_site()),
+        named_name: Ident::new(&format!("{}_named", fn_name), Span::call_site()),
+        modify_name: Ident::new(&format!("modify_{}", snake_name), Span::call_site()),
+        unchecked_name: Ident::new(&format!("{}_unchecked", panicking_name), Span::call_site()),
+        objects_name: Ident::new(&format!("{}_objects", snake_name), Span::call_site()),
+        count_name: Ident::new(
+            &format!("{}_VARIANT_COUNT", snake_name.to_case(Case::UpperSnake)),
+            Span::call_site(),
+        ),
+        trait_name,
+        result_path: common,
+        result_bounds: &[],
+        docs: &[],
+        matching_variants,
+    }
+}
+
+/// Generates the accessor trait, mirroring the fallible accessor of every trait, and the mutable one
+/// of every mutable trait.
+///
+/// # Arguments
+/// * `model` - The analyzed Model containing the traits
+///
+/// # Returns
+/// The Access struct, holding the names of the trait and its sealing supertrait, and the accessors
+fn generate_access<'t>(model: &'t Model<'t>) -> Access<'t> {
+    let mut methods = vec![];
+
+    for t in &model.traits {
+        let name = format!("try_{}", accessor_name(t));
+        methods.push(AccessMethod {
+            name: Ident::new(&name, Span::call_site()),
+            path: t.path,
+            bounds: t.bounds,
+            docs: t.docs,
+            mutable: false,
+        });
+        if t.mutable {
+            methods.push(AccessMethod {
+                name: Ident::new(&format!("{}_mut", name), Span::call_site()),
+                path: t.path,
+                bounds: t.bounds,
+                docs: t.docs,
+                mutable: true,
+            });
+        }
+    }
+
+    Access {
+        name: Ident::new(
+            &format!("{}Access", ident_name(model.enum_ident)),
+            Span::call_site(),
+        ),
+        sealed: snake_ident(model, "__{}_sealed"),
+        methods,
+    }
+}
+
+/// Generates the accessors passing the trait object of every trait to a closure.
+///
+/// # Arguments
+/// * `model` - The analyzed Model containing the traits
+///
+/// # Returns
+/// A vector of WithAccessor structs, in the sorted trait order
+fn generate_with_accessors<'t>(model: &'t Model<'t>) -> Vec<WithAccessor<'t>> {
+    model
+        .traits
+        .iter()
+        .map(|t| WithAccessor {
+            name: Ident::new(
+                &format!("with_{}", ident_name(t.ident).to_case(Case::Snake)),
+                Span::call_site(),
+            ),
+            accessor: Ident::new(&format!("try_{}", accessor_name(t)), Span::call_site()),
+            path: t.path,
+            bounds: t.bounds,
+            docs: t.docs,
+        })
+        .collect()
+}
+
+/// Finds the accessors of the listed `Debug` and `Display` traits.
+///
+/// # Arguments
+/// * `model` - The analyzed Model containing the traits
+///
+/// # Returns
+/// The Formatting struct, holding the accessors of the listed formatting traits
+fn formatting(model: &Model) -> Formatting {
+    Formatting {
+        debug: formatting_accessor(model, "Debug"),
+        display: formatting_accessor(model, "Display"),
+    }
+}
+
+/// Finds the accessor of a listed formatting trait, matched by the last segment of its path, so both
+/// `Debug` and `std::fmt::Debug` are found.
+///
+/// # Arguments
+/// * `model` - The analyzed Model containing the traits
+/// * `name` - The name of the formatting trait, e.g. `Debug`
+///
+/// # Returns
+/// The fallible accessor of the trait, e.g. `try_as_debug`, or None if it isn't listed
+fn formatting_accessor(model: &Model, name: &str) -> Option<Ident> {
+    model
+        .traits
+        .iter()
+        .find(|t| {
+            t.path
+                .segments
+                .last()
+                .is_some_and(|segment| segment.ident == name && segment.arguments.is_empty())
+        })
+        .map(|t| Ident::new(&format!("try_{}", accessor_name(t)), Span::call_site()))
+}
+
+/// Builds the base name of a trait's accessors, prefixed by `try_` for the fallible accessor.
+///
+/// # Arguments
+/// * `current_trait` - The trait the accessors give access to
+///
+/// # Returns
+/// The accessor name, e.g. `as_trait_name`
+fn accessor_name(current_trait: &Trait) -> String {
+    format!(
+        "as_{}",
+        ident_name(current_trait.ident).to_case(Case::Snake)
+    )
+}
+
+/// Generates the trait reference enum and the arms of the `map` function converting each enum
+/// variant into it.
+///
+/// # Arguments
+/// * `model` - The analyzed Model containing traits and enum variants
+///
+/// # Returns
+/// A TraitRef structure
+fn generate_trait_ref<'t>(model: &'t Model<'t>) -> TraitRef<'t> {
+    let map_arms = model
+        .enum_variants
+        .iter()
+        .map(|v| MapArm {
+            variant: &v.ident,
+            // Implemented traits are sorted, so the first one is the first in the sorted order.
+            r#trait: v.implemented_traits.first(),
+        })
+        .collect();
+
+    let iter_items = model
+        .traits
+        .iter()
+        .filter(|t| !t.mutable && !uses_enum_lifetime(t.path))
+        .map(|t| IterItem {
+            accessor: Ident::new(&format!("try_{}", accessor_name(t)), Span::call_site()),
+            variant: t.ident,
         })
         .collect();
 
-    EnumImpl { functions }
+    let enum_name = ident_name(model.enum_ident);
+    let (name, define) = match &model.options.trait_ref {
+        Some(trait_ref) => (trait_ref.path.clone(), !trait_ref.reused),
+        None => (
+            Ident::new(&format!("{}TraitRef", enum_name), Span::call_site()).into(),
+            true,
+        ),
+    };
+
+    TraitRef {
+        name,
+        define,
+        traits: model.traits.iter().collect(),
+        map_arms,
+        iter_name: Ident::new(&format!("{}Iter", enum_name), Span::call_site()),
+        iter_items,
+    }
 }
 
 /// Generates specializers for autoref specialization.
+/// The tag names are derived from the variant idents, which are prefixed with the enum name, so
+/// enums listing the same traits can be generated in the same module.
 ///
 /// # Arguments
 /// * `model` - The analyzed Model containing traits and enum variants
@@ -273,32 +1194,182 @@ fn generate_enum_impl<'t>(model: &'t Model<'t>) -> EnumImpl<'t> {
 /// # Returns
 /// A vector of AutorefSpecializer structures
 fn generate_autoref_specializers<'t>(model: &'t Model<'t>) -> Vec<AutorefSpecializer<'t>> {
-    model
+    let step_traits = step_traits(model);
+
+    let mut specializers = model
         .enum_variants
         .iter()
         .map(|v| {
             let tag = Ident::new(&format!("{}Tag", v.ident), Span::call_site());
-            let r#match = Ident::new(&format!("{}Match", v.ident), Span::call_site());
-            let deref_count = v.implemented_traits.len();
+            let mut implemented = step_traits
+                .iter()
+                .map(|t| {
+                    v.implemented_traits
+                        .iter()
+                        .any(|implemented| core::ptr::eq(implemented.path, t.path))
+                })
+                .collect::<Vec<_>>();
+            // The traits listed after the trait of the variant come first, and may be implemented
+            // or not. The variant without traits requires all of them to be missing.
+            if model.options.mode == Mode::PriorityChain {
+                let first = implemented.iter().position(|i| *i).unwrap_or(0);
+                implemented.drain(..first);
+            }
             let constraint = enum_variant_to_constraint(v);
 
             AutorefSpecializer {
                 tag,
-                r#match,
-                deref_count,
+                implemented,
+                fallbacks: vec![],
                 variant: &v.ident,
                 constraint,
+                mutable: v.implemented_traits.iter().any(|t| t.mutable),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    if model.options.mode != Mode::PriorityChain {
+        assign_fallbacks(&mut specializers);
+    }
+
+    specializers
+}
+
+/// Assigns the trait combinations without a variant, e.g. left out by an `implies` declaration,
+/// to the variant with the most traits among those whose traits they all include, like the deref
+/// chain formerly selecting the variant did. The traits of two such variants together form a
+/// variant as well, so that variant is unique. A combination without one, e.g. lacking the
+/// `universal` trait, keeps no tag, so converting a value with it fails to compile.
+///
+/// # Arguments
+/// * `specializers` - The specializers of every variant, with all the steps in `implemented`
+fn assign_fallbacks(specializers: &mut [AutorefSpecializer]) {
+    let Some(steps) = specializers.first().map(|s| s.implemented.len()) else {
+        return;
+    };
+    // Every combination has a variant unless some were left out. There are at most 32 traits, as
+    // the variants are looked up by a `u32` mask.
+    if specializers.len() as u64 == 1 << steps {
+        return;
+    }
+
+    let mask = |implemented: &[bool]| {
+        implemented
+            .iter()
+            .enumerate()
+            .filter(|(_, i)| **i)
+            .fold(0u32, |mask, (bit, _)| mask | 1 << bit)
+    };
+    let variants = specializers
+        .iter()
+        .map(|s| mask(&s.implemented))
+        .collect::<HashSet<_>>();
+
+    for combination in 0..(1u64 << steps) as u32 {
+        if variants.contains(&combination) {
+            continue;
+        }
+
+        let fallback = specializers
+            .iter()
+            .map(|s| mask(&s.implemented))
+            .enumerate()
+            .filter(|(_, variant)| variant & !combination == 0)
+            .max_by_key(|(_, variant)| variant.count_ones());
+        if let Some((index, _)) = fallback {
+            let state = (0..steps).map(|bit| combination & 1 << bit != 0).collect();
+            specializers[index].fallbacks.push(state);
+        }
+    }
+}
+
+/// Returns the traits detected by the selection steps, in the step order.
+/// The last step wraps the state of the others, so in priority chain mode the steps follow the
+/// reverse declaration order, taken from the variants, leaving the first listed trait outermost.
+///
+/// # Arguments
+/// * `model` - The analyzed Model containing the traits and enum variants
+///
+/// # Returns
+/// The traits in the sorted trait order, or in reverse declaration order in priority chain mode
+fn step_traits<'t>(model: &'t Model<'t>) -> Vec<&'t Trait<'t>> {
+    match model.options.mode {
+        Mode::Combinations | Mode::Auto => model.traits.iter().collect(),
+        Mode::PriorityChain => model
+            .enum_variants
+            .iter()
+            .rev()
+            .filter_map(|v| v.implemented_traits.first())
+            .collect(),
+    }
+}
+
+/// Generates the steps selecting the variant, detecting each trait separately.
+///
+/// # Arguments
+/// * `model` - The analyzed Model containing the traits
+///
+/// # Returns
+/// A vector of SelectionStep structures, in the order of `step_traits`
+fn generate_selection_steps<'t>(model: &'t Model<'t>) -> Vec<SelectionStep<'t>> {
+    step_traits(model)
+        .into_iter()
+        .map(|t| {
+            let name = format!("{}{}", ident_name(model.enum_ident), ident_name(t.ident));
+            let snake = ident_name(t.ident).to_case(Case::Snake);
+
+            SelectionStep {
+                path: t.path,
+                bounds: t.bounds,
+                step: Ident::new(&format!("{}Step", name), Span::call_site()),
+                none_step: Ident::new(&format!("{}NoneStep", name), Span::call_site()),
+                r#match: Ident::new(&format!("{}Match", name), Span::call_site()),
+                none_match: Ident::new(&format!("{}NoneMatch", name), Span::call_site()),
+                into_step: snake_ident(model, &format!("into_{{}}_{}_step", snake)),
+            }
+        })
+        .collect()
+}
+
+/// Generates the separately detected traits for the bitset, types and monomorphic modes.
+///
+/// # Arguments
+/// * `model` - The analyzed Model containing the traits
+///
+/// # Returns
+/// A vector of BitsetTrait structures, in the sorted trait order
+fn generate_bitset_traits<'t>(model: &'t Model<'t>) -> Vec<BitsetTrait<'t>> {
+    model
+        .traits
+        .iter()
+        .map(|t| {
+            let name = format!("{}{}", ident_name(model.enum_ident), ident_name(t.ident));
+            let snake = ident_name(t.ident).to_case(Case::Snake);
+
+            BitsetTrait {
+                path: t.path,
+                accessor: Ident::new(&format!("try_as_{}", snake), Span::call_site()),
+                docs: t.docs,
+                tag: Ident::new(&format!("{}Tag", name), Span::call_site()),
+                none_tag: Ident::new(&format!("{}NoneTag", name), Span::call_site()),
+                r#match: Ident::new(&format!("{}Match", name), Span::call_site()),
+                none_match: Ident::new(&format!("{}NoneMatch", name), Span::call_site()),
+                into_tag: snake_ident(model, &format!("into_{{}}_{}_tag", snake)),
+                erase: Ident::new(&format!("{}Erase", name), Span::call_site()),
             }
         })
         .collect()
 }
 
 #[cfg(test)]
+#[allow(clippy::cmp_owned)]
 mod tests {
     use std::collections::HashMap;
 
     use super::analyze::EnumVariant as AnalyzedEnumVariant;
     use super::*;
+    use crate::options::{Options, TraitRefName};
+    use quote::ToTokens;
     use syn::parse_quote;
 
     fn create_idents() -> HashMap<&'static str, (Ident, Path)> {
@@ -330,22 +1401,32 @@ mod tests {
     }
 
     fn create_test_model<'t>(
+        options: &'t Options,
         enum_ident: &'t Ident,
         map: &'t HashMap<&'static str, (Ident, Path)>,
     ) -> Model<'t> {
         let debug_trait = Trait {
             ident: &map["Debug"].0,
             path: &map["Debug"].1,
+            bounds: &[],
+            mutable: false,
+            docs: &[],
         };
 
         let display_trait = Trait {
             ident: &map["Display"].0,
             path: &map["Display"].1,
+            bounds: &[],
+            mutable: false,
+            docs: &[],
         };
 
         let pointer_trait = Trait {
             ident: &map["Pointer"].0,
             path: &map["Pointer"].1,
+            bounds: &[],
+            mutable: true,
+            docs: &[],
         };
 
         let no_trait_variant = AnalyzedEnumVariant {
@@ -369,9 +1450,16 @@ mod tests {
         };
 
         Model {
+            options,
             enum_ident,
             wrap_ident: Ident::new("test_wrap", Span::call_site()),
+            inner_ident: None,
             traits: vec![debug_trait, display_trait, pointer_trait],
+            types: None,
+            primary: None,
+            error: None,
+            combined: vec![],
+            monomorphic: false,
             enum_variants: vec![
                 debug_variant,
                 debug_display_variant,
@@ -383,9 +1471,10 @@ mod tests {
 
     #[test]
     fn test_generate_trait_aggregates() {
+        let options = Options::default();
         let enum_ident = Ident::new("TestEnum", Span::call_site());
         let traits = create_idents();
-        let model = create_test_model(&enum_ident, &traits);
+        let model = create_test_model(&options, &enum_ident, &traits);
 
         let aggregates = generate_trait_aggregates(&model);
 
@@ -411,9 +1500,10 @@ mod tests {
 
     #[test]
     fn test_generate_enum() {
+        let options = Options::default();
         let enum_ident = Ident::new("TestEnum", Span::call_site());
         let traits = create_idents();
-        let model = create_test_model(&enum_ident, &traits);
+        let model = create_test_model(&options, &enum_ident, &traits);
 
         let enum_ir = generate_enum(&model);
 
@@ -427,7 +1517,7 @@ mod tests {
             .find(|v| v.ident.to_string() == "DebugOnly")
             .unwrap();
         match &debug_variant.constraint {
-            Constraint::Path(path) => {
+            Constraint::Path(path, _) => {
                 let path_str = quote::quote! { #path }.to_string();
                 assert!(path_str.contains("Debug"));
             }
@@ -455,13 +1545,22 @@ mod tests {
             }
             _ => panic!("Expected Ident constraint for DebugAndDisplay variant"),
         }
+
+        // Only variants with the mutable Pointer trait hold mutable references
+        for v in &enum_ir.variants {
+            assert_eq!(v.mutable, v.ident.to_string() == "AllTraits");
+        }
+
+        let masks: Vec<_> = enum_ir.variants.iter().map(|v| v.mask).collect();
+        assert_eq!(masks, [0b001, 0b011, 0b111, 0b000]);
     }
 
     #[test]
     fn test_generate_enum_impl() {
+        let options = Options::default();
         let enum_ident = Ident::new("TestEnum", Span::call_site());
         let traits = create_idents();
-        let model = create_test_model(&enum_ident, &traits);
+        let model = create_test_model(&options, &enum_ident, &traits);
 
         let enum_impl = generate_enum_impl(&model);
 
@@ -472,7 +1571,10 @@ mod tests {
             .iter()
             .find(|f| f.name == "try_as_debug")
             .unwrap();
+        assert_eq!(debug_fn.panicking_name.to_string(), "as_debug");
+        assert_eq!(debug_fn.trait_name, "Debug");
         assert_eq!(debug_fn.matching_variants.len(), 3); // DebugOnly, DebugAndDisplay, AllTraits
+        assert_eq!(debug_fn.count_name.to_string(), "DEBUG_VARIANT_COUNT");
 
         let display_fn = enum_impl
             .functions
@@ -487,13 +1589,24 @@ mod tests {
             .find(|f| f.name == "try_as_pointer")
             .unwrap();
         assert_eq!(serialize_fn.matching_variants.len(), 1); // AllTraits
+
+        assert!(enum_impl.variants_table.is_none());
+        assert!(enum_impl.common.is_none());
+
+        let bits: Vec<_> = enum_impl.bits.iter().map(|b| b.to_string()).collect();
+        assert_eq!(bits, ["DEBUG_BIT", "DISPLAY_BIT", "POINTER_BIT"]);
+
+        assert_eq!(enum_impl.mut_functions.len(), 1); // Only Pointer is mutable
+        assert_eq!(enum_impl.mut_functions[0].name, "try_as_pointer_mut");
+        assert_eq!(enum_impl.mut_functions[0].matching_variants.len(), 1); // AllTraits
     }
 
     #[test]
     fn test_generate_autoref_specializers() {
+        let options = Options::default();
         let enum_ident = Ident::new("TestEnum", Span::call_site());
         let traits = create_idents();
-        let model = create_test_model(&enum_ident, &traits);
+        let model = create_test_model(&options, &enum_ident, &traits);
 
         let specializers = generate_autoref_specializers(&model);
 
@@ -503,22 +1616,196 @@ mod tests {
             .iter()
             .find(|s| s.variant.to_string() == "DebugOnly")
             .unwrap();
-        assert_eq!(debug_only_specializer.deref_count, 1);
+        assert_eq!(debug_only_specializer.implemented, [true, false, false]);
         assert_eq!(debug_only_specializer.tag.to_string(), "DebugOnlyTag");
-        assert_eq!(debug_only_specializer.r#match.to_string(), "DebugOnlyMatch");
+        // The model has no variant with `Pointer` but without `Display` or `Debug`.
+        assert_eq!(debug_only_specializer.fallbacks, [[true, false, true]]);
 
         let all_traits_specializer = specializers
             .iter()
             .find(|s| s.variant.to_string() == "AllTraits")
             .unwrap();
-        assert_eq!(all_traits_specializer.deref_count, 3);
+        assert_eq!(all_traits_specializer.implemented, [true, true, true]);
+        assert!(all_traits_specializer.fallbacks.is_empty());
+
+        let no_traits_specializer = specializers
+            .iter()
+            .find(|s| s.variant.to_string() == "NoTraits")
+            .unwrap();
+        assert_eq!(
+            no_traits_specializer.fallbacks,
+            [
+                [false, true, false],
+                [false, false, true],
+                [false, true, true]
+            ]
+        );
+    }
+
+    #[test]
+    fn test_generate_selection_steps() {
+        let options = Options::default();
+        let enum_ident = Ident::new("TestEnum", Span::call_site());
+        let traits = create_idents();
+        let model = create_test_model(&options, &enum_ident, &traits);
+
+        let steps = generate_selection_steps(&model);
+
+        assert_eq!(steps.len(), 3); // One for each trait
+        assert_eq!(steps[0].step.to_string(), "TestEnumDebugStep");
+        assert_eq!(steps[0].none_step.to_string(), "TestEnumDebugNoneStep");
+        assert_eq!(steps[0].r#match.to_string(), "TestEnumDebugMatch");
+        assert_eq!(steps[0].none_match.to_string(), "TestEnumDebugNoneMatch");
+        assert_eq!(steps[0].into_step.to_string(), "into_test_enum_debug_step");
+        assert_eq!(
+            steps[2].into_step.to_string(),
+            "into_test_enum_pointer_step"
+        );
+    }
+
+    #[test]
+    fn test_generate_trait_ref() {
+        let options = Options::default();
+        let enum_ident = Ident::new("TestEnum", Span::call_site());
+        let traits = create_idents();
+        let model = create_test_model(&options, &enum_ident, &traits);
+
+        let trait_ref = generate_trait_ref(&model);
+
+        assert!(trait_ref.name.is_ident("TestEnumTraitRef"));
+        assert!(trait_ref.define);
+        assert_eq!(trait_ref.traits.len(), 3);
+        assert_eq!(trait_ref.map_arms.len(), 4);
+
+        // The first trait in the sorted order is passed.
+        for arm in &trait_ref.map_arms {
+            let r#trait = arm.r#trait.map(|t| t.ident.to_string());
+            match arm.variant.to_string().as_str() {
+                "NoTraits" => assert_eq!(r#trait, None),
+                _ => assert_eq!(r#trait.as_deref(), Some("Debug")),
+            }
+        }
+
+        // Pointer is mutable, so it can't be iterated over.
+        assert_eq!(trait_ref.iter_name.to_string(), "TestEnumIter");
+        let accessors: Vec<_> = trait_ref
+            .iter_items
+            .iter()
+            .map(|i| i.accessor.to_string())
+            .collect();
+        assert_eq!(accessors, ["try_as_debug", "try_as_display"]);
+
+        // A reused enum keeps its path and isn't defined again.
+        let options = Options {
+            trait_ref: Some(TraitRefName {
+                path: parse_quote!(refs::SharedTraitRef),
+                reused: true,
+            }),
+            ..Default::default()
+        };
+        let model = create_test_model(&options, &enum_ident, &traits);
+        let trait_ref = generate_trait_ref(&model);
+        assert_eq!(trait_ref.name.segments.len(), 2);
+        assert!(!trait_ref.define);
+    }
+
+    #[test]
+    fn test_generate_enum_impl_variants_table() {
+        let options = Options {
+            expose_table: true,
+            ..Default::default()
+        };
+        let enum_ident = Ident::new("TestEnum", Span::call_site());
+        let traits = create_idents();
+        let model = create_test_model(&options, &enum_ident, &traits);
+
+        let table = generate_enum_impl(&model).variants_table.unwrap();
+
+        assert_eq!(table.len(), 4);
+        for row in &table {
+            let expected: &[&str] = match row.variant.to_string().as_str() {
+                "DebugOnly" => &["Debug"],
+                "DebugAndDisplay" => &["Debug", "Display"],
+                "AllTraits" => &["Debug", "Display", "Pointer"],
+                _ => &[],
+            };
+            assert_eq!(row.traits, expected);
+        }
+    }
+
+    #[test]
+    fn test_generate_enum_impl_common() {
+        let options = Options {
+            common: Some(parse_quote!(base::CommonBase)),
+            ..Default::default()
+        };
+        let enum_ident = Ident::new("TestEnum", Span::call_site());
+        let traits = create_idents();
+        let model = create_test_model(&options, &enum_ident, &traits);
+
+        let common = generate_enum_impl(&model).common.unwrap();
+
+        assert_eq!(common.name.to_string(), "try_as_common_base");
+        assert_eq!(common.panicking_name.to_string(), "as_common_base");
+        // Every variant but NoTraits
+        assert_eq!(common.matching_variants.len(), 3);
+        assert!(common.matching_variants.iter().all(|v| *v != "NoTraits"));
+    }
+
+    #[test]
+    fn test_generate_access() {
+        let options = Options::default();
+        let enum_ident = Ident::new("TestEnum", Span::call_site());
+        let traits = create_idents();
+        let model = create_test_model(&options, &enum_ident, &traits);
+
+        let access = generate_access(&model);
+
+        assert_eq!(access.name.to_string(), "TestEnumAccess");
+        assert_eq!(access.sealed.to_string(), "__test_enum_sealed");
+        let methods: Vec<_> = access
+            .methods
+            .iter()
+            .map(|m| (m.name.to_string(), m.mutable))
+            .collect();
+        assert_eq!(
+            methods,
+            [
+                ("try_as_debug".to_string(), false),
+                ("try_as_display".to_string(), false),
+                ("try_as_pointer".to_string(), false),
+                ("try_as_pointer_mut".to_string(), true),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_generate_with_accessors() {
+        let options = Options::default();
+        let enum_ident = Ident::new("TestEnum", Span::call_site());
+        let traits = create_idents();
+        let model = create_test_model(&options, &enum_ident, &traits);
+
+        let accessors: Vec<_> = generate_with_accessors(&model)
+            .iter()
+            .map(|a| (a.name.to_string(), a.accessor.to_string()))
+            .collect();
+        assert_eq!(
+            accessors,
+            [
+                ("with_debug".to_string(), "try_as_debug".to_string()),
+                ("with_display".to_string(), "try_as_display".to_string()),
+                ("with_pointer".to_string(), "try_as_pointer".to_string()),
+            ]
+        );
     }
 
     #[test]
     fn test_lower() {
+        let options = Options::default();
         let enum_ident = Ident::new("TestEnum", Span::call_site());
         let traits = create_idents();
-        let model = create_test_model(&enum_ident, &traits);
+        let model = create_test_model(&options, &enum_ident, &traits);
 
         let ir = lower(&model);
 
@@ -528,8 +1815,327 @@ mod tests {
         assert_eq!(ir.autoref_specializers.len(), 4);
 
         assert_eq!(ir.wrap_ident.to_string(), "test_wrap");
-        assert_eq!(ir.wrap_derefs, 4); // traits.len() + 1
+        assert_eq!(ir.value_generic.to_string(), "__T");
+        assert!(ir.borrow_mut); // Pointer is mutable
+        assert_eq!(ir.steps.len(), 3); // One per trait
         assert_eq!(ir.into.to_string(), "into_test_enum");
         assert_eq!(ir.into_tag.to_string(), "into_test_enum_tag");
+        assert_eq!(ir.from_any_as.public.to_string(), "from_any_as_test_enum");
+        assert_eq!(ir.from_any_as.inner.to_string(), "__from_any_as_test_enum");
+        assert!(ir.dispatch_array.is_none());
+        assert!(ir.dispatch_eq.is_none());
+        assert!(ir.dispatch_tuple.is_none());
+        assert!(ir.into_opt.is_none());
+        assert!(ir.into_result.is_none());
+        assert!(ir.assert_dispatch.is_none());
+        assert!(ir.bitset.is_none());
+        assert!(ir.types.is_none());
+        assert!(ir.owned.is_none());
+        assert!(ir.shared.is_none());
+        assert!(ir.variant_traits.is_none());
+        assert!(ir.counter.is_none());
+        assert!(ir.wrapper.is_none());
+        assert!(ir.strict.is_none());
+        assert_eq!(ir.trait_names, ["Debug", "Display", "Pointer"]);
+        let write_to = ir.write_to.unwrap();
+        assert_eq!(write_to.display.unwrap().to_string(), "try_as_display");
+        assert_eq!(write_to.debug.unwrap().to_string(), "try_as_debug");
+        assert!(ir.access.is_none());
+        assert!(ir.with_accessors.is_empty());
+        assert!(ir.reexport.is_none());
+        assert!(ir.extension.is_none());
+    }
+
+    #[test]
+    fn test_lower_strict() {
+        let options = Options {
+            strict: true,
+            ..Default::default()
+        };
+        let enum_ident = Ident::new("TestEnum", Span::call_site());
+        let traits = create_idents();
+        let model = create_test_model(&options, &enum_ident, &traits);
+
+        let strict = lower(&model).strict.unwrap();
+
+        assert_eq!(strict.r#macro.public.to_string(), "into_test_enum_strict");
+        assert_eq!(strict.r#macro.inner.to_string(), "__into_test_enum_strict");
+        assert_eq!(strict.into.to_string(), "into_test_enum_strict");
+        assert_eq!(strict.r#trait.to_string(), "TestEnumImplementsAnyTrait");
+    }
+
+    #[test]
+    fn test_lower_self_test() {
+        let enum_ident = Ident::new("TestEnum", Span::call_site());
+        let traits = create_idents();
+
+        let options = Options::default();
+        let model = create_test_model(&options, &enum_ident, &traits);
+        assert!(lower(&model).self_test.is_none());
+
+        let options = Options {
+            self_test: true,
+            ..Default::default()
+        };
+        let model = create_test_model(&options, &enum_ident, &traits);
+        let ir = lower(&model);
+        assert_eq!(ir.self_test.unwrap().to_string(), "__test_enum_self_test");
+    }
+
+    #[test]
+    fn test_lower_opaque() {
+        let options = Options {
+            opaque: true,
+            ..Default::default()
+        };
+        let enum_ident = Ident::new("TestEnum", Span::call_site());
+        let traits = create_idents();
+        let mut model = create_test_model(&options, &enum_ident, &traits);
+        model.inner_ident = Some(Ident::new("TestEnumInner", Span::call_site()));
+
+        let ir = lower(&model);
+
+        assert_eq!(ir.r#enum.name.to_string(), "TestEnumInner");
+        assert_eq!(ir.opaque.unwrap().to_string(), "TestEnum");
+        assert!(ir.matches_variant.is_none());
+    }
+
+    #[test]
+    fn test_lower_extension() {
+        let options = Options {
+            extension: vec![parse_quote!(String), parse_quote!(Vec<u8>)],
+            ..Default::default()
+        };
+        let enum_ident = Ident::new("TestEnum", Span::call_site());
+        let traits = create_idents();
+        let model = create_test_model(&options, &enum_ident, &traits);
+
+        let extension = lower(&model).extension.unwrap();
+
+        assert_eq!(extension.name.to_string(), "IntoTestEnum");
+        assert_eq!(extension.method.to_string(), "as_test_enum");
+        assert_eq!(extension.types.len(), 2);
+    }
+
+    #[test]
+    fn test_lower_debug_json() {
+        let options = Options {
+            debug_json: true,
+            ..Default::default()
+        };
+        let enum_ident = Ident::new("TestEnum", Span::call_site());
+        let traits = create_idents();
+        let model = create_test_model(&options, &enum_ident, &traits);
+
+        let debug_json = lower(&model).enum_impl.debug_json.unwrap();
+
+        // Both `std::fmt::Debug` and `fmt::Display` are found by their last segment.
+        assert_eq!(debug_json.debug.unwrap().to_string(), "try_as_debug");
+        assert_eq!(debug_json.display.unwrap().to_string(), "try_as_display");
+
+        let options = Options::default();
+        let model = create_test_model(&options, &enum_ident, &traits);
+        assert!(lower(&model).enum_impl.debug_json.is_none());
+    }
+
+    #[test]
+    fn test_lower_types() {
+        let options = Options::default();
+        let enum_ident = Ident::new("TestEnum", Span::call_site());
+        let traits = create_idents();
+        let ty: Type = parse_quote!(String);
+        let mut model = create_test_model(&options, &enum_ident, &traits);
+        model.types = Some(vec![ConcreteType {
+            ident: Ident::new("String", Span::call_site()),
+            ty: &ty,
+        }]);
+
+        let ir = lower(&model);
+        let types = ir.types.unwrap();
+
+        assert_eq!(types.variants.len(), 1);
+        assert_eq!(types.traits.len(), 3);
+        assert_eq!(types.traits[0].accessor.to_string(), "try_as_debug");
+        assert!(ir.bitset.is_none());
+    }
+
+    #[test]
+    fn test_lower_owned() {
+        let options = Options {
+            owned: true,
+            ..Default::default()
+        };
+        let enum_ident = Ident::new("TestEnum", Span::call_site());
+        let traits = create_idents();
+        let model = create_test_model(&options, &enum_ident, &traits);
+
+        let ir = lower(&model);
+
+        let owned = ir.owned.unwrap();
+        assert_eq!(owned.name.to_string(), "TestEnumOwned");
+        assert_eq!(owned.into.to_string(), "into_test_enum_owned");
+        assert_eq!(owned.inner_into.to_string(), "__into_test_enum_owned");
+    }
+
+    #[test]
+    fn test_lower_variant_macro() {
+        let options = Options {
+            variant_macro: true,
+            ..Default::default()
+        };
+        let enum_ident = Ident::new("TestEnum", Span::call_site());
+        let traits = create_idents();
+        let model = create_test_model(&options, &enum_ident, &traits);
+
+        let ir = lower(&model);
+        let variant_traits = ir.variant_traits.unwrap();
+        assert_eq!(
+            variant_traits.public.to_string(),
+            "test_enum_variant_traits"
+        );
+        assert_eq!(
+            variant_traits.inner.to_string(),
+            "__test_enum_variant_traits"
+        );
+    }
+
+    #[test]
+    fn test_lower_shared_view() {
+        let options = Options {
+            shared_view: true,
+            ..Default::default()
+        };
+        let enum_ident = Ident::new("TestEnum", Span::call_site());
+        let traits = create_idents();
+        let model = create_test_model(&options, &enum_ident, &traits);
+
+        let ir = lower(&model);
+        assert_eq!(ir.shared.unwrap().to_string(), "SharedTestEnum");
+    }
+
+    #[test]
+    fn test_generate_bitset_traits() {
+        let options = Options::default();
+        let enum_ident = Ident::new("TestEnum", Span::call_site());
+        let traits = create_idents();
+        let model = create_test_model(&options, &enum_ident, &traits);
+
+        let bitset = generate_bitset_traits(&model);
+
+        assert_eq!(bitset.len(), 3); // One for each trait
+
+        let debug = &bitset[0];
+        assert_eq!(debug.accessor.to_string(), "try_as_debug");
+        assert_eq!(debug.tag.to_string(), "TestEnumDebugTag");
+        assert_eq!(debug.none_tag.to_string(), "TestEnumDebugNoneTag");
+        assert_eq!(debug.r#match.to_string(), "TestEnumDebugMatch");
+        assert_eq!(debug.none_match.to_string(), "TestEnumDebugNoneMatch");
+        assert_eq!(debug.into_tag.to_string(), "into_test_enum_debug_tag");
+        assert_eq!(debug.erase.to_string(), "TestEnumDebugErase");
+    }
+
+    #[test]
+    fn test_lower_monomorphic() {
+        let options = Options::default();
+        let enum_ident = Ident::new("TestEnum", Span::call_site());
+        let traits = create_idents();
+        let mut model = create_test_model(&options, &enum_ident, &traits);
+        model.traits.truncate(1);
+        model.enum_variants.clear();
+        model.monomorphic = true;
+
+        let ir = lower(&model);
+
+        let monomorphic = ir.monomorphic.unwrap();
+        assert_eq!(monomorphic.r#trait.accessor.to_string(), "try_as_debug");
+        assert_eq!(monomorphic.r#trait.tag.to_string(), "TestEnumDebugTag");
+        assert_eq!(monomorphic.cast.to_string(), "TestEnumDebugCast");
+        assert!(ir.matches_variant.is_none());
+    }
+
+    #[test]
+    fn test_lower_dispatch_macros() {
+        let options = Options {
+            dispatch_array: true,
+            dispatch_eq: true,
+            dispatch_tuple: true,
+            into_opt_result: true,
+            ..Default::default()
+        };
+        let enum_ident = Ident::new("TestEnum", Span::call_site());
+        let traits = create_idents();
+        let model = create_test_model(&options, &enum_ident, &traits);
+
+        let ir = lower(&model);
+
+        let dispatch_array = ir.dispatch_array.unwrap();
+        assert_eq!(
+            dispatch_array.public.to_string(),
+            "dispatch_array_test_enum"
+        );
+        assert_eq!(
+            dispatch_array.inner.to_string(),
+            "__dispatch_array_test_enum"
+        );
+        let dispatch_eq = ir.dispatch_eq.unwrap();
+        assert_eq!(dispatch_eq.public.to_string(), "dispatch_eq_test_enum");
+        assert_eq!(dispatch_eq.inner.to_string(), "__dispatch_eq_test_enum");
+        let dispatch_tuple = ir.dispatch_tuple.unwrap();
+        assert_eq!(
+            dispatch_tuple.public.to_string(),
+            "dispatch_tuple_test_enum"
+        );
+        assert_eq!(
+            dispatch_tuple.inner.to_string(),
+            "__dispatch_tuple_test_enum"
+        );
+        assert_eq!(
+            ir.into_opt.unwrap().public.to_string(),
+            "into_test_enum_opt"
+        );
+        assert_eq!(
+            ir.into_result.unwrap().inner.to_string(),
+            "__into_test_enum_result"
+        );
+    }
+
+    #[test]
+    fn test_lower_test_helpers() {
+        let options = Options {
+            test_helpers: true,
+            ..Default::default()
+        };
+        let enum_ident = Ident::new("TestEnum", Span::call_site());
+        let traits = create_idents();
+        let model = create_test_model(&options, &enum_ident, &traits);
+
+        let ir = lower(&model);
+
+        let assert_dispatch = ir.assert_dispatch.unwrap();
+        assert_eq!(
+            assert_dispatch.public.to_string(),
+            "assert_dispatch_test_enum"
+        );
+        assert_eq!(
+            assert_dispatch.inner.to_string(),
+            "__assert_dispatch_test_enum"
+        );
+    }
+
+    #[test]
+    fn test_lower_reexport() {
+        let options = Options {
+            reexport_at: Some(parse_quote!(pub(in crate))),
+            ..Default::default()
+        };
+        let enum_ident = Ident::new("TestEnum", Span::call_site());
+        let traits = create_idents();
+        let model = create_test_model(&options, &enum_ident, &traits);
+
+        let ir = lower(&model);
+
+        let reexport = ir.reexport.unwrap();
+        assert_eq!(reexport.module.to_string(), "__test_enum_module");
+        assert_eq!(reexport.vis.to_token_stream().to_string(), "pub (in crate)");
     }
 }
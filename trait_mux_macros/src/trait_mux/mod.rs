@@ -1,4 +1,5 @@
 pub mod analyze;
 pub mod codegen;
 pub mod lower;
+pub mod options;
 pub mod parse;
@@ -0,0 +1,675 @@
+//! This module parses the optional `#[trait_mux(...)]` attributes that can precede the enum name,
+//! e.g. `#[trait_mux(implies(DisplayExt => Display))] SomeName{Display, DisplayExt}`.
+//! The parsed options are stored in the `Options` struct and consumed by the later stages.
+
+use syn::ext::IdentExt;
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::{Attribute, Error, Ident, LitStr, Path, Result, Token, Type, Visibility};
+
+/// All options that can be set through `#[trait_mux(...)]` attributes.
+#[derive(Default)]
+pub struct Options {
+    /// Declared implications between listed traits, e.g. `implies(DisplayExt => Display)`.
+    /// Aggregates, e.g. `aggregate(DebugDisplay => Debug, Display)`, add an implication per
+    /// implied trait.
+    pub implies: Vec<Implication>,
+    /// Whether to generate test helper macros, set by `test_helpers`.
+    pub test_helpers: bool,
+    /// Whether to detect each trait separately and store the results in a struct, instead of
+    /// generating an enum variant per trait combination, set by `bitset`.
+    pub bitset: bool,
+    /// Whether to generate an owned enum, holding boxed values instead of references, set by
+    /// `owned`.
+    pub owned: bool,
+    /// Whether to generate a `VARIANTS_TABLE` constant listing the traits of every variant, set by
+    /// `expose_table`.
+    pub expose_table: bool,
+    /// A wrapper type the enum payloads are stored in, e.g. `Guard<&'t dyn Trait>`, set by
+    /// `wrapper = Guard`.
+    pub wrapper: Option<Path>,
+    /// Whether to scope the generated macros to the module instead of exporting them at the crate
+    /// root, set by `scoped_macros`.
+    pub scoped_macros: bool,
+    /// A trait implemented by every variant with traits, typically a supertrait of all listed
+    /// traits, set by `common = CommonTrait`.
+    pub common: Option<Path>,
+    /// A trait implemented by every type, typically through a blanket implementation, which is
+    /// added to every variant so no variant without traits exists, set by `universal = Universal`.
+    pub universal: Option<Path>,
+    /// Whether to generate a strict into macro, failing to compile for values implementing none of
+    /// the traits, set by `strict`.
+    pub strict: bool,
+    /// The primitive representation of the enum, e.g. `u8`, set by `repr = u8`.
+    pub repr: Option<Ident>,
+    /// `#[cfg_attr(...)]` and `#[derive(...)]` attributes re-emitted unchanged on the generated
+    /// enum, e.g. `#[cfg_attr(feature = "serde", derive(Serialize))]` or `#[derive(Clone, Copy)]`.
+    pub enum_attrs: Vec<Attribute>,
+    /// Concrete types to implement the `IntoMyMux` extension trait for, set by
+    /// `extension(TypeA, TypeB)`.
+    pub extension: Vec<Type>,
+    /// Whether to generate `try_as_trait_name_for` accessors, returning trait objects bound by the
+    /// enum lifetime instead of the borrow of the enum, set by `lifetime_accessors`.
+    pub lifetime_accessors: bool,
+    /// Whether to generate unsafe `as_trait_name_unchecked` accessors, skipping the check that the
+    /// variant implements the trait, set by `unchecked_accessors`.
+    pub unchecked_accessors: bool,
+    /// Whether to generate a `to_debug_json` method, rendering the variant name and the `Debug` or
+    /// `Display` formatting of the value, set by `debug_json`.
+    pub debug_json: bool,
+    /// Whether to generate a `MyMuxDynRef` type, holding the trait object of a variant as a raw
+    /// pointer without a lifetime, set by `unsafe_erased`.
+    pub unsafe_erased: bool,
+    /// Whether listing the same trait twice is an error, instead of merging the entries, set by
+    /// `no_duplicates`.
+    pub no_duplicates: bool,
+    /// Whether to generate `try_as_trait_name_static` accessors for enums holding `'static` values,
+    /// returning `'static` bound trait objects, set by `static`.
+    pub static_accessors: bool,
+    /// Whether to generate a `MyMuxVec` collection and a `push_my_mux!` macro, set by `collection`.
+    pub collection: bool,
+    /// Whether to generate `try_as_trait_name_named` accessors, returning the variant name along
+    /// with the trait object, set by `named_accessors`.
+    pub named_accessors: bool,
+    /// A file the generated code is written to for inspection, relative to the crate root, set by
+    /// `dump_to = "target/generated.rs"`.
+    pub dump_to: Option<LitStr>,
+    /// Another `trait_mux` enum whose current traits can be compared with those of this one, set
+    /// by `compat(OtherMux)`.
+    pub compat: Option<Path>,
+    /// Whether to generate the enum as a private type wrapped by a `#[non_exhaustive]` struct, so
+    /// its variants can't be matched on, set by `opaque`.
+    pub opaque: bool,
+    /// Whether to generate a `#[cfg(test)]` module checking that values implementing each set of
+    /// traits are converted into the expected variant, set by `self_test`.
+    pub self_test: bool,
+    /// A listed trait accessed through `as_primary()`, a name independent of the trait's, set by
+    /// `primary = Debug`.
+    pub primary: Option<Path>,
+    /// Whether to derive `Debug` for the tag structs selecting the variant, so they can be
+    /// inspected when debugging the selection, set by `show_internals`.
+    pub show_internals: bool,
+    /// Whether to generate `try_as_trait_name_copy` accessors in types mode over a single `Copy`
+    /// type, returning the value itself instead of a trait object, set by `copy_accessors`.
+    pub copy_accessors: bool,
+    /// Whether to generate a sealed `MyMuxAccess` trait mirroring the fallible accessors, so generic
+    /// code can be bounded on it instead of naming the enum, set by `access_trait`.
+    pub access_trait: bool,
+    /// Whether to generate `with_trait_name` accessors, passing the trait object to a closure and
+    /// returning its result, set by `with_accessors`.
+    pub with_accessors: bool,
+    /// Whether to generate a `SharedMyMux` enum and an `as_shared()` method, viewing the mutable
+    /// trait objects of the enum as shared ones, set by `shared_view`.
+    pub shared_view: bool,
+    /// The name of the generic parameter standing for the converted value in the generated
+    /// implementations, e.g. `impl<__T: Debug>`, set by `value_generic = V`.
+    pub value_generic: Option<Ident>,
+    /// Whether to generate a `my_mux_variant_traits!(Variant)` macro, expanding to the traits of
+    /// a variant, set by `variant_macro`.
+    pub variant_macro: bool,
+    /// Whether to generate a `MyMuxCounter` struct counting the recorded values per variant, set
+    /// by `counter`.
+    pub counter: bool,
+    /// Whether to generate a `dispatch_array_my_mux!(array)` macro converting every element of an
+    /// array, set by `dispatch_array`.
+    pub dispatch_array: bool,
+    /// Whether to generate a `dispatch_eq_my_mux!(a, b)` macro checking whether two values are
+    /// converted into the same variant, set by `dispatch_eq`.
+    pub dispatch_eq: bool,
+    /// Whether to generate a `dispatch_tuple_my_mux!((a, b))` macro converting every element of a
+    /// tuple expression, set by `dispatch_tuple`.
+    pub dispatch_tuple: bool,
+    /// Whether to generate `into_my_mux_opt!(&option)` and `into_my_mux_result!(&result)` macros
+    /// converting the value inside an `Option` or a `Result`, set by `into_opt_result`.
+    pub into_opt_result: bool,
+    /// Whether to implement `Debug`, `Display` and `std::error::Error` for the enum, delegating to
+    /// the listed `std::error::Error` trait, set by `error_impl`.
+    pub error_impl: bool,
+    /// Whether to implement `PartialEq`, `Eq` and `Hash` for the enum by comparing and hashing the
+    /// variants only, set by `eq_by_variant`.
+    pub eq_by_variant: bool,
+    /// A marker trait every listed trait extends, viewed by the generated `as_marker()` method,
+    /// set by `marker_super = MyMarker`.
+    pub marker_super: Option<Path>,
+    /// How the variants are derived from the listed traits, set by `mode = priority_chain` or
+    /// `mode = auto`.
+    pub mode: Mode,
+    /// The visibility the generated items are re-exported with from the module wrapping them, set
+    /// by `reexport_at = crate` (`pub(in crate)`) or `reexport_at = pub`.
+    pub reexport_at: Option<Visibility>,
+    /// The name of the trait reference enum, set by `trait_ref = MyTraitRef`, or reused from
+    /// another `trait_mux` enum listing the same traits by `trait_ref = use MyTraitRef`.
+    pub trait_ref: Option<TraitRefName>,
+}
+
+/// How the variants of the enum are derived from the listed traits.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub enum Mode {
+    /// A variant per combination of traits, holding the value as all the traits it implements,
+    /// set by `mode = combinations`.
+    #[default]
+    Combinations,
+    /// A variant per trait, holding the value as the first listed trait it implements, plus the
+    /// variant without traits, set by `mode = priority_chain`.
+    PriorityChain,
+    /// A generic struct detecting the trait at compile time if a single trait is listed, with the
+    /// same accessor, and a variant per combination otherwise, set by `mode = auto`.
+    Auto,
+}
+
+/// The name of the trait reference enum, either defined by this enum or reused from another one.
+pub struct TraitRefName {
+    /// The name of the defined enum, or the path to the reused one.
+    pub path: Path,
+    /// Whether the enum is defined by another `trait_mux` enum, so it isn't generated again.
+    pub reused: bool,
+}
+
+/// A declared implication between two traits: any type implementing `from` also implements `to`.
+///
+/// Typically used when `from` has `to` as a supertrait, which the macro can't detect on its own.
+pub struct Implication {
+    /// The implying trait (e.g. `DisplayExt`).
+    pub from: Path,
+    /// The implied trait (e.g. `Display`).
+    pub to: Path,
+}
+
+impl Parse for Implication {
+    /// Parses a syntax like `DisplayExt => Display`.
+    fn parse(input: ParseStream) -> Result<Self> {
+        let from = input.parse()?;
+        input.parse::<Token![=>]>()?;
+        let to = input.parse()?;
+
+        Ok(Implication { from, to })
+    }
+}
+
+impl Options {
+    /// Builds the options from a list of attributes.
+    ///
+    /// # Arguments
+    /// * `attrs` - The outer attributes preceding the enum name.
+    ///
+    /// # Errors
+    /// Returns an error if an attribute isn't `#[trait_mux(...)]`, `#[cfg_attr(...)]` or
+    /// `#[derive(...)]`, or if it contains an unknown or malformed option.
+    pub fn from_attrs(attrs: &[Attribute]) -> Result<Self> {
+        let mut options = Options::default();
+
+        for attr in attrs {
+            if attr.path.is_ident("cfg_attr") || attr.path.is_ident("derive") {
+                options.enum_attrs.push(attr.clone());
+                continue;
+            }
+
+            if !attr.path.is_ident("trait_mux") {
+                return Err(Error::new_spanned(
+                    attr,
+                    "expected a `#[trait_mux(...)]` attribute",
+                ));
+            }
+
+            attr.parse_args_with(|input: ParseStream| options.parse_items(input))?;
+        }
+
+        Ok(options)
+    }
+
+    /// Parses a comma-separated list of options, e.g. `implies(A => B), ...`.
+    fn parse_items(&mut self, input: ParseStream) -> Result<()> {
+        while !input.is_empty() {
+            // Keywords are accepted as well, for the `static` option.
+            let key = input.call(Ident::parse_any)?;
+
+            match key.to_string().as_str() {
+                "implies" => {
+                    let content;
+                    syn::parenthesized!(content in input);
+                    let implications =
+                        Punctuated::<Implication, Token![,]>::parse_terminated(&content)?;
+                    self.implies.extend(implications);
+                }
+                "aggregate" => {
+                    let content;
+                    syn::parenthesized!(content in input);
+                    let from: Path = content.parse()?;
+                    content.parse::<Token![=>]>()?;
+                    let targets = Punctuated::<Path, Token![,]>::parse_terminated(&content)?;
+                    self.implies
+                        .extend(targets.into_iter().map(|to| Implication {
+                            from: from.clone(),
+                            to,
+                        }));
+                }
+                "compat" => {
+                    let content;
+                    syn::parenthesized!(content in input);
+                    self.compat = Some(content.parse()?);
+                }
+                "extension" => {
+                    let content;
+                    syn::parenthesized!(content in input);
+                    let types = Punctuated::<Type, Token![,]>::parse_terminated(&content)?;
+                    self.extension.extend(types);
+                }
+                "test_helpers" => self.test_helpers = true,
+                "bitset" => self.bitset = true,
+                "owned" => self.owned = true,
+                "expose_table" => self.expose_table = true,
+                "scoped_macros" => self.scoped_macros = true,
+                "strict" => self.strict = true,
+                "lifetime_accessors" => self.lifetime_accessors = true,
+                "unchecked_accessors" => self.unchecked_accessors = true,
+                "debug_json" => self.debug_json = true,
+                "unsafe_erased" => self.unsafe_erased = true,
+                "no_duplicates" => self.no_duplicates = true,
+                "static" => self.static_accessors = true,
+                "collection" => self.collection = true,
+                "named_accessors" => self.named_accessors = true,
+                "opaque" => self.opaque = true,
+                "self_test" => self.self_test = true,
+                "show_internals" => self.show_internals = true,
+                "copy_accessors" => self.copy_accessors = true,
+                "access_trait" => self.access_trait = true,
+                "with_accessors" => self.with_accessors = true,
+                "shared_view" => self.shared_view = true,
+                "variant_macro" => self.variant_macro = true,
+                "counter" => self.counter = true,
+                "dispatch_array" => self.dispatch_array = true,
+                "dispatch_eq" => self.dispatch_eq = true,
+                "dispatch_tuple" => self.dispatch_tuple = true,
+                "into_opt_result" => self.into_opt_result = true,
+                "error_impl" => self.error_impl = true,
+                "eq_by_variant" => self.eq_by_variant = true,
+                "mode" => {
+                    input.parse::<Token![=]>()?;
+                    let mode: Ident = input.parse()?;
+                    self.mode = match mode.to_string().as_str() {
+                        "combinations" => Mode::Combinations,
+                        "priority_chain" => Mode::PriorityChain,
+                        "auto" => Mode::Auto,
+                        _ => {
+                            return Err(Error::new(
+                                mode.span(),
+                                format!(
+                                    "unknown mode `{}`, expected `combinations`, `priority_chain` or `auto`",
+                                    mode
+                                ),
+                            ));
+                        }
+                    };
+                }
+                "value_generic" => {
+                    input.parse::<Token![=]>()?;
+                    self.value_generic = Some(input.parse()?);
+                }
+                "repr" => {
+                    input.parse::<Token![=]>()?;
+                    self.repr = Some(input.parse()?);
+                }
+                "wrapper" => {
+                    input.parse::<Token![=]>()?;
+                    self.wrapper = Some(input.parse()?);
+                }
+                "common" => {
+                    input.parse::<Token![=]>()?;
+                    self.common = Some(input.parse()?);
+                }
+                "marker_super" => {
+                    input.parse::<Token![=]>()?;
+                    self.marker_super = Some(input.parse()?);
+                }
+                "universal" => {
+                    input.parse::<Token![=]>()?;
+                    self.universal = Some(input.parse()?);
+                }
+                "primary" => {
+                    input.parse::<Token![=]>()?;
+                    self.primary = Some(input.parse()?);
+                }
+                "dump_to" => {
+                    input.parse::<Token![=]>()?;
+                    self.dump_to = Some(input.parse()?);
+                }
+                "reexport_at" => {
+                    input.parse::<Token![=]>()?;
+                    self.reexport_at = Some(if input.peek(Token![pub]) {
+                        input.parse()?
+                    } else {
+                        let path = input.call(Path::parse_mod_style)?;
+                        syn::parse_quote!(pub(in #path))
+                    });
+                }
+                "trait_ref" => {
+                    input.parse::<Token![=]>()?;
+                    let reused = input.parse::<Option<Token![use]>>()?.is_some();
+                    self.trait_ref = Some(TraitRefName {
+                        path: input.parse()?,
+                        reused,
+                    });
+                }
+                _ => {
+                    return Err(Error::new(
+                        key.span(),
+                        format!("unknown trait_mux option `{}`", key),
+                    ));
+                }
+            }
+
+            if input.is_empty() {
+                break;
+            }
+            input.parse::<Token![,]>()?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::parse_quote;
+
+    /// Parses the given attributes into options, panicking on failure.
+    fn options(attrs: Vec<Attribute>) -> Options {
+        Options::from_attrs(&attrs).unwrap()
+    }
+
+    #[test]
+    fn no_attributes() {
+        let options = options(vec![]);
+        assert!(options.implies.is_empty());
+        assert!(!options.test_helpers);
+        assert!(!options.bitset);
+        assert!(!options.owned);
+        assert!(!options.expose_table);
+        assert!(options.wrapper.is_none());
+        assert!(!options.scoped_macros);
+        assert!(options.common.is_none());
+        assert!(options.universal.is_none());
+        assert!(!options.strict);
+        assert!(options.repr.is_none());
+        assert!(options.extension.is_empty());
+        assert!(!options.lifetime_accessors);
+        assert!(!options.unchecked_accessors);
+        assert!(!options.debug_json);
+        assert!(!options.unsafe_erased);
+        assert!(!options.no_duplicates);
+        assert!(!options.static_accessors);
+        assert!(!options.collection);
+        assert!(!options.named_accessors);
+        assert!(options.dump_to.is_none());
+        assert!(options.compat.is_none());
+        assert!(!options.opaque);
+        assert!(!options.self_test);
+        assert!(!options.show_internals);
+        assert!(!options.copy_accessors);
+        assert!(!options.access_trait);
+        assert!(!options.with_accessors);
+        assert!(!options.shared_view);
+        assert!(options.value_generic.is_none());
+        assert!(!options.variant_macro);
+        assert!(!options.counter);
+        assert!(!options.dispatch_array);
+        assert!(!options.dispatch_eq);
+        assert!(!options.dispatch_tuple);
+        assert!(!options.into_opt_result);
+        assert!(!options.error_impl);
+        assert!(!options.eq_by_variant);
+        assert_eq!(options.mode, Mode::Combinations);
+        assert!(options.reexport_at.is_none());
+        assert!(options.primary.is_none());
+        assert!(options.trait_ref.is_none());
+    }
+
+    #[test]
+    fn flags() {
+        let options = options(vec![
+            parse_quote!(#[trait_mux(test_helpers, bitset, owned)]),
+            parse_quote!(#[trait_mux(expose_table, scoped_macros, strict)]),
+            parse_quote!(#[trait_mux(lifetime_accessors, unchecked_accessors, debug_json)]),
+            parse_quote!(#[trait_mux(unsafe_erased, no_duplicates, static)]),
+            parse_quote!(#[trait_mux(collection, named_accessors, opaque, self_test)]),
+            parse_quote!(#[trait_mux(show_internals, copy_accessors, access_trait)]),
+            parse_quote!(#[trait_mux(with_accessors, shared_view, variant_macro)]),
+            parse_quote!(#[trait_mux(counter, error_impl, dispatch_array, dispatch_eq)]),
+            parse_quote!(#[trait_mux(dispatch_tuple, into_opt_result)]),
+        ]);
+        assert!(options.test_helpers);
+        assert!(options.bitset);
+        assert!(options.owned);
+        assert!(options.expose_table);
+        assert!(options.scoped_macros);
+        assert!(options.strict);
+        assert!(options.lifetime_accessors);
+        assert!(options.unchecked_accessors);
+        assert!(options.debug_json);
+        assert!(options.unsafe_erased);
+        assert!(options.no_duplicates);
+        assert!(options.static_accessors);
+        assert!(options.collection);
+        assert!(options.named_accessors);
+        assert!(options.opaque);
+        assert!(options.self_test);
+        assert!(options.show_internals);
+        assert!(options.copy_accessors);
+        assert!(options.access_trait);
+        assert!(options.with_accessors);
+        assert!(options.shared_view);
+        assert!(options.variant_macro);
+        assert!(options.counter);
+        assert!(options.error_impl);
+        assert!(options.dispatch_array);
+        assert!(options.dispatch_eq);
+        assert!(options.dispatch_tuple);
+        assert!(options.into_opt_result);
+    }
+
+    #[test]
+    fn implies() {
+        let options = options(vec![
+            parse_quote!(#[trait_mux(implies(DisplayExt => Display, Ord => std::cmp::Eq))]),
+            parse_quote!(#[trait_mux(implies(A => B))]),
+        ]);
+
+        assert_eq!(options.implies.len(), 3);
+        assert!(options.implies[0].from.is_ident("DisplayExt"));
+        assert!(options.implies[0].to.is_ident("Display"));
+        assert_eq!(options.implies[1].to.segments.len(), 3);
+        assert!(options.implies[2].from.is_ident("A"));
+    }
+
+    #[test]
+    fn aggregate() {
+        let options = options(vec![
+            parse_quote!(#[trait_mux(aggregate(DebugDisplay => Debug, std::fmt::Display))]),
+            parse_quote!(#[trait_mux(implies(A => B))]),
+        ]);
+
+        assert_eq!(options.implies.len(), 3);
+        assert!(options.implies[0].from.is_ident("DebugDisplay"));
+        assert!(options.implies[0].to.is_ident("Debug"));
+        assert!(options.implies[1].from.is_ident("DebugDisplay"));
+        assert_eq!(options.implies[1].to.segments.len(), 3);
+        assert!(options.implies[2].from.is_ident("A"));
+
+        let attrs: Vec<Attribute> = vec![parse_quote!(#[trait_mux(aggregate(DebugDisplay))])];
+        assert!(Options::from_attrs(&attrs).is_err());
+    }
+
+    #[test]
+    fn wrapper() {
+        let options = options(vec![
+            parse_quote!(#[trait_mux(wrapper = guard::Guard, owned)]),
+        ]);
+        assert_eq!(options.wrapper.unwrap().segments.len(), 2);
+        assert!(options.owned);
+
+        let attrs: Vec<Attribute> = vec![parse_quote!(#[trait_mux(wrapper)])];
+        assert!(Options::from_attrs(&attrs).is_err());
+    }
+
+    #[test]
+    fn repr() {
+        let options = options(vec![parse_quote!(#[trait_mux(repr = u8)])]);
+        assert!(options.repr.unwrap() == "u8");
+    }
+
+    #[test]
+    fn value_generic() {
+        let options = options(vec![parse_quote!(#[trait_mux(value_generic = V)])]);
+        assert!(options.value_generic.unwrap() == "V");
+
+        let attrs: Vec<Attribute> = vec![parse_quote!(#[trait_mux(value_generic = a::B)])];
+        assert!(Options::from_attrs(&attrs).is_err());
+    }
+
+    #[test]
+    fn mode() {
+        let priority_chain = options(vec![parse_quote!(#[trait_mux(mode = priority_chain)])]);
+        assert_eq!(priority_chain.mode, Mode::PriorityChain);
+
+        let combinations = options(vec![parse_quote!(#[trait_mux(mode = combinations)])]);
+        assert_eq!(combinations.mode, Mode::Combinations);
+
+        let auto = options(vec![parse_quote!(#[trait_mux(mode = auto)])]);
+        assert_eq!(auto.mode, Mode::Auto);
+
+        let attrs: Vec<Attribute> = vec![parse_quote!(#[trait_mux(mode = first)])];
+        assert!(Options::from_attrs(&attrs).is_err());
+    }
+
+    #[test]
+    fn extension() {
+        let options = options(vec![
+            parse_quote!(#[trait_mux(extension(String, Vec<u8>))]),
+            parse_quote!(#[trait_mux(extension(std::path::PathBuf))]),
+        ]);
+        assert_eq!(options.extension.len(), 3);
+    }
+
+    #[test]
+    fn common() {
+        let options = options(vec![parse_quote!(#[trait_mux(common = base::Base)])]);
+        assert_eq!(options.common.unwrap().segments.len(), 2);
+    }
+
+    #[test]
+    fn marker_super() {
+        let options = options(vec![
+            parse_quote!(#[trait_mux(marker_super = marker::Marker)]),
+        ]);
+        assert_eq!(options.marker_super.unwrap().segments.len(), 2);
+    }
+
+    #[test]
+    fn universal() {
+        let options = options(vec![parse_quote!(#[trait_mux(universal = Universal)])]);
+        assert!(options.universal.unwrap().is_ident("Universal"));
+    }
+
+    #[test]
+    fn primary() {
+        let options = options(vec![parse_quote!(#[trait_mux(primary = std::fmt::Debug)])]);
+        assert_eq!(options.primary.unwrap().segments.len(), 3);
+
+        let attrs: Vec<Attribute> = vec![parse_quote!(#[trait_mux(primary)])];
+        assert!(Options::from_attrs(&attrs).is_err());
+    }
+
+    #[test]
+    fn dump_to() {
+        let options = options(vec![
+            parse_quote!(#[trait_mux(dump_to = "target/generated.rs")]),
+        ]);
+        assert_eq!(options.dump_to.unwrap().value(), "target/generated.rs");
+
+        let attrs: Vec<Attribute> = vec![parse_quote!(#[trait_mux(dump_to = generated)])];
+        assert!(Options::from_attrs(&attrs).is_err());
+    }
+
+    #[test]
+    fn reexport_at() {
+        let restricted = options(vec![parse_quote!(#[trait_mux(reexport_at = crate)])]);
+        match restricted.reexport_at {
+            Some(Visibility::Restricted(vis)) => {
+                assert!(vis.in_token.is_some());
+                assert!(vis.path.is_ident("crate"));
+            }
+            _ => panic!("expected a restricted visibility"),
+        }
+
+        let public = options(vec![parse_quote!(#[trait_mux(reexport_at = pub)])]);
+        assert!(matches!(public.reexport_at, Some(Visibility::Public(_))));
+
+        let attrs: Vec<Attribute> = vec![parse_quote!(#[trait_mux(reexport_at = "crate")])];
+        assert!(Options::from_attrs(&attrs).is_err());
+    }
+
+    #[test]
+    fn compat() {
+        let options = options(vec![parse_quote!(#[trait_mux(compat(other::Other))])]);
+        assert_eq!(options.compat.unwrap().segments.len(), 2);
+
+        let attrs: Vec<Attribute> = vec![parse_quote!(#[trait_mux(compat(A, B))])];
+        assert!(Options::from_attrs(&attrs).is_err());
+    }
+
+    #[test]
+    fn trait_ref() {
+        let trait_ref = options(vec![parse_quote!(#[trait_mux(trait_ref = MyTraitRef)])])
+            .trait_ref
+            .unwrap();
+        assert!(trait_ref.path.is_ident("MyTraitRef"));
+        assert!(!trait_ref.reused);
+
+        let trait_ref = options(vec![
+            parse_quote!(#[trait_mux(trait_ref = use refs::MyTraitRef)]),
+        ])
+        .trait_ref
+        .unwrap();
+        assert_eq!(trait_ref.path.segments.len(), 2);
+        assert!(trait_ref.reused);
+    }
+
+    #[test]
+    fn unknown_option() {
+        let attrs: Vec<Attribute> = vec![parse_quote!(#[trait_mux(unknown)])];
+        assert!(Options::from_attrs(&attrs).is_err());
+    }
+
+    #[test]
+    fn foreign_attribute() {
+        let attrs: Vec<Attribute> = vec![parse_quote!(#[repr(u8)])];
+        assert!(Options::from_attrs(&attrs).is_err());
+    }
+
+    #[test]
+    fn cfg_attr() {
+        let attrs: Vec<Attribute> = vec![
+            parse_quote!(#[cfg_attr(feature = "serde", derive(Serialize))]),
+            parse_quote!(#[trait_mux(strict)]),
+        ];
+        let options = Options::from_attrs(&attrs).unwrap();
+
+        assert_eq!(options.enum_attrs.len(), 1);
+        assert!(options.enum_attrs[0].path.is_ident("cfg_attr"));
+        assert!(options.strict);
+    }
+
+    #[test]
+    fn derive() {
+        let attrs: Vec<Attribute> = vec![
+            parse_quote!(#[derive(Clone, Copy)]),
+            parse_quote!(#[trait_mux(eq_by_variant)]),
+        ];
+        let options = Options::from_attrs(&attrs).unwrap();
+
+        assert_eq!(options.enum_attrs.len(), 1);
+        assert!(options.enum_attrs[0].path.is_ident("derive"));
+        assert!(options.eq_by_variant);
+    }
+}
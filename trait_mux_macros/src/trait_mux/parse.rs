@@ -1,29 +1,180 @@
 //! This module provides functionality to parse a named list of traits or paths from a `TokenStream`.
 //! It supports both simple trait names (e.g., `Display`) and full paths (e.g., `std::fmt::Display`).
-//! The parsed traits are stored as `Path` objects in the `Ast` struct, along with the name of the implementation.
+//! The parsed traits are stored as `TraitEntry` objects in the `Ast` struct, along with the name of the implementation.
+//! Each entry may be prefixed with `mut` to request mutable access to that trait (e.g., `mut std::io::Write`).
+//! Each entry may be followed by `as Name` to set the name used in generated identifiers (e.g., `io::Write as IoWrite`).
+//! The path of an entry may be followed by auto-trait bounds added to its trait objects (e.g., `Debug + Send`).
+//! Each entry may be preceded by doc comments, which document the generated accessors of that trait.
+//! Elided lifetime arguments of an entry are bound to the enum lifetime, e.g. `Named<'_>` becomes `Named<'t>`.
+//! Closure traits are written with their parenthesized arguments, e.g. `Fn(u32) -> u32`.
+//! Several traits may be combined into a single entry with parentheses, e.g. `(Debug + Display)`,
+//! detected and accessed as one trait generated by the enum, named after them (`DebugDisplay`).
+//! Optional `#[trait_mux(...)]` attributes may precede the name, and are parsed into `Options`.
+//! In types mode, the concrete types are listed before the traits, e.g. `SomeName types{String, i32} traits{Debug}`.
+//! The lists may be delimited by parentheses instead of braces, e.g. `SomeName(Display, Debug)`.
+//! Trait lists registered with `trait_mux_traits!(fmt_traits = {Debug, Display})` are parsed into
+//! a `TraitSet`, and used by name with `SomeName = @fmt_traits`, parsed into a `TraitSetUse`.
+//! A set may also be spliced into a trait list along with other entries, e.g.
+//! `SomeName{@fmt_traits, Clone}`, which is split into a `TraitSetSplice`.
 
-use proc_macro2::TokenStream;
 use proc_macro_error::abort;
-use syn::parse::{Parse, ParseStream};
+use proc_macro2::{Delimiter, Group, TokenStream, TokenTree};
+use syn::ext::IdentExt;
+use syn::parse::{Parse, ParseStream, Parser};
 use syn::punctuated::Punctuated;
-use syn::token::Comma;
-use syn::{parse2, Ident, Path, Result, Token};
+use syn::token::{Comma, Paren};
+use syn::{
+    Attribute, Error, GenericArgument, Ident, Lifetime, Path, PathArguments, Result, Token, Type,
+    parse2,
+};
+
+use crate::options::Options;
 
 /// Represents the parsed Abstract Syntax Tree (AST) for a named list of traits or paths.
 ///
 /// The syntax format is `SomeName{Display, std::fmt::Debug}`, where:
 /// - `SomeName` is the name of the implementation.
 /// - `{Display, std::fmt::Debug}` is a comma-separated list of traits or paths.
+///
+/// The name may be preceded by `#[trait_mux(...)]` attributes configuring the generated code.
+///
+/// In types mode, the syntax is `SomeName types{String, i32} traits{Display, std::fmt::Debug}`,
+/// where `{String, i32}` is a comma-separated list of the concrete types held by the enum.
+///
+/// The lists may also be delimited by parentheses, e.g. `SomeName(Display, std::fmt::Debug)`, as
+/// required in some attribute positions.
 pub struct Ast {
+    /// The options set through `#[trait_mux(...)]` attributes.
+    pub options: Options,
     /// The name of the implementation (e.g., `SomeName`).
     pub name: Ident,
-    /// A punctuated list of parsed paths representing traits or modules.
-    /// Each path can be a simple identifier (e.g., `Display`) or a full path (e.g., `std::fmt::Display`).
-    pub paths: Punctuated<Path, Comma>,
+    /// The concrete types listed in types mode, or None otherwise.
+    pub types: Option<Punctuated<Type, Comma>>,
+    /// A punctuated list of parsed trait entries.
+    pub traits: Punctuated<TraitEntry, Comma>,
+}
+
+/// A single entry of the trait list, e.g. `std::fmt::Debug`, `mut Writer`, `Debug + Send`,
+/// `fmt::Write as FmtWrite` or `(Debug + Display)`.
+pub struct TraitEntry {
+    /// The doc comments and `#[deprecated]` attributes preceding the entry, attached to the
+    /// generated accessors of the trait.
+    pub docs: Vec<Attribute>,
+    /// The `mut` token, present if the trait should be accessible mutably.
+    pub mutability: Option<Token![mut]>,
+    /// The path of the trait.
+    /// Can be a simple identifier (e.g., `Display`), a full path (e.g., `std::fmt::Display`) or a
+    /// closure trait (e.g., `Fn(u32) -> u32`).
+    /// Elided lifetime arguments are replaced by the enum lifetime, e.g. `Named<'_>` by
+    /// `Named<'t>`.
+    /// For a combination of traits, the trait generated for it, prefixed with the enum name, e.g.
+    /// `SomeNameDebugDisplay` for `(Debug + Display)`.
+    pub path: Path,
+    /// The auto-trait bounds following the path with `+`, added to the trait objects of this
+    /// trait only (e.g., `Send` in `Debug + Send`).
+    pub bounds: Vec<Path>,
+    /// The name set with `as`, used instead of the last path segment for the variant and accessor
+    /// names (e.g., `FmtWrite` in `fmt::Write as FmtWrite`).
+    /// A combination of traits is named after them if no name is set, e.g. `DebugDisplay`.
+    pub alias: Option<Ident>,
+    /// The traits combined by a parenthesized entry, e.g. `Debug` and `Display` in
+    /// `(Debug + Display)`, which the generated trait has as supertraits. Empty for other entries.
+    pub combined: Vec<Path>,
+}
+
+impl Parse for TraitEntry {
+    /// Parses a syntax like `std::fmt::Debug`, `mut Writer`, `Debug + Send`,
+    /// `fmt::Write as FmtWrite` or `(Debug + Display)`, optionally preceded by doc comments and
+    /// `#[deprecated]` attributes.
+    /// The path of a combination of traits is only its name, e.g. `DebugDisplay`, which is prefixed
+    /// with the enum name once it's known.
+    ///
+    /// # Arguments
+    /// * `input` - The input stream to parse.
+    ///
+    /// # Returns
+    /// * `Result<Self>` - The parsed `TraitEntry`.
+    ///
+    /// # Errors
+    /// Returns an error if the input is not an optionally `mut` prefixed path, optionally followed
+    /// by `+` separated bounds and by `as` and an identifier, or if it's preceded by an attribute other than a doc comment or
+    /// `#[deprecated]`. Parentheses must combine at least two traits.
+    fn parse(input: ParseStream) -> Result<Self> {
+        let docs = input.call(Attribute::parse_outer)?;
+        if let Some(attr) = docs
+            .iter()
+            .find(|attr| !attr.path.is_ident("doc") && !attr.path.is_ident("deprecated"))
+        {
+            return Err(Error::new_spanned(
+                attr,
+                "only doc comments and `#[deprecated]` are allowed on trait entries",
+            ));
+        }
+
+        let mutability = input.parse()?;
+
+        let mut combined = vec![];
+        let mut path: Path = if input.peek(Paren) {
+            let content;
+            let parens = syn::parenthesized!(content in input);
+            combined = Punctuated::<Path, Token![+]>::parse_separated_nonempty(&content)?
+                .into_iter()
+                .collect();
+            if !content.is_empty() || combined.len() < 2 {
+                return Err(Error::new(
+                    parens.span,
+                    "expected at least two traits combined with `+`, e.g. `(Debug + Display)`",
+                ));
+            }
+
+            let name: String = combined
+                .iter()
+                .filter_map(|path| path.segments.last())
+                .map(|segment| segment.ident.unraw().to_string())
+                .collect();
+            Ident::new(&name, parens.span).into()
+        } else {
+            input.parse()?
+        };
+
+        // Path parsing stops before parenthesized arguments, as in `Fn(u32) -> u32`.
+        if combined.is_empty() && input.peek(Paren) {
+            if let Some(segment) = path.segments.last_mut() {
+                if segment.arguments.is_empty() {
+                    segment.arguments = PathArguments::Parenthesized(input.parse()?);
+                }
+            }
+        }
+        bind_elided_lifetimes(&mut path);
+        combined.iter_mut().for_each(bind_elided_lifetimes);
+
+        let mut bounds = vec![];
+        while input.peek(Token![+]) {
+            input.parse::<Token![+]>()?;
+            bounds.push(input.parse()?);
+        }
+
+        let alias = if input.peek(Token![as]) {
+            input.parse::<Token![as]>()?;
+            Some(input.parse()?)
+        } else {
+            None
+        };
+
+        Ok(TraitEntry {
+            docs,
+            mutability,
+            path,
+            bounds,
+            alias,
+            combined,
+        })
+    }
 }
 
 impl Parse for Ast {
-    /// Parses a syntax like `SomeName{Display, std::fmt::Debug}`.
+    /// Parses a syntax like `SomeName{Display, std::fmt::Debug}` or
+    /// `SomeName(Display, std::fmt::Debug)`.
     ///
     /// # Arguments
     /// * `input` - The input stream to parse.
@@ -34,15 +185,159 @@ impl Parse for Ast {
     /// # Errors
     /// Returns an error if the input does not match the expected syntax.
     fn parse(input: ParseStream) -> Result<Self> {
+        let attrs = input.call(Attribute::parse_outer)?;
+        let options = Options::from_attrs(&attrs)?;
+
         let name = input.parse::<Ident>()?;
 
-        let content;
+        let types = if input.peek(Ident) {
+            expect_keyword(input, "types")?;
+            let types = parse_list::<Type>(input)?;
+            expect_keyword(input, "traits")?;
+
+            Some(types)
+        } else {
+            None
+        };
+
+        let mut traits = parse_list::<TraitEntry>(input)?;
+
+        // The generated traits of combinations are prefixed with the enum name, so they don't
+        // collide with other traits, and keep their unprefixed name for the generated identifiers.
+        for entry in traits.iter_mut().filter(|entry| !entry.combined.is_empty()) {
+            if let Some(ident) = entry.path.get_ident().cloned() {
+                let prefixed = format!("{}{}", name.unraw(), ident);
+                entry.path = Ident::new(&prefixed, ident.span()).into();
+                entry.alias.get_or_insert(ident);
+            }
+        }
+
+        Ok(Ast {
+            options,
+            name,
+            types,
+            traits,
+        })
+    }
+}
+
+/// A named trait list registered with `trait_mux_traits!`, e.g. `fmt_traits = {Debug, Display}`.
+pub struct TraitSet {
+    /// The name of the set, given to the generated `macro_rules!` macro.
+    pub name: Ident,
+    /// The delimited trait list, kept as written so it can be passed on to `trait_mux!`.
+    pub traits: Group,
+}
+
+impl Parse for TraitSet {
+    /// Parses a syntax like `fmt_traits = {Display, std::fmt::Debug}`.
+    ///
+    /// # Errors
+    /// Returns an error if the input does not match the expected syntax, or if the list isn't a
+    /// valid trait list.
+    fn parse(input: ParseStream) -> Result<Self> {
+        let name = input.parse::<Ident>()?;
+        input.parse::<Token![=]>()?;
+
+        // The entries are only parsed to report errors where the set is registered.
+        let traits = input.fork();
+        parse_list::<TraitEntry>(input)?;
+
+        Ok(TraitSet {
+            name,
+            traits: traits.parse()?,
+        })
+    }
+}
+
+/// An enum listing the traits of a registered set, e.g. `#[trait_mux(owned)] SomeName = @fmt_traits`.
+pub struct TraitSetUse {
+    /// The `#[trait_mux(...)]` attributes preceding the name, passed on along with it.
+    pub attrs: Vec<Attribute>,
+    /// The name of the implementation (e.g., `SomeName`).
+    pub name: Ident,
+    /// The name of the registered set (e.g., `fmt_traits`).
+    pub set: Ident,
+}
+
+impl Parse for TraitSetUse {
+    /// Parses a syntax like `SomeName = @fmt_traits`.
+    ///
+    /// # Errors
+    /// Returns an error if the input does not match the expected syntax.
+    fn parse(input: ParseStream) -> Result<Self> {
+        let attrs = input.call(Attribute::parse_outer)?;
+        let name = input.parse::<Ident>()?;
+        input.parse::<Token![=]>()?;
+        input.parse::<Token![@]>()?;
+        let set = input.parse::<Ident>()?;
+
+        Ok(TraitSetUse { attrs, name, set })
+    }
+}
+
+/// A registered trait set spliced into a trait list, e.g. `#[trait_mux(owned)] SomeName{@fmt_traits, Clone}`.
+pub struct TraitSetSplice {
+    /// The tokens preceding the trait list, e.g. `#[trait_mux(owned)] SomeName`, passed on as is.
+    pub head: TokenStream,
+    /// The name of the spliced set (e.g., `fmt_traits`).
+    pub set: Ident,
+    /// The other entries of the list, with the set's entry removed (e.g., `Clone`).
+    pub rest: TokenStream,
+}
+
+/// Replaces the elided lifetime arguments of a trait path, e.g. `'_` in `Named<'_>`, by the enum
+/// lifetime `'t`, as an elided lifetime can't appear in the generated items.
+///
+/// # Arguments
+/// * `path` - The path of the trait
+fn bind_elided_lifetimes(path: &mut Path) {
+    for segment in &mut path.segments {
+        let PathArguments::AngleBracketed(arguments) = &mut segment.arguments else {
+            continue;
+        };
+
+        for argument in &mut arguments.args {
+            if let GenericArgument::Lifetime(lifetime) = argument {
+                if lifetime.ident == "_" {
+                    *lifetime = Lifetime::new("'t", lifetime.apostrophe);
+                }
+            }
+        }
+    }
+}
+
+/// Parses a comma-separated list delimited by braces, e.g. `{Display, Debug}`, or by parentheses,
+/// e.g. `(Display, Debug)`.
+///
+/// # Errors
+/// Returns an error if the next token isn't a braced or parenthesized group, or if its contents
+/// aren't a comma-separated list of `T`.
+fn parse_list<T: Parse>(input: ParseStream) -> Result<Punctuated<T, Comma>> {
+    let content;
+    if input.peek(Paren) {
+        syn::parenthesized!(content in input);
+    } else {
         syn::braced!(content in input);
+    }
 
-        let paths = Punctuated::<Path, Token![,]>::parse_terminated(&content)?;
+    Punctuated::parse_terminated(&content)
+}
 
-        Ok(Ast { name, paths })
+/// Parses an identifier that must be the given keyword, e.g. `types`.
+///
+/// # Errors
+/// Returns an error if the next token isn't the keyword.
+fn expect_keyword(input: ParseStream, keyword: &str) -> Result<()> {
+    let ident = input.parse::<Ident>()?;
+    if ident != keyword {
+        return Err(Error::new(
+            ident.span(),
+            format!("expected `{}`, found `{}`", keyword, ident),
+        ));
     }
+
+    Ok(())
 }
 
 /// Parses a `TokenStream` into an `Ast` containing a named list of paths.
@@ -66,6 +361,102 @@ pub fn parse(ts: TokenStream) -> Ast {
     }
 }
 
+/// Parses a `TokenStream` into a `TraitSet`, registered with `trait_mux_traits!`.
+///
+/// # Arguments
+/// * `ts` - The `TokenStream` to parse.
+///
+/// # Returns
+/// * `TraitSet` - The parsed trait set.
+///
+/// # Panics
+/// Panics if the input cannot be parsed, using the `abort!` macro to provide an error message.
+pub fn parse_trait_set(ts: TokenStream) -> TraitSet {
+    match parse2::<TraitSet>(ts) {
+        Ok(set) => set,
+        Err(e) => {
+            abort!(e.span(), e)
+        }
+    }
+}
+
+/// Parses a `TokenStream` into a `TraitSetUse`, if the name is followed by `=` instead of the trait
+/// list, e.g. `SomeName = @fmt_traits`.
+///
+/// # Arguments
+/// * `ts` - The `TokenStream` to parse.
+///
+/// # Returns
+/// * `Option<TraitSetUse>` - The parsed use of a trait set, or None if the input lists the traits.
+///
+/// # Panics
+/// Panics if the name is followed by `=` but the rest cannot be parsed, using the `abort!` macro
+/// to provide an error message.
+pub fn parse_trait_set_use(ts: TokenStream) -> Option<TraitSetUse> {
+    let uses_set = |input: ParseStream| -> Result<bool> {
+        input.call(Attribute::parse_outer)?;
+        input.parse::<Ident>()?;
+        let uses_set = input.peek(Token![=]);
+        input.parse::<TokenStream>()?;
+
+        Ok(uses_set)
+    };
+    if !uses_set.parse2(ts.clone()).unwrap_or(false) {
+        return None;
+    }
+
+    match parse2::<TraitSetUse>(ts) {
+        Ok(set_use) => Some(set_use),
+        Err(e) => {
+            abort!(e.span(), e)
+        }
+    }
+}
+
+/// Splits a `TokenStream` into a `TraitSetSplice`, if its trait list contains an entry starting
+/// with `@`, e.g. `SomeName{@fmt_traits, Clone}`. Only the first such entry is split off, the set's
+/// macro passes the list back to `trait_mux!`, which splits off the next one.
+///
+/// # Arguments
+/// * `ts` - The `TokenStream` to parse.
+///
+/// # Returns
+/// * `Option<TraitSetSplice>` - The split list, or None if no set is spliced into it.
+pub fn parse_trait_set_splice(ts: TokenStream) -> Option<TraitSetSplice> {
+    let mut head: Vec<_> = ts.into_iter().collect();
+    let Some(TokenTree::Group(list)) = head.pop() else {
+        return None;
+    };
+    if !matches!(list.delimiter(), Delimiter::Brace | Delimiter::Parenthesis) {
+        return None;
+    }
+
+    let mut entries: Vec<_> = list.stream().into_iter().collect();
+    let is_punct = |token: Option<&TokenTree>, c| matches!(token, Some(TokenTree::Punct(p)) if p.as_char() == c);
+
+    // The `@` must start an entry, and be followed by the name of the set.
+    let at = (0..entries.len()).find(|&i| {
+        is_punct(entries.get(i), '@')
+            && (i == 0 || is_punct(entries.get(i - 1), ','))
+            && matches!(entries.get(i + 1), Some(TokenTree::Ident(_)))
+    })?;
+    let Some(TokenTree::Ident(set)) = entries.get(at + 1).cloned() else {
+        return None;
+    };
+    let end = if is_punct(entries.get(at + 2), ',') {
+        at + 3
+    } else {
+        at + 2
+    };
+    entries.drain(at..end);
+
+    Some(TraitSetSplice {
+        head: head.into_iter().collect(),
+        set,
+        rest: entries.into_iter().collect(),
+    })
+}
+
 #[cfg(test)]
 mod tests {
     //! Unit tests for the parsing functionality.
@@ -84,16 +475,35 @@ mod tests {
         let ast = parse(quote!(SomeName{Display, std::fmt::Debug}));
 
         assert_eq!(ast.name.to_string(), "SomeName");
-        assert_eq!(ast.paths.len(), 2);
-        assert_eq!(ast.paths[0].get_ident().unwrap().to_string(), "Display");
+        assert_eq!(ast.traits.len(), 2);
+        assert_eq!(
+            ast.traits[0].path.get_ident().unwrap().to_string(),
+            "Display"
+        );
 
-        let debug = &ast.paths[1].segments;
+        let debug = &ast.traits[1].path.segments;
         assert_eq!(debug.len(), 3);
         assert_eq!(debug[0].ident.to_string(), "std");
         assert_eq!(debug[1].ident.to_string(), "fmt");
         assert_eq!(debug[2].ident.to_string(), "Debug");
     }
 
+    /// Tests parsing with the traits delimited by parentheses: Name(traits...).
+    ///
+    /// Verifies that the parser extracts the same name and paths as with braces.
+    #[test]
+    fn valid_named_syntax_parens() {
+        let ast = parse(quote!(SomeName(Display, std::fmt::Debug)));
+
+        assert_eq!(ast.name.to_string(), "SomeName");
+        assert_eq!(ast.traits.len(), 2);
+        assert_eq!(
+            ast.traits[0].path.get_ident().unwrap().to_string(),
+            "Display"
+        );
+        assert_eq!(ast.traits[1].path.segments.len(), 3);
+    }
+
     /// Tests parsing with mixed path formats.
     ///
     /// Verifies that the parser handles a mix of full paths and simple trait names.
@@ -102,25 +512,243 @@ mod tests {
         let ast = parse(quote!(MyImpl{std::fmt::Display, ::fmt::Debug, Clone}));
 
         assert_eq!(ast.name.to_string(), "MyImpl");
-        assert_eq!(ast.paths.len(), 3);
+        assert_eq!(ast.traits.len(), 3);
 
         // Check the segments of the path for the first trait
-        let display = &ast.paths[0].segments;
+        let display = &ast.traits[0].path.segments;
         assert_eq!(display.len(), 3);
         assert_eq!(display[0].ident.to_string(), "std");
         assert_eq!(display[1].ident.to_string(), "fmt");
         assert_eq!(display[2].ident.to_string(), "Display");
 
-        let debug = &ast.paths[1].segments;
+        let debug = &ast.traits[1].path.segments;
         assert_eq!(debug.len(), 2);
         assert_eq!(debug[0].ident.to_string(), "fmt");
         assert_eq!(debug[1].ident.to_string(), "Debug");
 
-        let clone = &ast.paths[2].segments;
+        let clone = &ast.traits[2].path.segments;
         assert_eq!(clone.len(), 1);
         assert_eq!(clone[0].ident.to_string(), "Clone");
     }
 
+    /// Tests parsing entries prefixed with `mut`.
+    ///
+    /// Verifies that the mutability is recorded per entry.
+    #[test]
+    fn valid_syntax_mut_entries() {
+        let ast = parse(quote!(MutImpl{mut std::io::Write, Debug}));
+
+        assert_eq!(ast.traits.len(), 2);
+        assert!(ast.traits[0].mutability.is_some());
+        assert_eq!(ast.traits[0].path.segments.len(), 3);
+        assert!(ast.traits[1].mutability.is_none());
+        assert!(ast.traits[1].path.is_ident("Debug"));
+    }
+
+    /// Tests parsing entries renamed with `as`.
+    ///
+    /// Verifies that the alias is recorded, and that the path is kept intact.
+    #[test]
+    fn valid_syntax_as_alias() {
+        let ast = parse(quote!(Aliased{mut very::long::path::Trait as ShortName, Debug}));
+
+        assert_eq!(ast.traits.len(), 2);
+        assert!(ast.traits[0].mutability.is_some());
+        assert_eq!(ast.traits[0].path.segments.len(), 4);
+        assert_eq!(
+            ast.traits[0].alias.as_ref().unwrap().to_string(),
+            "ShortName"
+        );
+        assert!(ast.traits[1].alias.is_none());
+    }
+
+    /// Tests parsing entries with auto-trait bounds.
+    ///
+    /// Verifies that the bounds are recorded per entry, apart from the path and the alias.
+    #[test]
+    fn valid_syntax_bounds() {
+        let ast =
+            parse(quote!(Bounded{Debug + Send + Sync, mut Fn(u32) -> u32 + Send as Call, Display}));
+
+        assert_eq!(ast.traits.len(), 3);
+        assert!(ast.traits[0].path.is_ident("Debug"));
+        let bounds: Vec<_> = ast.traits[0]
+            .bounds
+            .iter()
+            .map(|b| b.get_ident().unwrap().to_string())
+            .collect();
+        assert_eq!(bounds, ["Send", "Sync"]);
+
+        assert!(ast.traits[1].mutability.is_some());
+        assert!(matches!(
+            ast.traits[1].path.segments[0].arguments,
+            PathArguments::Parenthesized(_)
+        ));
+        assert!(ast.traits[1].bounds[0].is_ident("Send"));
+        assert_eq!(ast.traits[1].alias.as_ref().unwrap().to_string(), "Call");
+
+        assert!(ast.traits[2].bounds.is_empty());
+    }
+
+    /// Tests parsing a `+` without a bound.
+    ///
+    /// Verifies that the parser fails when the bound is missing.
+    #[test]
+    #[should_panic]
+    fn invalid_bound_missing() {
+        parse(quote!(Bounded{Debug +}));
+    }
+
+    /// Tests parsing an `as` without an alias.
+    ///
+    /// Verifies that the parser fails when the alias is missing.
+    #[test]
+    #[should_panic]
+    fn invalid_as_without_alias() {
+        parse(quote!(Aliased{std::fmt::Write as}));
+    }
+
+    /// Tests parsing parenthesized combinations of traits.
+    ///
+    /// Verifies that each is a single entry named after the combined traits, whose path is the
+    /// generated trait prefixed with the enum name.
+    #[test]
+    fn valid_syntax_combined() {
+        let ast =
+            parse(quote!(D{(Debug + std::fmt::Display), mut (Read + Write) + Send as Io, Clone}));
+
+        assert_eq!(ast.traits.len(), 3);
+        assert!(ast.traits[0].path.is_ident("DDebugDisplay"));
+        assert_eq!(
+            ast.traits[0].alias.as_ref().unwrap().to_string(),
+            "DebugDisplay"
+        );
+        let combined = &ast.traits[0].combined;
+        assert!(combined[0].is_ident("Debug"));
+        assert_eq!(combined[1].segments.len(), 3);
+
+        assert!(ast.traits[1].mutability.is_some());
+        assert!(ast.traits[1].path.is_ident("DReadWrite"));
+        assert_eq!(ast.traits[1].alias.as_ref().unwrap().to_string(), "Io");
+        assert!(ast.traits[1].bounds[0].is_ident("Send"));
+
+        assert!(ast.traits[2].path.is_ident("Clone"));
+        assert!(ast.traits[2].combined.is_empty());
+    }
+
+    /// Tests parsing parentheses around a single trait.
+    ///
+    /// Verifies that the parser fails when less than two traits are combined.
+    #[test]
+    #[should_panic]
+    fn invalid_combined_single() {
+        parse(quote!(D{(Debug)}));
+    }
+
+    /// Tests parsing traits with elided lifetime arguments.
+    ///
+    /// Verifies that they're replaced by the enum lifetime, and other lifetimes are kept.
+    #[test]
+    fn valid_syntax_elided_lifetimes() {
+        let ast = parse(quote!(Elided{Named<'_>, Both<'_, 'static>, Debug}));
+
+        let named = &ast.traits[0].path;
+        assert_eq!(quote!(#named).to_string(), quote!(Named<'t>).to_string());
+        let both = &ast.traits[1].path;
+        assert_eq!(
+            quote!(#both).to_string(),
+            quote!(Both<'t, 'static>).to_string()
+        );
+        assert!(ast.traits[2].path.is_ident("Debug"));
+    }
+
+    /// Tests parsing traits with generic arguments and associated-type bindings.
+    ///
+    /// Verifies that the arguments are kept in the path.
+    #[test]
+    fn valid_syntax_associated_types() {
+        let ast = parse(quote!(Assoc{mut Iterator<Item = u8>, AsRef<str>}));
+
+        assert_eq!(ast.traits.len(), 2);
+        let iterator = &ast.traits[0].path;
+        assert_eq!(
+            quote!(#iterator).to_string(),
+            quote!(Iterator<Item = u8>).to_string()
+        );
+        assert!(!ast.traits[1].path.segments[0].arguments.is_empty());
+    }
+
+    /// Tests parsing closure traits with parenthesized arguments and a return type.
+    ///
+    /// Verifies that the arguments are kept in the path, along with an alias.
+    #[test]
+    fn valid_syntax_fn_traits() {
+        let ast = parse(quote!(Closures{Fn(u32) -> u32, mut FnMut(&str) as Callback, Debug}));
+
+        assert_eq!(ast.traits.len(), 3);
+        let function = &ast.traits[0].path;
+        assert_eq!(
+            quote!(#function).to_string(),
+            quote!(Fn(u32) -> u32).to_string()
+        );
+        let callback = &ast.traits[1];
+        let path = &callback.path;
+        assert_eq!(quote!(#path).to_string(), quote!(FnMut(&str)).to_string());
+        assert!(callback.mutability.is_some());
+        assert_eq!(callback.alias.as_ref().unwrap(), "Callback");
+        assert!(ast.traits[2].path.is_ident("Debug"));
+    }
+
+    /// Tests parsing entries preceded by doc comments.
+    ///
+    /// Verifies that the doc comments are recorded per entry.
+    #[test]
+    fn valid_syntax_doc_comments() {
+        let ast = parse(quote!(Documented {
+            /// The debug one.
+            /// Spanning two lines.
+            Debug,
+            Display,
+            /// The writer.
+            mut std::io::Write
+        }));
+
+        assert_eq!(ast.traits.len(), 3);
+        assert_eq!(ast.traits[0].docs.len(), 2);
+        assert!(ast.traits[1].docs.is_empty());
+        assert_eq!(ast.traits[2].docs.len(), 1);
+        assert!(ast.traits[2].mutability.is_some());
+    }
+
+    /// Tests parsing an entry preceded by a `#[deprecated]` attribute.
+    ///
+    /// Verifies that the attribute is recorded along with the doc comments.
+    #[test]
+    fn valid_syntax_deprecated() {
+        let ast = parse(quote!(Deprecated {
+            /// The old one.
+            #[deprecated(note = "use `Debug`")]
+            OldTrait,
+            Debug
+        }));
+
+        assert_eq!(ast.traits[0].docs.len(), 2);
+        assert!(ast.traits[0].docs[1].path.is_ident("deprecated"));
+        assert!(ast.traits[1].docs.is_empty());
+    }
+
+    /// Tests parsing an entry preceded by an attribute other than a doc comment.
+    ///
+    /// Verifies that the parser fails on the attribute.
+    #[test]
+    #[should_panic]
+    fn invalid_attribute_on_entry() {
+        parse(quote!(Attributed {
+            #[cfg(test)]
+            Debug
+        }));
+    }
+
     /// Tests parsing an empty list of traits with a name.
     ///
     /// Verifies that the parser correctly handles an empty list of traits.
@@ -128,7 +756,82 @@ mod tests {
     fn empty_named_trait_list() {
         let ast = parse(quote!(EmptyImpl {}));
         assert_eq!(ast.name.to_string(), "EmptyImpl");
-        assert_eq!(ast.paths.len(), 0);
+        assert_eq!(ast.traits.len(), 0);
+    }
+
+    /// Tests parsing with `#[trait_mux(...)]` options preceding the name.
+    ///
+    /// Verifies that the options are parsed and the rest of the syntax is unaffected.
+    #[test]
+    fn valid_syntax_with_options() {
+        let ast = parse(quote!(
+            #[trait_mux(implies(DisplayExt => Display))]
+            Opts {
+                Display,
+                DisplayExt
+            }
+        ));
+
+        assert_eq!(ast.name.to_string(), "Opts");
+        assert_eq!(ast.traits.len(), 2);
+        assert_eq!(ast.options.implies.len(), 1);
+    }
+
+    /// Tests parsing types mode, listing concrete types before the traits.
+    ///
+    /// Verifies that both lists are parsed.
+    #[test]
+    fn valid_syntax_types() {
+        let ast = parse(quote!(Known types{String, i32, Vec<u8>} traits{Debug, Display}));
+
+        assert_eq!(ast.name.to_string(), "Known");
+        assert_eq!(ast.types.unwrap().len(), 3);
+        assert_eq!(ast.traits.len(), 2);
+
+        assert!(parse(quote!(Unknown { Debug })).types.is_none());
+    }
+
+    /// Tests parsing types mode with the lists delimited by parentheses.
+    ///
+    /// Verifies that both lists are parsed, including with mixed delimiters.
+    #[test]
+    fn valid_syntax_types_parens() {
+        let ast = parse(quote!(Known types(String, i32) traits(Debug, Display)));
+
+        assert_eq!(ast.types.unwrap().len(), 2);
+        assert_eq!(ast.traits.len(), 2);
+
+        let ast = parse(quote!(Known types(String) traits{Debug}));
+        assert_eq!(ast.types.unwrap().len(), 1);
+        assert_eq!(ast.traits.len(), 1);
+    }
+
+    /// Tests parsing an empty list of traits delimited by parentheses.
+    ///
+    /// Verifies that the parser correctly handles an empty list of traits.
+    #[test]
+    fn empty_named_trait_list_parens() {
+        let ast = parse(quote!(EmptyImpl()));
+        assert_eq!(ast.name.to_string(), "EmptyImpl");
+        assert_eq!(ast.traits.len(), 0);
+    }
+
+    /// Tests parsing invalid input delimited by brackets.
+    ///
+    /// Verifies that the parser fails on delimiters other than braces and parentheses.
+    #[test]
+    #[should_panic]
+    fn invalid_trait_input_brackets() {
+        parse(quote!(Bracketed[Display, Debug]));
+    }
+
+    /// Tests parsing types mode without the `traits` keyword.
+    ///
+    /// Verifies that the parser fails on the missing keyword.
+    #[test]
+    #[should_panic]
+    fn invalid_types_without_traits_keyword() {
+        parse(quote!(Known types{String} tratis{Debug}));
     }
 
     /// Tests parsing invalid input where a number is used instead of a valid path.
@@ -159,4 +862,85 @@ mod tests {
     fn invalid_empty_input() {
         parse(quote!());
     }
+
+    /// Tests parsing a trait set registered with `trait_mux_traits!`.
+    ///
+    /// Verifies that the name and the trait list, kept as written, are extracted.
+    #[test]
+    fn valid_trait_set() {
+        let set = parse_trait_set(quote!(fmt_traits = {Debug, mut std::io::Write as IoWrite}));
+
+        assert_eq!(set.name.to_string(), "fmt_traits");
+        assert_eq!(
+            set.traits.to_string(),
+            quote!({Debug, mut std::io::Write as IoWrite}).to_string()
+        );
+    }
+
+    /// Tests parsing a trait set whose list contains an invalid entry.
+    ///
+    /// Verifies that the parser fails where the set is registered.
+    #[test]
+    #[should_panic]
+    fn invalid_trait_set_entry() {
+        parse_trait_set(quote!(fmt_traits = {Debug, 123}));
+    }
+
+    /// Tests parsing the use of a trait set, e.g. `SomeName = @fmt_traits`.
+    ///
+    /// Verifies that the attributes, the name and the set are extracted, and that inputs listing
+    /// the traits aren't taken for a use of a set.
+    #[test]
+    fn valid_trait_set_use() {
+        let set_use =
+            parse_trait_set_use(quote!(#[trait_mux(owned)] SomeName = @fmt_traits)).unwrap();
+
+        assert_eq!(set_use.attrs.len(), 1);
+        assert_eq!(set_use.name.to_string(), "SomeName");
+        assert_eq!(set_use.set.to_string(), "fmt_traits");
+
+        assert!(parse_trait_set_use(quote!(SomeName { Debug, Display })).is_none());
+        assert!(parse_trait_set_use(quote!(Known types{String} traits{Debug})).is_none());
+    }
+
+    /// Tests splitting a trait set spliced into a trait list, e.g. `SomeName{@fmt_traits, Clone}`.
+    ///
+    /// Verifies that the tokens before the list are kept, and that the set's entry is removed
+    /// along with its comma, wherever it's placed.
+    #[test]
+    fn valid_trait_set_splice() {
+        let splice =
+            parse_trait_set_splice(quote!(#[trait_mux(owned)] SomeName{@fmt_traits, Clone}))
+                .unwrap();
+        assert_eq!(
+            splice.head.to_string(),
+            quote!(
+                #[trait_mux(owned)]
+                SomeName
+            )
+            .to_string()
+        );
+        assert_eq!(splice.set.to_string(), "fmt_traits");
+        assert_eq!(splice.rest.to_string(), quote!(Clone).to_string());
+
+        let splice =
+            parse_trait_set_splice(quote!(Known types{u8} traits(Clone, @fmt_traits))).unwrap();
+        assert_eq!(
+            splice.head.to_string(),
+            quote!(Known types{u8} traits).to_string()
+        );
+        assert_eq!(splice.rest.to_string(), quote!(Clone,).to_string());
+
+        assert!(parse_trait_set_splice(quote!(SomeName { Debug, Display })).is_none());
+        assert!(parse_trait_set_splice(quote!(SomeName = @fmt_traits)).is_none());
+    }
+
+    /// Tests parsing the use of a trait set without the `@` before the set's name.
+    ///
+    /// Verifies that the parser fails instead of parsing the input as a trait list.
+    #[test]
+    #[should_panic]
+    fn invalid_trait_set_use_missing_at() {
+        parse_trait_set_use(quote!(SomeName = fmt_traits));
+    }
 }